@@ -19,6 +19,18 @@ pub struct Config {
     pub osc: OscConfig,
     pub frame_recorder: FrameRecorderConfig,
     pub speed: SpeedConfig,
+    #[serde(default)]
+    pub spectator: SpectatorConfig,
+    #[serde(default)]
+    pub input: InputConfig,
+    #[serde(default)]
+    pub replay: ReplayConfig,
+    #[serde(default)]
+    pub session: SessionConfig,
+    #[serde(default)]
+    pub score_format: ScoreFormat,
+    #[serde(default)]
+    pub boards: Vec<BoardEntry>,
 }
 
 impl Config {
@@ -52,6 +64,32 @@ impl Config {
         Ok(toml::from_str(&content)?)
     }
 
+    /************************* Validation ********************/
+
+    // Board ids must be unique and every board centered on the render texture
+    pub fn validate_boards(&self) -> Result<(), String> {
+        let half_width = self.rendering.texture_width as f32 / 2.0;
+        let half_height = self.rendering.texture_height as f32 / 2.0;
+
+        for (i, entry) in self.boards.iter().enumerate() {
+            if self.boards[..i].iter().any(|other| other.id == entry.id) {
+                return Err(format!("duplicate board id \"{}\"", entry.id));
+            }
+            if entry.x.abs() > half_width || entry.y.abs() > half_height {
+                return Err(format!(
+                    "board \"{}\" at ({}, {}) is outside the {}x{} texture",
+                    entry.id,
+                    entry.x,
+                    entry.y,
+                    self.rendering.texture_width,
+                    self.rendering.texture_height
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     /************************* Resolving paths to the types needed in app ********************/
 
     /*
@@ -103,3 +141,139 @@ impl Config {
         path.to_string_lossy().into_owned() // Convert PathBuf to String safely
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE_CONFIG: &str = r#"
+[board]
+width = 10
+height = 20
+
+[paths]
+output_directory = "frames"
+
+[rendering]
+texture_width = 3800
+texture_height = 1080
+texture_samples = 1
+arc_resolution = 25
+
+[window]
+width = 1900
+height = 540
+
+[osc]
+rx_port = 8000
+
+[frame_recorder]
+frame_limit = 0
+fps = 30
+
+[speed]
+bpm = 120
+"#;
+
+    fn parse_with_boards(boards: &str) -> Config {
+        toml::from_str(&format!("{}\n{}", BASE_CONFIG, boards)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_board_entries() {
+        let config = parse_with_boards(
+            r#"
+[[boards]]
+id = "left"
+x = -600.0
+y = 0.0
+
+[[boards]]
+id = "middle"
+x = 0.0
+y = 50.0
+cell_size = 20.0
+
+[[boards]]
+id = "right"
+x = 600.0
+y = -50.0
+"#,
+        );
+
+        let ids: Vec<&str> = config.boards.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["left", "middle", "right"]);
+        let locations: Vec<(f32, f32)> = config.boards.iter().map(|b| (b.x, b.y)).collect();
+        assert_eq!(locations, vec![(-600.0, 0.0), (0.0, 50.0), (600.0, -50.0)]);
+        assert!(config.validate_boards().is_ok());
+
+        // Overrides apply per board, the rest comes from [board]
+        let middle = config.boards[1].board_config(&config.board);
+        assert_eq!(middle.cell_size, CellSize::Pixels(20.0));
+        assert_eq!(middle.width, 10);
+    }
+
+    #[test]
+    fn test_duplicate_board_id_is_rejected() {
+        let config = parse_with_boards(
+            r#"
+[[boards]]
+id = "left"
+x = -600.0
+y = 0.0
+
+[[boards]]
+id = "left"
+x = 600.0
+y = 0.0
+"#,
+        );
+
+        assert_eq!(
+            config.validate_boards(),
+            Err("duplicate board id \"left\"".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_off_texture_board_is_rejected() {
+        // x past the 3800 wide texture's right edge
+        let config = parse_with_boards(
+            r#"
+[[boards]]
+id = "far"
+x = 1901.0
+y = 0.0
+"#,
+        );
+        assert_eq!(
+            config.validate_boards(),
+            Err("board \"far\" at (1901, 0) is outside the 3800x1080 texture".to_owned())
+        );
+
+        // y below the 1080 high texture's bottom edge
+        let config = parse_with_boards(
+            r#"
+[[boards]]
+id = "low"
+x = 0.0
+y = -541.0
+"#,
+        );
+        assert_eq!(
+            config.validate_boards(),
+            Err("board \"low\" at (0, -541) is outside the 3800x1080 texture".to_owned())
+        );
+
+        // On the edge is still on the texture
+        let config = parse_with_boards(
+            r#"
+[[boards]]
+id = "edge"
+x = 1900.0
+y = 540.0
+"#,
+        );
+        assert!(config.validate_boards().is_ok());
+    }
+}
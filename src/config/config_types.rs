@@ -2,15 +2,492 @@
 //
 // Config types for the app
 
+use crate::models::PieceType;
 use serde::Deserialize;
+use std::path::PathBuf;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct BoardConfig {
     pub width: usize,
     pub height: usize,
-    pub cell_size: f32,
+    pub cell_size: CellSize,
+    // "auto" cell_size: share of the screen the board may fill, and the size limits
+    pub fit_fraction: f32,
+    pub cell_size_min: f32,
+    pub cell_size_max: f32,
     pub gravity_interval: f32,
     pub lock_delay: f32,
+    // extra seconds before locking when the piece was moved sideways this late
+    pub lock_grace: f32,
+    // seconds after a soft drop during which a hard drop is ignored; 0.0 = off
+    pub hard_drop_lockout: f32,
+    // cells per frame; 0.0 uses the gravity_interval timer instead
+    pub gravity_per_frame: f32,
+    // false: sandbox mode, pieces only move by input and lock where they rest
+    pub gravity_enabled: bool,
+    // handicap: scales this board's fall speed, 2.0 falls twice as fast
+    pub gravity_multiplier: f32,
+    pub entry_delay: f32,
+    // a rotate pressed during entry_delay spawns the piece rotated
+    pub irs: bool,
+    // a hold pressed during entry_delay holds the piece as it spawns
+    pub ihs: bool,
+    pub spawn_telegraph: bool,
+    pub color_from_next: bool,
+    // lines cleared per level; 0 turns levels off
+    pub level_lines: usize,
+    // degrees the board's hue rotates each level
+    pub level_hue_step: f32,
+    // seconds the color change takes on level up
+    pub level_fade: f32,
+    // how lock_delay shrinks as the level rises
+    pub lock_delay_curve: LockDelayCurve,
+    // chain meter: chain_per_line added per line cleared, up to chain_max,
+    // draining chain_decay per second; 0 chain_max = off
+    pub chain_per_line: f32,
+    pub chain_max: f32,
+    pub chain_decay: f32,
+    // meter levels that fire ChainMeterChanged as the meter crosses them
+    pub chain_thresholds: Vec<f32>,
+    // fixed piece sequence seed; random when unset
+    pub seed: Option<u64>,
+    // seed every board from today's UTC date
+    pub daily_challenge: bool,
+    // every board draws from one sequence, each at its own pace
+    pub shared_sequence: bool,
+    // piece sequence seed after a reset
+    pub reseed_on_reset: ReseedOnReset,
+    // algorithm that picks each next piece
+    pub randomizer: Randomizer,
+    // where and in which orientation new pieces appear
+    pub spawn_rule: SpawnRule,
+    // relative odds per piece for the weighted randomizer, I J L S Z T O
+    pub piece_weights: [f32; 7],
+    // practice drill: every piece is this one, whatever the randomizer
+    pub drill_piece: Option<PieceType>,
+    // seconds frozen after game over before the board resets; 0.0 never resets
+    #[serde(alias = "game_over_hold")]
+    pub idle_reset: f32,
+    // seconds for the grayed stack to clear row by row after game over, 0.0 = keep it
+    pub game_over_finale: f32,
+    // what input does on a frozen board
+    pub frozen_input: FrozenInput,
+    // what moves and rotations during a clear animation do
+    pub clearing_input: ClearingInput,
+    // seconds incoming garbage waits, shown on a meter, before it can rise
+    pub garbage_delay: f32,
+    // top-outs per game forgiven by clearing the top of the stack, 0 = off
+    pub mercy_count: usize,
+    // rows cleared from the top of the stack by a mercy
+    pub mercy_rows: usize,
+    // hold automatically when the new piece would top out but the swap wouldn't
+    pub auto_hold_assist: bool,
+    // stack height in rows that fires StackHigh, 0 = off
+    pub stack_warning: usize,
+    // rows the stack must fall below stack_warning before StackCleared fires
+    pub stack_warning_band: usize,
+    // slow motion when the stack drops out of the warning within this many
+    // seconds of reaching it, 0 = off
+    pub near_miss_window: f32,
+    // seconds the slow motion takes to ease back to full speed, and the speed it starts at
+    pub slow_mo_duration: f32,
+    pub slow_mo_factor: f32,
+    // competitive mode: first board to this score wins and the rest lose, 0 = off
+    pub score_to_win: usize,
+    // timed and fixed-length games: end after this many pieces placed or seconds
+    // played, 0 = no limit
+    pub piece_limit: usize,
+    pub time_limit: f32,
+    // dig mode: start on this many garbage rows, refilled from below as they're
+    // cleared, and score dig_points per garbage row dug instead of lines, 0 = off
+    pub dig_rows: usize,
+    pub dig_points: usize,
+    // hold every board's gravity while any board is clearing
+    pub sync_clear_pause: bool,
+    // points per cell dropped by soft drop and by hard drop
+    pub soft_drop_points: usize,
+    pub hard_drop_points: usize,
+    // semi-opaque panel behind the playfield, rgba
+    pub panel: bool,
+    pub panel_color: [f32; 4],
+    // soft shadow under each filled cell
+    pub shadow: bool,
+    // shadow offset from the cell, in screen units [x, y]
+    pub shadow_offset: [f32; 2],
+    // shadow color, RGBA 0.0 - 1.0
+    pub shadow_color: [f32; 4],
+    // which full lines clear: rows, columns or both
+    pub clear_axis: ClearAxis,
+    // how a cleared row's height scales its points
+    pub row_score_weight: RowScoreWeight,
+    // per-row multipliers from the bottom, for row_score_weight = "custom"
+    pub row_score_weights: Vec<f32>,
+    // clockwise screen rotation in degrees: 0, 90, 180 or 270
+    pub orientation: u32,
+    // pieces only lock on a beat of [speed] bpm
+    pub beat_lock: bool,
+    // held pieces; 0 disables hold
+    pub hold_capacity: usize,
+    // gray out the hold box while hold is used up for the current piece
+    pub dim_used_hold: bool,
+    // puzzle mode: filled rows stay until the player confirms the clear
+    pub manual_clear: bool,
+    // gimmick: F flips the stack upside down
+    pub flip_input: bool,
+    // whether a piece that completes a row locks at once or after the lock delay
+    pub row_fill_lock: RowFillLock,
+    // what a falling piece has to land on to start locking
+    pub grounded_lock: GroundedLock,
+    // experimental: the left and right edges join, pieces wrap across them
+    pub wrap_x: bool,
+    // faintly highlight the suggested placement of each new piece
+    pub show_hint: bool,
+    // teaching: mark the point the active piece rotates about
+    pub show_pivot: bool,
+    // practice: faintly show every legal resting place of the active piece,
+    // green when hole-free and red when it leaves a hole, up to this many
+    pub placement_overlay: bool,
+    pub placement_overlay_max: usize,
+    // highlight lines the active piece would complete if dropped now
+    pub clear_preview: bool,
+    // seconds cleared cells flash bright before the clear animation; 0 for none
+    pub pre_clear_flash: f32,
+    // line clears flash the background, spreading from the cleared lines
+    pub clear_flash_background: bool,
+    // sparks thrown off the cleared cells per line cleared; 0 for none
+    pub clear_particles: usize,
+    // most sparks alive on a board at once
+    pub max_particles: usize,
+    // bar up the right edge showing how full the board is, green to red
+    pub fill_meter: bool,
+    // origin crosshair and coordinates on each board while the debug display is on
+    pub debug_axes: bool,
+    // image drawn in each cell, tinted by the cell color; flat color when unset
+    pub cell_texture: Option<PathBuf>,
+    pub preview: PreviewConfig,
+    pub glow: GlowConfig,
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        Self {
+            width: 16,
+            height: 24,
+            cell_size: CellSize::Pixels(DEFAULT_CELL_SIZE),
+            fit_fraction: 0.9,
+            cell_size_min: 8.0,
+            cell_size_max: 60.0,
+            gravity_interval: 0.5,
+            lock_delay: 0.25,
+            lock_grace: 0.0,
+            hard_drop_lockout: 0.0,
+            gravity_per_frame: 0.0,
+            gravity_enabled: true,
+            gravity_multiplier: 1.0,
+            entry_delay: 0.0,
+            irs: false,
+            ihs: false,
+            spawn_telegraph: true,
+            color_from_next: false,
+            level_lines: 0,
+            level_hue_step: 30.0,
+            level_fade: 0.5,
+            lock_delay_curve: LockDelayCurve::default(),
+            chain_per_line: 1.0,
+            chain_max: 0.0,
+            chain_decay: 0.5,
+            chain_thresholds: vec![2.0, 4.0, 8.0],
+            seed: None,
+            daily_challenge: false,
+            shared_sequence: false,
+            reseed_on_reset: ReseedOnReset::Continue,
+            randomizer: Randomizer::Uniform,
+            piece_weights: [1.0; 7],
+            drill_piece: None,
+            idle_reset: 0.0,
+            game_over_finale: 0.0,
+            frozen_input: FrozenInput::Ignore,
+            garbage_delay: 1.0,
+            spawn_rule: SpawnRule::Srs,
+            clearing_input: ClearingInput::Drop,
+            mercy_count: 0,
+            mercy_rows: 4,
+            auto_hold_assist: false,
+            stack_warning: 0,
+            stack_warning_band: 2,
+            near_miss_window: 0.0,
+            slow_mo_duration: 1.5,
+            slow_mo_factor: 0.3,
+            score_to_win: 0,
+            piece_limit: 0,
+            time_limit: 0.0,
+            dig_rows: 0,
+            dig_points: 100,
+            sync_clear_pause: false,
+            soft_drop_points: 1,
+            hard_drop_points: 2,
+            panel: false,
+            panel_color: [0.0, 0.0, 0.0, 0.5],
+            shadow: false,
+            shadow_offset: [3.0, -3.0],
+            shadow_color: [0.0, 0.0, 0.0, 0.5],
+            clear_axis: ClearAxis::Rows,
+            row_score_weight: RowScoreWeight::Flat,
+            row_score_weights: Vec::new(),
+            orientation: 0,
+            beat_lock: false,
+            hold_capacity: 0,
+            dim_used_hold: true,
+            manual_clear: false,
+            flip_input: false,
+            row_fill_lock: RowFillLock::Immediate,
+            grounded_lock: GroundedLock::Contact,
+            wrap_x: false,
+            show_hint: false,
+            show_pivot: false,
+            placement_overlay: false,
+            placement_overlay_max: 40,
+            clear_preview: false,
+            pre_clear_flash: 0.0,
+            clear_flash_background: false,
+            clear_particles: 0,
+            max_particles: 200,
+            fill_meter: false,
+            debug_axes: true,
+            cell_texture: None,
+            preview: PreviewConfig::default(),
+            glow: GlowConfig::default(),
+        }
+    }
+}
+
+impl BoardConfig {
+    // Largest whole-pixel cell_size that keeps the board within fit_fraction
+    // of a width x height screen, clamped to cell_size_min/max
+    pub fn fit_cell_size(&self, width: f32, height: f32) -> f32 {
+        let cols = self.width.max(1) as f32;
+        let rows = self.height.max(1) as f32;
+        let fit = (width * self.fit_fraction / cols)
+            .min(height * self.fit_fraction / rows)
+            .floor();
+        fit.clamp(
+            self.cell_size_min,
+            self.cell_size_max.max(self.cell_size_min),
+        )
+    }
+
+    // The config with an "auto" cell_size fitted to the screen
+    pub fn fitted(&self, width: f32, height: f32) -> BoardConfig {
+        let mut config = self.clone();
+        if self.cell_size == CellSize::Auto {
+            config.cell_size = CellSize::Pixels(self.fit_cell_size(width, height));
+        }
+        config
+    }
+}
+
+pub const DEFAULT_CELL_SIZE: f32 = 30.0;
+
+// cell_size in the config: pixels, or "auto" to fit the board to the screen
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "toml::Value")]
+pub enum CellSize {
+    Pixels(f32),
+    Auto,
+}
+
+impl TryFrom<toml::Value> for CellSize {
+    type Error = String;
+
+    fn try_from(value: toml::Value) -> Result<Self, Self::Error> {
+        match value {
+            toml::Value::Float(px) => Ok(CellSize::Pixels(px as f32)),
+            toml::Value::Integer(px) => Ok(CellSize::Pixels(px as f32)),
+            toml::Value::String(s) if s == "auto" => Ok(CellSize::Auto),
+            other => Err(format!(
+                "cell_size must be a number or \"auto\", not {}",
+                other
+            )),
+        }
+    }
+}
+
+impl CellSize {
+    // "auto" is resolved by BoardConfig::fitted when a board is made;
+    // an unfitted one falls back to the default size
+    pub fn pixels(&self) -> f32 {
+        match self {
+            CellSize::Pixels(px) => *px,
+            CellSize::Auto => DEFAULT_CELL_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClearAxis {
+    Rows,
+    Cols,
+    Both,
+}
+
+impl ClearAxis {
+    pub fn rows(&self) -> bool {
+        matches!(self, ClearAxis::Rows | ClearAxis::Both)
+    }
+
+    pub fn cols(&self) -> bool {
+        matches!(self, ClearAxis::Cols | ClearAxis::Both)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReseedOnReset {
+    Continue,  // the sequence carries on from where the last game left it
+    Same,      // restart the sequence from the same seed
+    Increment, // seed + 1
+    Random,    // a fresh random seed
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Randomizer {
+    Uniform,  // independent draws
+    SevenBag, // all seven pieces, shuffled, then the next bag
+    Weighted, // independent draws by piece_weights
+    Tgm,      // rerolls pieces seen in the last four
+    Nes,      // one reroll on a repeat
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowScoreWeight {
+    Flat,        // every row scores the same
+    HeightBonus, // up to double for the top row
+    Custom,      // row_score_weights, 1.0 past the end of the list
+}
+
+impl RowScoreWeight {
+    // Multiplier for each row of a board `height` rows tall, bottom first
+    pub fn weights(&self, height: usize, custom: &[f32]) -> Vec<f32> {
+        (0..height)
+            .map(|y| match self {
+                RowScoreWeight::Flat => 1.0,
+                RowScoreWeight::HeightBonus => 1.0 + y as f32 / height.max(1) as f32,
+                RowScoreWeight::Custom => custom.get(y).copied().unwrap_or(1.0),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RowFillLock {
+    Immediate, // lock and clear the moment a row fills
+    Delay,     // keep playing; the row clears when the piece locks normally
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroundedLock {
+    Contact, // resting on the stack or the floor
+    Floor,   // only on the floor; a piece held up by the stack locks when gravity can't move it
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrozenInput {
+    Ignore,  // stay frozen until idle_reset, if any
+    Restart, // any input starts a new game
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpawnRule {
+    Srs, // every piece in its first orientation, centered
+    Nes, // T, J and L flat side up, in the NES columns
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClearingInput {
+    Drop,   // ignored, there's no piece to move
+    Buffer, // replayed on the next piece as it spawns
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewSide {
+    Left,
+    Right,
+    Top,
+}
+
+// Next-piece preview box. Sizes are in board cells so boards scale alike.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PreviewConfig {
+    pub enabled: bool,
+    pub side: PreviewSide,
+    pub spacing: f32,
+    pub cell_scale: f32,
+    pub background: [f32; 4],
+    // each piece in its own color rather than the board's, like pieces in play
+    pub true_colors: bool,
+    // upcoming pieces shown, one box each, next piece nearest the top
+    pub count: usize,
+    // gap between stacked boxes, in board cells
+    pub list_gap: f32,
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            side: PreviewSide::Right,
+            spacing: 1.0,
+            cell_scale: 0.6,
+            background: [0.0, 0.0, 0.0, 0.6],
+            true_colors: false,
+            count: 1,
+            list_gap: 0.3,
+        }
+    }
+}
+
+// Selective bloom: cells lifted just over the bloom threshold so only they glow
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GlowConfig {
+    // active pieces of these types glow
+    pub pieces: Vec<PieceType>,
+    // cells in this row and above glow, counted from 1 at the bottom, 0 = off
+    pub from_row: usize,
+    // brightness above which the post-processing blooms
+    pub bloom_threshold: f32,
+    // how far over the threshold glowing cells are lifted
+    pub margin: f32,
+}
+
+impl Default for GlowConfig {
+    fn default() -> Self {
+        Self {
+            pieces: Vec::new(),
+            from_row: 0,
+            bloom_threshold: 0.8,
+            margin: 0.1,
+        }
+    }
+}
+
+impl GlowConfig {
+    // Whether a cell in `row` (0 at the bottom), of the active piece if given, glows
+    pub fn glows(&self, piece: Option<PieceType>, row: isize) -> bool {
+        let in_band = self.from_row > 0 && row + 1 >= self.from_row as isize;
+        in_band || piece.is_some_and(|piece| self.pieces.contains(&piece))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -19,12 +496,31 @@ pub struct RenderConfig {
     pub texture_height: u32,
     pub texture_samples: u32,
     pub arc_resolution: u32,
+    #[serde(default)]
+    pub batch_cells: bool,
+    // render at this multiple of texture_width/height, then downscale
+    #[serde(default = "default_render_scale")]
+    pub render_scale: f32,
+    // cap on either side of the render texture, 0 = the GPU's limit
+    #[serde(default)]
+    pub max_texture_size: u32,
+    // skip the redraw and post-processing while nothing on screen changes
+    #[serde(default)]
+    pub render_on_change: bool,
+}
+
+fn default_render_scale() -> f32 {
+    1.0
 }
 
 #[derive(Debug, Deserialize)]
 pub struct WindowConfig {
     pub width: u32,
     pub height: u32,
+    #[serde(default)]
+    pub preserve_aspect: bool,
+    #[serde(default)]
+    pub pause_on_unfocus: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -36,6 +532,16 @@ pub struct FrameRecorderConfig {
 #[derive(Debug, Deserialize)]
 pub struct SpeedConfig {
     pub bpm: u32,
+    // game steps per second, 0 = one step per frame of real dt
+    #[serde(default)]
+    pub fixed_step_hz: u32,
+    // most steps run in one frame when catching up after a slow frame
+    #[serde(default = "default_max_steps")]
+    pub max_steps_per_frame: usize,
+}
+
+fn default_max_steps() -> usize {
+    8
 }
 
 #[derive(Debug, Deserialize)]
@@ -47,3 +553,253 @@ pub struct PathConfig {
 pub struct OscConfig {
     pub rx_port: u16,
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SpectatorConfig {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for SpectatorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 9000,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct InputConfig {
+    // max inputs held between frames; extras are dropped
+    pub buffer_size: usize,
+    // what to do when Left and Right arrive in the same frame
+    pub opposite_inputs: OppositeInputs,
+    // order of a move and a soft drop applied in the same frame
+    pub combo_order: ComboOrder,
+    // soft drop against the stack restarts the lock delay
+    pub soft_drop_resets_lock: bool,
+    // inputs averaged for the input latency readout in the debug display, 0 = off
+    pub latency_samples: usize,
+    // repeats of the same input closer together than this are dropped, 0 = off
+    pub debounce_ms: u64,
+    // holding rotate spins the piece repeatedly, like held moves
+    pub auto_repeat_rotation: bool,
+    // keys rebound in bind mode (F1), saved on exit and loaded over the defaults
+    pub keybinds_path: String,
+}
+
+impl Default for InputConfig {
+    fn default() -> Self {
+        Self {
+            buffer_size: 8,
+            opposite_inputs: OppositeInputs::Cancel,
+            combo_order: ComboOrder::MoveFirst,
+            soft_drop_resets_lock: false,
+            latency_samples: 30,
+            debounce_ms: 0,
+            auto_repeat_rotation: false,
+            keybinds_path: "keybinds.txt".to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OppositeInputs {
+    FirstWins, // keep the direction pressed first
+    LastWins,  // keep the direction pressed last
+    Cancel,    // drop both directions
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComboOrder {
+    MoveFirst, // slide sideways, then drop
+    DropFirst, // drop, then slide sideways
+}
+
+// Record runs to `path`, and/or race the run saved there as a ghost
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ReplayConfig {
+    pub record: bool,
+    pub ghost: bool,
+    pub path: String,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            record: false,
+            ghost: false,
+            path: "replay.json".to_owned(),
+        }
+    }
+}
+
+// A board created at startup. Unset overrides use [board].
+#[derive(Debug, Clone, Deserialize)]
+pub struct BoardEntry {
+    pub id: String,
+    pub x: f32,
+    pub y: f32,
+    #[serde(default)]
+    pub width: Option<usize>,
+    #[serde(default)]
+    pub height: Option<usize>,
+    #[serde(default)]
+    pub cell_size: Option<CellSize>,
+    #[serde(default)]
+    pub gravity_interval: Option<f32>,
+    #[serde(default)]
+    pub lock_delay: Option<f32>,
+    #[serde(default)]
+    pub gravity_multiplier: Option<f32>,
+}
+
+impl BoardEntry {
+    pub fn board_config(&self, base: &BoardConfig) -> BoardConfig {
+        BoardConfig {
+            width: self.width.unwrap_or(base.width),
+            height: self.height.unwrap_or(base.height),
+            cell_size: self.cell_size.unwrap_or(base.cell_size),
+            gravity_interval: self.gravity_interval.unwrap_or(base.gravity_interval),
+            lock_delay: self.lock_delay.unwrap_or(base.lock_delay),
+            gravity_multiplier: self.gravity_multiplier.unwrap_or(base.gravity_multiplier),
+            ..base.clone()
+        }
+    }
+}
+
+// Session save file; a .bin extension saves in the compact binary format
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    pub path: String,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            path: "session.json".to_owned(),
+        }
+    }
+}
+
+// Lock delay per level: lock_delay scaled by `scale` once per level, held at `floor`
+#[derive(Debug, Copy, Clone, Deserialize)]
+#[serde(default)]
+pub struct LockDelayCurve {
+    pub scale: f32, // 1.0 keeps the delay fixed
+    pub floor: f32, // shortest delay in seconds
+}
+
+impl LockDelayCurve {
+    // A floor above the base delay never lengthens it
+    pub fn delay(&self, base: f32, level: usize) -> f32 {
+        let scaled = base * self.scale.powi(level.min(i32::MAX as usize) as i32);
+        scaled.max(self.floor.min(base))
+    }
+}
+
+impl Default for LockDelayCurve {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            floor: 0.0,
+        }
+    }
+}
+
+// How scores are written on the HUD
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScoreFormat {
+    pub separator: String,   // between groups of three digits, "" for none
+    pub width: usize,        // minimum width, right aligned
+    pub leading_zeros: bool, // pad to width with zeros instead of spaces
+}
+
+impl Default for ScoreFormat {
+    fn default() -> Self {
+        Self {
+            separator: ",".to_owned(),
+            width: 0,
+            leading_zeros: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_cell_size_fits_screen() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            cell_size: CellSize::Auto,
+            ..Default::default()
+        };
+
+        // Height bound: 1080 * 0.9 / 20 rows = 48.6
+        let fitted = config.fitted(1920.0, 1080.0);
+        let cell = fitted.cell_size.pixels();
+        assert_eq!(cell, 48.0);
+        assert!(10.0 * cell <= 1920.0 * 0.9 && 20.0 * cell <= 1080.0 * 0.9);
+
+        // Clamped on a tiny and a huge screen
+        assert_eq!(config.fit_cell_size(100.0, 100.0), config.cell_size_min);
+        assert_eq!(config.fit_cell_size(8000.0, 8000.0), config.cell_size_max);
+
+        // Fixed sizes are left alone
+        let fixed = BoardConfig::default().fitted(100.0, 100.0);
+        assert_eq!(fixed.cell_size, CellSize::Pixels(DEFAULT_CELL_SIZE));
+    }
+
+    #[test]
+    fn test_cell_size_values() {
+        let parse = |value| CellSize::try_from(value);
+        assert_eq!(parse(toml::Value::Float(25.0)), Ok(CellSize::Pixels(25.0)));
+        assert_eq!(parse(toml::Value::Integer(25)), Ok(CellSize::Pixels(25.0)));
+        assert_eq!(
+            parse(toml::Value::String("auto".into())),
+            Ok(CellSize::Auto)
+        );
+        assert!(parse(toml::Value::String("big".into())).is_err());
+    }
+
+    #[test]
+    fn test_game_over_hold_sets_idle_reset() {
+        let config: BoardConfig = toml::from_str("game_over_hold = 4.5").unwrap();
+        assert_eq!(config.idle_reset, 4.5);
+    }
+
+    #[test]
+    fn test_board_panel_config() {
+        let config: BoardConfig =
+            toml::from_str("panel = true\npanel_color = [0.1, 0.2, 0.3, 0.4]").unwrap();
+        assert!(config.panel);
+        assert_eq!(config.panel_color, [0.1, 0.2, 0.3, 0.4]);
+
+        // Unset keys keep their defaults
+        assert_eq!(config.width, BoardConfig::default().width);
+        assert!(!BoardConfig::default().panel);
+    }
+
+    #[test]
+    fn test_board_shadow_config() {
+        let config: BoardConfig = toml::from_str(
+            "shadow = true\nshadow_offset = [4.0, -2.0]\nshadow_color = [0.1, 0.0, 0.2, 0.3]",
+        )
+        .unwrap();
+        assert!(config.shadow);
+        assert_eq!(config.shadow_offset, [4.0, -2.0]);
+        assert_eq!(config.shadow_color, [0.1, 0.0, 0.2, 0.3]);
+        assert!(!BoardConfig::default().shadow);
+    }
+}
@@ -3,6 +3,9 @@ pub mod config_types;
 
 pub use config_load::Config;
 pub use config_types::{
-    BoardConfig, FrameRecorderConfig, OscConfig, PathConfig, RenderConfig, SpeedConfig,
-    WindowConfig,
+    BoardConfig, BoardEntry, CellSize, ClearAxis, ClearingInput, ComboOrder, FrameRecorderConfig,
+    FrozenInput, GlowConfig, GroundedLock, InputConfig, LockDelayCurve, OppositeInputs, OscConfig,
+    PathConfig, PreviewConfig, PreviewSide, Randomizer, RenderConfig, ReplayConfig, ReseedOnReset,
+    RowFillLock, RowScoreWeight, ScoreFormat, SessionConfig, SpawnRule, SpectatorConfig,
+    SpeedConfig, WindowConfig,
 };
@@ -86,6 +86,11 @@ impl BackgroundColorFade {
             is_active: false,
         }
     }
+
+    // The color the fade ends on
+    pub fn target(&self) -> Rgb {
+        self.target_color
+    }
 }
 
 impl BackgroundEffect for BackgroundColorFade {
@@ -149,3 +154,54 @@ impl BackgroundEffect for BackgroundColorFade {
 fn lerp(a: f32, b: f32, t: f32) -> f32 {
     a * (1.0 - t) + b * t
 }
+
+// A circle of light from a point in the scene that grows and fades out,
+// drawn over the background color. Used to bleed line clears into the background.
+#[derive(Debug, Default)]
+pub struct RadialFlash {
+    center: Vec2,
+    radius: f32,
+    color: Rgb,
+    start_time: f32,
+    duration: f32,
+    is_active: bool,
+}
+
+impl RadialFlash {
+    pub fn new(center: Vec2, radius: f32, color: Rgb, duration: f32, current_time: f32) -> Self {
+        Self {
+            center,
+            radius,
+            color,
+            start_time: current_time,
+            duration,
+            is_active: true,
+        }
+    }
+
+    // Center, radius and alpha to draw now, or None once it has faded
+    pub fn update(&mut self, current_time: f32) -> Option<(Vec2, f32, f32)> {
+        if !self.is_active {
+            return None;
+        }
+
+        let elapsed = current_time - self.start_time;
+        if elapsed > self.duration || self.duration <= 0.0 {
+            self.is_active = false;
+            return None;
+        }
+
+        // Grows quickly to full size while fading linearly
+        let progress = elapsed / self.duration;
+        let radius = self.radius * (0.5 + 0.5 * progress.sqrt());
+        Some((self.center, radius, 1.0 - progress))
+    }
+
+    pub fn color(&self) -> Rgb {
+        self.color
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+}
@@ -1,7 +1,9 @@
 use nannou::prelude::*;
 
 pub mod background_fx;
-pub use background_fx::{BackgroundColorFade, BackgroundFlash};
+pub mod particles;
+pub use background_fx::{BackgroundColorFade, BackgroundFlash, RadialFlash};
+pub use particles::{burst, Particle};
 
 pub trait BackgroundEffect {
     fn start(&mut self, start_color: Rgb, target_color: Rgb, duration: f32, current_time: f32);
@@ -0,0 +1,81 @@
+// src/effects/particles.rs
+//
+// Sparks thrown off cleared cells: each flies outward from its cell, slowing
+// and fading over its lifetime
+
+use crate::utils::Timer;
+use nannou::prelude::*;
+
+// Seconds a particle lives
+const LIFETIME: f32 = 0.8;
+
+// Launch speed in cells per second, before drag
+const SPEED_CELLS: f32 = 6.0;
+
+// Fraction of velocity kept each second
+const DRAG: f32 = 0.15;
+
+#[derive(Debug)]
+pub struct Particle {
+    pub position: Vec2,
+    velocity: Vec2,
+    life: Timer,
+    color: Rgba,
+}
+
+impl Particle {
+    pub fn new(position: Vec2, velocity: Vec2, color: Rgba) -> Self {
+        Self {
+            position,
+            velocity,
+            life: Timer::new(LIFETIME),
+            color,
+        }
+    }
+
+    // Move and age the particle. Returns false once it has burned out.
+    pub fn update(&mut self, dt: f32) -> bool {
+        self.position += self.velocity * dt;
+        self.velocity *= DRAG.powf(dt);
+        !self.life.tick(dt)
+    }
+
+    // Color with the alpha fading out over the lifetime
+    pub fn color(&self) -> Rgba {
+        let mut color = self.color;
+        color.alpha *= 1.0 - self.life.progress().min(1.0);
+        color
+    }
+}
+
+// `count` particles spread evenly over `cells`, each launched in a different
+// direction. Directions step by the golden angle so any count looks scattered.
+pub fn burst(cells: &[Vec2], count: usize, cell_size: f32, color: Rgba) -> Vec<Particle> {
+    if cells.is_empty() {
+        return Vec::new();
+    }
+    let golden_angle = PI * (3.0 - 5.0_f32.sqrt());
+    (0..count)
+        .map(|i| {
+            let cell = cells[i * cells.len() / count];
+            let angle = i as f32 * golden_angle;
+            let speed = SPEED_CELLS * cell_size * (0.6 + 0.2 * (i % 3) as f32);
+            let velocity = vec2(angle.cos(), angle.sin()) * speed;
+            Particle::new(cell, velocity, color)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_particle_fades_and_expires() {
+        let mut particle = Particle::new(vec2(0.0, 0.0), vec2(10.0, 0.0), rgba(1.0, 1.0, 1.0, 1.0));
+        assert!(particle.update(LIFETIME * 0.5));
+        assert!(particle.position.x > 0.0);
+        assert!((particle.color().alpha - 0.5).abs() < 1e-4);
+        assert!(!particle.update(LIFETIME));
+    }
+}
@@ -1,5 +1,6 @@
 pub mod config;
 pub mod effects;
 pub mod models;
+pub mod net;
 pub mod utils;
 pub mod views;
@@ -2,33 +2,115 @@
 
 use gameover2025::{
     config::*,
-    views::{BackgroundManager, BoardInstance, PlayerInput},
+    models::{Replay, Session, SharedSequence},
+    net::{OscReceiver, SpectatorServer},
+    utils::{
+        daily_seed, fade_pixels, format_score, letterbox_rect, load_cell_texture,
+        scaled_texture_size, texture_limit, today_utc, Debouncer, FixedStep, InputBuffer,
+        LatencyMeter, FAINT_CELL_OPACITY,
+    },
+    views::{
+        accept_key_repeat, action_name, apply_input_combos, resolve_opposite_inputs,
+        settle_score_race, sync_clear_pause, BackgroundManager, BoardInstance, GameState, Keymap,
+        Palette, PlayerInput, Rebinder, SoundCue,
+    },
 };
-use nannou::prelude::*;
+use nannou::{prelude::*, rand::random};
+use nannou_osc as osc;
 use nnpipe::*;
-use std::{collections::HashMap, time::Instant};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::Path,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+// Seconds a line clear's background flash takes to fade
+const CLEAR_FLASH_DURATION: f32 = 0.6;
 
 struct Model {
     // Tetris Boards
     boards: HashMap<String, BoardInstance>,
     board_config: BoardConfig,
+    // One piece sequence every board draws from, when shared
+    shared_sequence: Option<Rc<RefCell<SharedSequence>>>,
 
     // Background
     background: BackgroundManager,
 
-    // Player input pending update
-    player_input: Option<PlayerInput>,
+    // Player inputs pending update
+    input_buffer: InputBuffer<PlayerInput>,
+    opposite_inputs: OppositeInputs,
+    held_inputs: Vec<PlayerInput>, // gameplay keys currently down
+    combo_order: ComboOrder,
+
+    // When queued inputs arrived, for the latency readout; None when off
+    latency: Option<LatencyMeter>,
+    input_times: Vec<Instant>,
+    // drops rapid repeats of an input; None when off
+    debounce: Option<Debouncer<PlayerInput>>,
+    auto_repeat_rotation: bool, // a held rotate key keeps rotating
+
+    // Key bindings, edited live in bind mode and saved on exit when changed
+    keymap: Keymap,
+    rebinder: Rebinder,
+    keybinds_path: String,
+    keymap_changed: bool,
+
+    // Daily challenge date and seed, when enabled
+    daily: Option<(String, u64)>,
+
+    // Beat for rhythm locking
+    bpm: u32,
+
+    // Fixed game steps, None to step once per frame. Inputs wait for the next step.
+    fixed_step: Option<FixedStep>,
+    pending_inputs: Vec<PlayerInput>,
+
+    // Where F5/F9 save and restore the boards
+    session_path: String,
+
+    // HUD score text
+    score_format: ScoreFormat,
+
+    // Cell sprite and its translucent copy
+    cell_texture: Option<(wgpu::Texture, wgpu::Texture)>,
+
+    // Run recording and the past run raced as a ghost
+    replay_config: ReplayConfig,
+    ghost: Option<Replay>,
+
+    // Streams board snapshots to remote spectators
+    spectator: Option<SpectatorServer>,
 
-    // Random
-    rng: nannou::rand::rngs::ThreadRng,
+    // Incoming OSC; rebinds itself if the socket fails
+    osc: OscReceiver,
+
+    // Only this board's events go out, when set
+    solo: Option<String>,
 
     // Nannou API
     draw: nannou::Draw,
     draw_renderer: nannou::draw::Renderer,
 
     texture: wgpu::Texture,
+    scene_size: Vec2, // drawing area in scene units, for fitting "auto" boards
     texture_reshaper: wgpu::TextureReshaper,
     post_processing: Nnpipe,
+    render_on_change: bool, // only render frames where something changed
+    dirty: bool,            // this frame differs from the last one rendered
+
+    // Letterbox the texture instead of stretching it to the window
+    preserve_aspect: bool,
+
+    // Boards paused because the window lost focus, resumed when it comes back
+    pause_on_unfocus: bool,
+    focus_paused: Vec<String>,
+
+    // Draw board cells as one mesh per board
+    batch_cells: bool,
+    soft_drop_resets_lock: bool,
 
     // FPS
     last_update: Instant,
@@ -44,7 +126,53 @@ struct Model {
 
 fn model(app: &App) -> Model {
     // Load config
-    let config = Config::load().expect("\nGameOver: FAILED TO LOAD CONFIG.TOML\n");
+    let mut config = Config::load().expect("\nGameOver: FAILED TO LOAD CONFIG.TOML\n");
+    if let Err(e) = config.validate_boards() {
+        panic!("\nGameOver: INVALID [[boards]] IN CONFIG.TOML: {}\n", e);
+    }
+
+    // Daily challenge: every board plays today's sequence
+    let daily = config.board.daily_challenge.then(|| {
+        let date = today_utc();
+        let seed = daily_seed(&date);
+        println!("Daily challenge {} -- seed {}", date, seed);
+        (date, seed)
+    });
+    if let Some((_, seed)) = daily {
+        config.board.seed = Some(seed);
+    }
+
+    // Keys rebound in an earlier run
+    let keymap = Keymap::load(Path::new(&config.input.keybinds_path)).unwrap_or_else(|e| {
+        println!(
+            "Failed to load keybinds {}: {}",
+            config.input.keybinds_path, e
+        );
+        Keymap::default()
+    });
+
+    // Ghost run: boards play the same sequence it did
+    let ghost = if config.replay.ghost {
+        Replay::load(Path::new(&config.replay.path))
+            .map_err(|e| println!("Failed to load ghost {}: {}", config.replay.path, e))
+            .ok()
+    } else {
+        None
+    };
+    if let Some(ghost) = &ghost {
+        config.board.seed = Some(ghost.seed);
+    }
+
+    // Competition: every board is dealt the same pieces. Boards keep the
+    // shared seed too, so their recordings name the sequence they played.
+    let shared_sequence = config.board.shared_sequence.then(|| {
+        let seed = *config.board.seed.get_or_insert_with(random);
+        Rc::new(RefCell::new(SharedSequence::new(
+            seed,
+            config.board.randomizer,
+            config.board.piece_weights,
+        )))
+    });
 
     // Create window
     let window_id = app
@@ -54,19 +182,68 @@ fn model(app: &App) -> Model {
         .msaa_samples(1)
         .view(view)
         .key_pressed(key_pressed)
+        .key_released(key_released)
+        .focused(window_focused)
+        .unfocused(window_unfocused)
         .build()
         .unwrap();
 
     let window = app.window(window_id).unwrap();
 
-    // Set up render texture
+    // Cell sprite and a translucent copy for ghost and preview cells, flat
+    // cells if it won't load
+    let cell_texture = load_cell_texture(config.board.cell_texture.as_deref(), |path| {
+        nannou::image::open(path)
+    })
+    .map(|image| {
+        let mut faint = image.to_rgba8();
+        fade_pixels(&mut faint, FAINT_CELL_OPACITY);
+        let faint = nannou::image::DynamicImage::ImageRgba8(faint);
+        (
+            wgpu::Texture::from_image(app, &image),
+            wgpu::Texture::from_image(app, &faint),
+        )
+    });
+
+    // Start the spectator server
+    let spectator = if config.spectator.enabled {
+        SpectatorServer::start(config.spectator.port)
+            .map_err(|e| println!("Spectator server failed to start: {}", e))
+            .ok()
+    } else {
+        None
+    };
+
+    // Start listening for OSC
+    let osc = OscReceiver::start(config.osc.rx_port);
+
+    // Set up render texture, supersampled by render_scale
     let device = window.device();
-    let draw = nannou::Draw::new();
-    let texture = wgpu::TextureBuilder::new()
-        .size([
+    let max_texture = texture_limit(
+        config.rendering.max_texture_size,
+        device.limits().max_texture_dimension_2d,
+    );
+    let texture_size = scaled_texture_size(
+        config.rendering.texture_width,
+        config.rendering.texture_height,
+        config.rendering.render_scale,
+        max_texture,
+    );
+    if texture_size.clamped {
+        println!(
+            "Warning: {}x{} at render_scale {} exceeds the max texture size {}; rendering at {}x{}",
             config.rendering.texture_width,
             config.rendering.texture_height,
-        ])
+            config.rendering.render_scale,
+            max_texture,
+            texture_size.width,
+            texture_size.height
+        );
+    }
+    // Scene coordinates stay in unscaled texture pixels
+    let draw = nannou::Draw::new().scale(texture_size.scale);
+    let texture = wgpu::TextureBuilder::new()
+        .size([texture_size.width, texture_size.height])
         // Our texture will be used as the RENDER_ATTACHMENT for our `Draw` render pass.
         // It will also be SAMPLED by the `TextureCapturer` and `TextureResizer`.
         .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
@@ -84,8 +261,8 @@ fn model(app: &App) -> Model {
     let sample_count = window.msaa_samples();
     let post_processing = Nnpipe::new(
         device,
-        config.rendering.texture_width,
-        config.rendering.texture_height,
+        texture_size.width,
+        texture_size.height,
         config.rendering.texture_samples,
     );
 
@@ -103,21 +280,68 @@ fn model(app: &App) -> Model {
         dst_format,
     );
 
-    Model {
+    let mut model = Model {
         boards: HashMap::new(),
         board_config: config.board,
+        shared_sequence,
 
         background: BackgroundManager::new(rgb(0.05, 0.03, 0.0)),
 
-        player_input: None,
+        input_buffer: InputBuffer::new(config.input.buffer_size),
+        opposite_inputs: config.input.opposite_inputs,
+        held_inputs: Vec::new(),
+        combo_order: config.input.combo_order,
+        latency: (config.input.latency_samples > 0)
+            .then(|| LatencyMeter::new(config.input.latency_samples)),
+        input_times: Vec::new(),
+        debounce: (config.input.debounce_ms > 0)
+            .then(|| Debouncer::new(Duration::from_millis(config.input.debounce_ms))),
+        auto_repeat_rotation: config.input.auto_repeat_rotation,
+
+        keymap,
+        rebinder: Rebinder::default(),
+        keybinds_path: config.input.keybinds_path.clone(),
+        keymap_changed: false,
+
+        daily,
+
+        bpm: config.speed.bpm,
 
-        rng: nannou::rand::thread_rng(),
+        fixed_step: (config.speed.fixed_step_hz > 0)
+            .then(|| FixedStep::new(config.speed.fixed_step_hz, config.speed.max_steps_per_frame)),
+        pending_inputs: Vec::new(),
+
+        session_path: config.session.path,
+        score_format: config.score_format,
+        cell_texture,
+
+        replay_config: config.replay,
+        ghost,
+
+        spectator,
+
+        osc,
+        solo: None,
 
         draw,
         draw_renderer,
         texture,
+        scene_size: vec2(
+            config.rendering.texture_width as f32,
+            config.rendering.texture_height as f32,
+        ),
         texture_reshaper,
         post_processing,
+        render_on_change: config.rendering.render_on_change,
+        dirty: true,
+
+        preserve_aspect: config.window.preserve_aspect,
+
+        pause_on_unfocus: config.window.pause_on_unfocus,
+        focus_paused: Vec::new(),
+
+        batch_cells: config.rendering.batch_cells,
+        soft_drop_resets_lock: config.input.soft_drop_resets_lock,
 
         last_update: Instant::now(),
         fps: 0.0,
@@ -127,32 +351,76 @@ fn model(app: &App) -> Model {
         frame_time_accumulator: 0.0,
 
         verbose: false,
+    };
+
+    // Boards listed in config.toml
+    for entry in &config.boards {
+        let board_config = entry.board_config(&model.board_config);
+        model.add_board(&entry.id, vec2(entry.x, entry.y), &board_config);
     }
+
+    model
 }
 
 impl Model {
     fn make_board(&mut self, id: &str, location: Vec2) {
-        let config = &self.board_config;
-        let board = BoardInstance::new(
-            id,
-            location,
-            config.width,
-            config.height,
-            config.cell_size,
-            config.gravity_interval,
-            config.lock_delay,
-        );
+        let config = self.board_config.clone();
+        self.add_board(id, location, &config);
+    }
+
+    // Cell size of boards made from [board], once fitted
+    fn cell_size(&self) -> f32 {
+        let (x, y) = self.scene_size.into();
+        self.board_config.fitted(x, y).cell_size.pixels()
+    }
+
+    fn add_board(&mut self, id: &str, location: Vec2, config: &BoardConfig) {
+        let config = &config.fitted(self.scene_size.x, self.scene_size.y);
+        let mut board = BoardInstance::new(id, location, config);
+        if config.beat_lock {
+            board.set_beat_lock(self.bpm);
+        }
+        board.set_batch_cells(self.batch_cells);
+        board.set_render_on_change(self.render_on_change);
+        board.set_soloed_out(is_soloed_out(&self.solo, id));
+        if let Some((texture, faint)) = &self.cell_texture {
+            board.set_cell_texture(texture.clone(), faint.clone());
+        }
+        board.set_soft_drop_resets_lock(self.soft_drop_resets_lock);
+        if self.replay_config.record {
+            board.start_recording();
+        }
+        if let Some(ghost) = &self.ghost {
+            board.set_ghost(ghost.clone());
+        }
+        if let Some(sequence) = &self.shared_sequence {
+            board.share_sequence(sequence.clone());
+        }
         self.boards.insert(board.id.to_owned(), board);
         println!("\n<------ Board Created: <{}> ----->", id);
         println!(
-            "size: {}x{} blocks\nlocation: {}\n",
-            config.width, config.height, location
+            "size: {}x{} blocks of {}\nlocation: {}\n",
+            config.width,
+            config.height,
+            config.cell_size.pixels(),
+            location
         );
     }
 }
 
 fn main() {
-    nannou::app(model).update(update).run();
+    nannou::app(model).update(update).exit(exit).run();
+}
+
+// Keep keys rebound this run
+fn exit(_app: &App, model: Model) {
+    if !model.keymap_changed {
+        return;
+    }
+    match model.keymap.save(Path::new(&model.keybinds_path)) {
+        Ok(()) => println!("Keybinds saved to {}", model.keybinds_path),
+        Err(e) => println!("Failed to save keybinds: {}", e),
+    }
 }
 
 fn update(app: &App, model: &mut Model, _update: Update) {
@@ -169,24 +437,142 @@ fn update(app: &App, model: &mut Model, _update: Update) {
     // Handle the background
     model.background.draw(&model.draw, app.time);
 
-    // Update & draw the boards
-    for board in model.boards.values_mut() {
-        board.update(dt, &model.player_input, &mut model.rng);
+    let messages = model.osc.drain();
+    model.dirty = !messages.is_empty();
+    for message in messages {
+        if model.verbose {
+            println!("OSC: {}", message.addr);
+        }
+        handle_osc(model, &message);
+    }
+
+    // Update the boards, in fixed steps when configured
+    let mut inputs = std::mem::take(&mut model.pending_inputs);
+    inputs.extend(model.input_buffer.drain());
+    let inputs = apply_input_combos(inputs, &model.held_inputs, model.combo_order);
+    let inputs = resolve_opposite_inputs(inputs, model.opposite_inputs);
+    let (steps, step_dt) = match model.fixed_step.as_mut() {
+        Some(fixed_step) => (fixed_step.advance(dt), fixed_step.step()),
+        None => (1, dt),
+    };
+    if steps == 0 {
+        model.pending_inputs = inputs.clone();
+    }
+    for step in 0..steps {
+        if model.board_config.sync_clear_pause {
+            sync_clear_pause(model.boards.values_mut());
+        }
+        let step_inputs: &[PlayerInput] = if step == 0 { &inputs } else { &[] };
+        for board in model.boards.values_mut() {
+            board.update(step_dt, step_inputs);
+        }
+        let score_to_win = model.board_config.score_to_win;
+        if let Some(winner) = settle_score_race(model.boards.values_mut(), score_to_win) {
+            println!("<{}> reached {} and wins", winner, score_to_win);
+        }
+    }
+    if let Some(fixed_step) = &model.fixed_step {
+        for board in model.boards.values_mut() {
+            board.set_render_alpha(fixed_step.alpha());
+        }
+    }
+
+    // Draw the boards and handle what they fired
+    for (id, board) in model.boards.iter_mut() {
         board.draw(&model.draw);
+
+        // No audio engine yet; cues are only logged
+        for cue in board.drain_sound_cues() {
+            if model.verbose {
+                println!("{}: {:?}", id, cue);
+            }
+
+            // Let the clear bleed into the background
+            if matches!(cue, SoundCue::Clear(_) | SoundCue::Tetris) {
+                if let Some((center, radius)) = board.background_flash() {
+                    model.background.radial_flash(
+                        center,
+                        radius,
+                        rgb(1.0, 0.91, 0.65),
+                        CLEAR_FLASH_DURATION,
+                        app.time,
+                    );
+                }
+            }
+        }
+
+        // Save the finished run
+        if matches!(board.game_state(), GameState::Frozen | GameState::GameWon) {
+            if let Some(replay) = board.take_recording() {
+                let path = Replay::board_path(Path::new(&model.replay_config.path), id);
+                match replay.save(&path) {
+                    Ok(()) => println!("{}: replay saved to {}", id, path.display()),
+                    Err(e) => println!("{}: failed to save replay: {}", id, e),
+                }
+            }
+        }
     }
 
-    model.player_input = None;
+    // Stream the updated boards
+    if let Some(spectator) = &mut model.spectator {
+        for (id, board) in &model.boards {
+            spectator.send(id, board);
+        }
+    }
+
+    if let Some((date, seed)) = &model.daily {
+        draw_daily(model, date, *seed);
+    }
+
+    if let Some(input) = model.rebinder.selected() {
+        draw_bind_prompt(model, input);
+    }
 
     // Handle FPS and origin display
     if model.verbose {
         draw_fps(model);
         draw_score(model);
+        for board in model.boards.values() {
+            board.draw_telemetry(&model.draw);
+            board.draw_origin(&model.draw);
+        }
+    }
+
+    // Inputs waiting on a fixed step haven't taken effect yet
+    if steps > 0 {
+        let queued = std::mem::take(&mut model.input_times);
+        if let Some(latency) = model.latency.as_mut() {
+            latency.record(&queued, Instant::now());
+        }
+    }
+
+    // Keep showing the last rendered frame while nothing changes
+    model.dirty |= model.boards.values().any(BoardInstance::is_dirty)
+        || model.background.is_animating()
+        || model.rebinder.is_active()
+        || model.verbose;
+    if model.render_on_change && !model.dirty {
+        model.draw.reset();
+        return;
     }
 
     render_and_post(app, model);
 }
 
-fn view(_app: &App, model: &Model, frame: Frame) {
+fn view(app: &App, model: &Model, frame: Frame) {
+    if model.preserve_aspect {
+        // fit texture inside the window, black bars on the uncovered sides
+        let [texture_w, texture_h] = model.texture.size();
+        let window = frame.rect();
+        let dest = letterbox_rect(window.w(), window.h(), texture_w as f32, texture_h as f32);
+
+        let draw = app.draw();
+        draw.background().color(BLACK);
+        draw.texture(&model.texture).xy(dest.xy()).wh(dest.wh());
+        draw.to_frame(app, &frame).unwrap();
+        return;
+    }
+
     //resize texture to screen
     let mut encoder = frame.command_encoder();
 
@@ -195,42 +581,261 @@ fn view(_app: &App, model: &Model, frame: Frame) {
         .encode_render_pass(frame.texture_view(), &mut encoder);
 }
 
+// ******************************* Window Focus ****************************
+
+fn window_unfocused(_app: &App, model: &mut Model) {
+    // Keys released while unfocused never send a release
+    model.held_inputs.clear();
+
+    if !model.pause_on_unfocus {
+        return;
+    }
+    for (id, board) in model.boards.iter_mut() {
+        if board.set_paused(true) {
+            model.focus_paused.push(id.to_owned());
+        }
+    }
+}
+
+// Only boards paused by the focus loss resume; ones the player paused stay paused
+fn window_focused(_app: &App, model: &mut Model) {
+    for id in model.focus_paused.drain(..) {
+        if let Some(board) = model.boards.get_mut(&id) {
+            board.set_paused(false);
+        }
+    }
+}
+
 // ******************************* Key Capture *****************************
 
 fn key_pressed(app: &App, model: &mut Model, key: Key) {
-    match key {
-        Key::Left => model.player_input = Some(PlayerInput::L),
-        Key::Right => model.player_input = Some(PlayerInput::R),
-        Key::Up => model.player_input = Some(PlayerInput::Rotate),
-        Key::Space => model.player_input = Some(PlayerInput::HardDrop),
-        Key::Return => model.player_input = Some(PlayerInput::Pause),
-        Key::Key1 => model.player_input = Some(PlayerInput::SaveState),
-        Key::Key2 => model.player_input = Some(PlayerInput::ResumeState),
+    // Bind mode takes every key until F1 ends it
+    if key == Key::F1 {
+        model.rebinder.toggle();
+        return;
+    }
+    if model.rebinder.is_active() {
+        if model.rebinder.key_pressed(key, &mut model.keymap) {
+            model.keymap_changed = true;
+        }
+        return;
+    }
 
+    // Gameplay keys are queued for the next update
+    if let Some(input) = key_to_input(&model.keymap, key, app.keys.mods.shift()) {
+        if !accept_key_repeat(input, &model.held_inputs, model.auto_repeat_rotation) {
+            return;
+        }
+        let now = Instant::now();
+        if let Some(debounce) = model.debounce.as_mut() {
+            if !debounce.accept(input, now) {
+                return;
+            }
+        }
+        if model.input_buffer.push(input) && model.latency.is_some() {
+            model.input_times.push(now);
+        }
+        if !model.held_inputs.contains(&input) {
+            model.held_inputs.push(input);
+        }
+        return;
+    }
+
+    match key {
         Key::G => {
-            model.make_board(
-                "board1",
-                vec2(
-                    (model.board_config.width as f32 * model.board_config.cell_size / -2.0) - 100.0,
-                    0.0,
-                ),
-            );
-            model.make_board(
-                "board2",
-                vec2(
-                    (model.board_config.width as f32 * model.board_config.cell_size / 2.0) + 100.0,
-                    0.0,
-                ),
-            );
+            let half_width = model.board_config.width as f32 * model.cell_size() / 2.0;
+            model.make_board("board1", vec2(-half_width - 100.0, 0.0));
+            model.make_board("board2", vec2(half_width + 100.0, 0.0));
         }
         Key::P => {
             model.verbose = !model.verbose;
             init_fps(app, model);
         }
+        Key::F2 => {
+            for board in model.boards.values_mut() {
+                board.set_placement_overlay(!board.placement_overlay());
+            }
+        }
+        Key::F5 => save_session(model),
+        Key::F9 => load_session(model),
+        Key::F12 => export_boards(model),
+        _ => {}
+    }
+}
+
+fn key_released(app: &App, model: &mut Model, key: Key) {
+    if let Some(input) = key_to_input(&model.keymap, key, app.keys.mods.shift()) {
+        model.held_inputs.retain(|&held| held != input);
+    }
+}
+
+// /mute <board> <0|1> mutes a board's events; /solo <board> lets only that
+// board's events through, and /solo with no board clears it.
+// /gravity <board> <seconds> retunes a board's fall speed.
+// /sandbox <board> <0|1> turns a board's gravity back on or off.
+// /handicap <board> <float> multiplies a board's fall speed.
+// /garbage <board> <rows> sends rows of garbage, telegraphed before they rise.
+// /palette <board> <hue 0-1> [seconds] recolors a board around a hue, fading.
+fn handle_osc(model: &mut Model, message: &osc::Message) {
+    let board_id = match message.args.first() {
+        Some(osc::Type::String(id)) => Some(id.as_str()),
+        _ => None,
+    };
+
+    match (message.addr.as_str(), board_id) {
+        ("/mute", Some(id)) => {
+            let muted = !matches!(message.args.get(1), Some(osc::Type::Int(0)));
+            match model.boards.get_mut(id) {
+                Some(board) => board.set_emit(!muted),
+                None => println!("OSC: no board <{}> to mute", id),
+            }
+        }
+        ("/solo", id) => {
+            model.solo = id.map(str::to_owned);
+            for (board_id, board) in model.boards.iter_mut() {
+                board.set_soloed_out(is_soloed_out(&model.solo, board_id));
+            }
+        }
+        ("/gravity", Some(id)) => {
+            let Some(osc::Type::Float(secs)) = message.args.get(1) else {
+                return;
+            };
+            match model.boards.get_mut(id) {
+                Some(board) => board.set_gravity_interval(*secs),
+                None => println!("OSC: no board <{}> to retune", id),
+            }
+        }
+        ("/palette", Some(id)) => {
+            let Some(osc::Type::Float(hue)) = message.args.get(1) else {
+                return;
+            };
+            let secs = match message.args.get(2) {
+                Some(osc::Type::Float(secs)) => *secs,
+                _ => 0.0,
+            };
+            match model.boards.get_mut(id) {
+                Some(board) => {
+                    let palette = Palette::from_hue(*hue, board.palette().panel);
+                    board.set_palette(palette, secs);
+                }
+                None => println!("OSC: no board <{}> to recolor", id),
+            }
+        }
+        ("/garbage", Some(id)) => {
+            let Some(osc::Type::Int(rows)) = message.args.get(1) else {
+                return;
+            };
+            match model.boards.get_mut(id) {
+                Some(board) => board.queue_garbage((*rows).max(0) as usize),
+                None => println!("OSC: no board <{}> to send garbage to", id),
+            }
+        }
+        ("/handicap", Some(id)) => {
+            let Some(osc::Type::Float(multiplier)) = message.args.get(1) else {
+                return;
+            };
+            match model.boards.get_mut(id) {
+                Some(board) => board.set_gravity_multiplier(*multiplier),
+                None => println!("OSC: no board <{}> to handicap", id),
+            }
+        }
+        ("/sandbox", Some(id)) => {
+            let sandbox = !matches!(message.args.get(1), Some(osc::Type::Int(0)));
+            match model.boards.get_mut(id) {
+                Some(board) => board.set_gravity_enabled(!sandbox),
+                None => println!("OSC: no board <{}> to sandbox", id),
+            }
+        }
         _ => {}
     }
 }
 
+// Write each board's stack to board_<id>.png
+fn export_boards(model: &Model) {
+    for (id, board) in &model.boards {
+        let path = format!("board_{}.png", id);
+        match board.export_png(Path::new(&path), false) {
+            Ok(()) => println!("{}: exported to {}", id, path),
+            Err(e) => println!("{}: failed to export: {}", id, e),
+        }
+    }
+}
+
+fn save_session(model: &Model) {
+    let session = Session {
+        boards: model
+            .boards
+            .iter()
+            .map(|(id, board)| (id.clone(), board.board().state().clone()))
+            .collect(),
+    };
+
+    match session.save(Path::new(&model.session_path)) {
+        Ok(()) => println!("Session saved to {}", model.session_path),
+        Err(e) => println!("Failed to save session: {}", e),
+    }
+}
+
+fn load_session(model: &mut Model) {
+    let session = match Session::load(Path::new(&model.session_path)) {
+        Ok(session) => session,
+        Err(e) => {
+            println!("Failed to load session {}: {}", model.session_path, e);
+            return;
+        }
+    };
+
+    for (id, state) in session.boards {
+        let Some(board) = model.boards.get_mut(&id) else {
+            println!("No board {} to restore", id);
+            continue;
+        };
+
+        if board.restore(state) {
+            println!("Restored board {}", id);
+        } else {
+            println!("Saved board {} doesn't fit the current board size", id);
+        }
+    }
+}
+
+// Another board is soloed, so this one stays quiet
+fn is_soloed_out(solo: &Option<String>, id: &str) -> bool {
+    solo.as_deref().is_some_and(|solo| solo != id)
+}
+
+// Paused, a number key loads that save slot and Shift + number saves to it
+fn key_to_input(keymap: &Keymap, key: Key, shift: bool) -> Option<PlayerInput> {
+    keymap.input(key).or_else(|| {
+        digit_slot(key).map(|slot| {
+            if shift {
+                PlayerInput::SaveSlot(slot)
+            } else {
+                PlayerInput::LoadSlot(slot)
+            }
+        })
+    })
+}
+
+// Save slot of a number key, 1 - 9
+fn digit_slot(key: Key) -> Option<u8> {
+    let digits = [
+        Key::Key1,
+        Key::Key2,
+        Key::Key3,
+        Key::Key4,
+        Key::Key5,
+        Key::Key6,
+        Key::Key7,
+        Key::Key8,
+        Key::Key9,
+    ];
+    digits
+        .iter()
+        .position(|&digit| digit == key)
+        .map(|idx| idx as u8 + 1)
+}
+
 // ******************************* Rendering and Capture *****************************
 fn render_and_post(app: &App, model: &mut Model) {
     // Get the window device and queue
@@ -292,13 +897,34 @@ fn draw_fps(model: &Model) {
         .x_y(900.0, 520.0)
         .color(RED)
         .font_size(20);
+
+    let osc_status = if model.osc.osc_connected() {
+        "OSC: connected"
+    } else {
+        "OSC: reconnecting"
+    };
+    draw.text(osc_status)
+        .x_y(700.0, 520.0)
+        .color(RED)
+        .font_size(20);
+
+    if let Some(average) = model.latency.as_ref().and_then(LatencyMeter::average) {
+        draw.text(&format!("Input: {:.1} ms", average.as_secs_f32() * 1000.0))
+            .x_y(500.0, 520.0)
+            .color(RED)
+            .font_size(20);
+    }
 }
 
 fn draw_score(model: &Model) {
     let draw = &model.draw;
     let mut count = model.boards.len();
     for (board_name, board) in &model.boards {
-        draw.text(&format!("{}: {:?}", board_name, board.score()))
+        let mut score = format_score(board.score(), &model.score_format);
+        if board.is_dig_mode() {
+            score = format!("{} depth {}", score, board.max_dig_depth());
+        }
+        draw.text(&format!("{}: {}", board_name, score))
             .x_y(900.0, 520.0 - (25.0 * count as f32))
             .color(RED)
             .font_size(20);
@@ -306,6 +932,34 @@ fn draw_score(model: &Model) {
     }
 }
 
+fn draw_daily(model: &Model, date: &str, seed: u64) {
+    model
+        .draw
+        .text(&format!("Daily {}  seed {}", date, seed))
+        .x_y(0.0, 520.0)
+        .w(600.0)
+        .color(RED)
+        .font_size(20);
+}
+
+fn draw_bind_prompt(model: &Model, input: PlayerInput) {
+    let current = model
+        .keymap
+        .key_for(input)
+        .map_or("unbound".to_owned(), |key| format!("{:?}", key));
+    model
+        .draw
+        .text(&format!(
+            "BIND {} ({}): press a key -- Tab skips, F1 done",
+            action_name(input),
+            current
+        ))
+        .x_y(0.0, 490.0)
+        .w(900.0)
+        .color(RED)
+        .font_size(20);
+}
+
 fn init_fps(app: &App, model: &mut Model) {
     model.fps = 0.0;
     model.frame_count = 0;
@@ -3,24 +3,35 @@
 // The Tetris Board model
 // handles grid state and piece positioning
 
-use crate::views::{BoardPosition, PieceInstance, RotationDirection};
+use crate::{
+    config::ClearAxis,
+    models::{drop_placements, session::invalid_data, BoardSnapshot, Level, LevelCell},
+    views::{BoardPosition, PieceInstance, RotationDirection},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+};
 
 const DEBUG: bool = true;
 
 #[derive(PartialEq)]
 pub enum PlaceResult {
     PlaceOk,
-    RowFilled,
+    RowFilled, // a row, or a column when clearing columns, would be filled
     OutOfBounds,
     PlaceBad,
 }
 
 pub struct Board {
-    pub width: isize,                // overall width in cells
-    pub height: isize,               // overall height in cells
-    state: BoardState,               // grid state
-    backup_state: BoardState,        // previous grid state for testing positions
-    saved_state: Option<BoardState>, // saved state for pausing
+    pub width: isize,                      // overall width in cells
+    pub height: isize,                     // overall height in cells
+    state: BoardState,                     // grid state
+    backup_state: BoardState,              // previous grid state for testing positions
+    saved_states: HashMap<u8, BoardState>, // numbered save slots for practice
+    clear_axis: ClearAxis,                 // which full lines are cleared
+    wrap_x: bool,                          // left and right edges join, x taken modulo width
 }
 
 impl Board {
@@ -31,7 +42,34 @@ impl Board {
             height: height as isize,
             state: prev_state.clone(),
             backup_state: prev_state,
-            saved_state: None,
+            saved_states: HashMap::new(),
+            clear_axis: ClearAxis::Rows,
+            wrap_x: false,
+        }
+    }
+
+    pub fn set_clear_axis(&mut self, clear_axis: ClearAxis) {
+        self.clear_axis = clear_axis;
+    }
+
+    pub fn set_wrap_x(&mut self, wrap_x: bool) {
+        self.wrap_x = wrap_x;
+    }
+
+    pub fn wrap_x(&self) -> bool {
+        self.wrap_x
+    }
+
+    // The position on the board a cell lands on: x taken modulo width when
+    // wrapping, unchanged otherwise
+    pub fn wrap(&self, pos: BoardPosition) -> BoardPosition {
+        if self.wrap_x {
+            BoardPosition {
+                x: pos.x.rem_euclid(self.width),
+                y: pos.y,
+            }
+        } else {
+            pos
         }
     }
 
@@ -41,18 +79,12 @@ impl Board {
     pub fn try_place(&mut self, piece: &PieceInstance, board_pos: BoardPosition) -> PlaceResult {
         // First check if the piece's position is valid
 
-        for &(dx, dy) in piece.cells() {
-            let cell_pos = BoardPosition {
-                x: board_pos.x + dx,
-                y: board_pos.y + dy,
-            };
-
+        for cell_pos in piece.cells_at(board_pos) {
             if self.idx(cell_pos.x, cell_pos.y).is_none() {
                 if DEBUG {
                     println!(
                         "Try Position: {:?} is OOB -- cell at {:?}",
-                        board_pos,
-                        (dx, dy)
+                        board_pos, cell_pos
                     );
                 }
                 return PlaceResult::OutOfBounds;
@@ -62,8 +94,7 @@ impl Board {
                 if DEBUG {
                     println!(
                         "Try Position: {:?} is occupied -- cell at {:?}",
-                        board_pos,
-                        (dx, dy)
+                        board_pos, cell_pos
                     );
                 }
                 return PlaceResult::PlaceBad;
@@ -76,7 +107,7 @@ impl Board {
         // Check if cells would be filled
         let mut test_piece = piece.clone();
         test_piece.position = board_pos;
-        let row_filled = self.fills_row(&test_piece);
+        let row_filled = self.fills_row(&test_piece) || self.fills_col(&test_piece);
 
         // Unwind temporary changes
         std::mem::swap(&mut self.state, &mut self.backup_state);
@@ -92,11 +123,12 @@ impl Board {
         PlaceResult::PlaceOk
     }
 
+    // Position after rotating, and the index of the wall kick that fit (0 = no kick)
     pub fn try_rotation(
         &mut self,
         piece: &PieceInstance,
         rotation_direction: &RotationDirection,
-    ) -> Option<BoardPosition> {
+    ) -> Option<(BoardPosition, usize)> {
         // Save current position
         let current_pos = piece.position;
 
@@ -104,12 +136,16 @@ impl Board {
         let mut test_piece = piece.clone();
         test_piece.rotate(rotation_direction);
 
-        // First, try rotation at the current position (no wall kick needed)
-        if self.try_place(&test_piece, current_pos) == PlaceResult::PlaceOk {
+        // First, try rotation at the current position (no wall kick needed).
+        // A spot that completes a line is still a legal fit, as with moves.
+        if matches!(
+            self.try_place(&test_piece, current_pos),
+            PlaceResult::PlaceOk | PlaceResult::RowFilled
+        ) {
             if DEBUG {
                 println!("Rotation succeeded in original position");
             }
-            return Some(current_pos);
+            return Some((current_pos, 0));
         }
 
         // Get wall kick offsets for this rotation transition
@@ -118,19 +154,22 @@ impl Board {
             .wall_kick_offsets(piece.rot_idx, test_piece.rot_idx);
 
         // Try each offset, skipping [0,0] that was already tried)
-        for &(dx, dy) in offsets.iter().skip(1) {
+        for (kick_idx, &(dx, dy)) in offsets.iter().enumerate().skip(1) {
             let test_pos = BoardPosition {
                 x: current_pos.x + dx,
                 y: current_pos.y + dy,
             };
 
-            if self.try_place(&test_piece, test_pos) == PlaceResult::PlaceOk {
+            if matches!(
+                self.try_place(&test_piece, test_pos),
+                PlaceResult::PlaceOk | PlaceResult::RowFilled
+            ) {
                 // Success
                 if DEBUG {
                     println!("Rotation succeeded with wall kick to {:?}", test_pos);
                 }
 
-                return Some(test_pos);
+                return Some((test_pos, kick_idx));
             }
         }
 
@@ -144,27 +183,27 @@ impl Board {
 
     // Quick check that a piece would fill a row
     fn fills_row(&mut self, piece: &PieceInstance) -> bool {
-        piece.cells().iter().any(|&(dx, dy)| {
-            let cell_pos = BoardPosition {
-                x: piece.position.x + dx,
-                y: piece.position.y + dy,
-            };
+        piece
+            .absolute_cells()
+            .into_iter()
+            .any(|cell_pos| matches!(self.fill_cell(cell_pos), PlaceResult::RowFilled))
+    }
 
-            matches!(self.fill_cell(cell_pos), PlaceResult::RowFilled)
-        })
+    // Check, after a test fill, whether any column the piece touches is now full
+    fn fills_col(&self, piece: &PieceInstance) -> bool {
+        self.clear_axis.cols()
+            && piece
+                .absolute_cells()
+                .iter()
+                .any(|cell_pos| self.is_col_full(self.wrap(*cell_pos).x))
     }
 
     // commit all cells of a pre-validated piece, returns any a Vec of any filled rows
     pub fn commit_piece(&mut self, piece: &PieceInstance) -> Option<Vec<isize>> {
         let filled_rows = piece
-            .cells()
-            .iter()
-            .filter_map(|&(dx, dy)| {
-                let cell_pos = BoardPosition {
-                    x: piece.position.x + dx,
-                    y: piece.position.y + dy,
-                };
-
+            .absolute_cells()
+            .into_iter()
+            .filter_map(|cell_pos| {
                 // Remember the y-index of each row that has been filled
                 (self.fill_cell(cell_pos) == PlaceResult::RowFilled).then_some(cell_pos.y)
             })
@@ -173,15 +212,48 @@ impl Board {
         (!filled_rows.is_empty()).then_some(filled_rows)
     }
 
+    // Test helper: commit a piece at the given position without clearing any filled rows
+    #[cfg(test)]
+    pub fn commit_piece_at(
+        &mut self,
+        piece: &PieceInstance,
+        pos: BoardPosition,
+    ) -> Option<Vec<isize>> {
+        let mut piece = piece.clone();
+        piece.position = pos;
+        self.commit_piece(&piece)
+    }
+
+    // Test helper: lay out the grid from text, top row first, '#' filled and '.' empty.
+    // Missing rows above the text are empty.
+    #[cfg(test)]
+    pub fn set_grid_ascii(&mut self, grid_ascii: &str) {
+        let rows: Vec<&str> = grid_ascii
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        self.state.grid = vec![false; (self.width * self.height) as usize];
+        for (y, line) in rows.iter().rev().enumerate() {
+            for (x, cell) in line.chars().enumerate() {
+                if let Some(idx) = self.idx(x as isize, y as isize) {
+                    self.state.grid[idx] = cell == '#';
+                }
+            }
+        }
+        self.rebuild_scores();
+    }
+
     // Fill the cell in the Grid abstraction & update the col/row scores
     fn fill_cell(&mut self, pos: BoardPosition) -> PlaceResult {
+        let pos = self.wrap(pos);
         self.idx(pos.x, pos.y)
             .map(|idx| {
                 self.state.grid[idx] = true;
                 self.state.update_col_score(pos);
 
                 // Notice if the row has been filled while updating row score
-                if self.state.update_row_score(pos) == self.width {
+                if self.state.update_row_score(pos) == self.width && self.clear_axis.rows() {
                     PlaceResult::RowFilled
                 } else {
                     PlaceResult::PlaceOk
@@ -199,11 +271,22 @@ impl Board {
 
     /************************ Piece Drop *******************************/
 
+    // Every (rotation, position) the piece can come to rest at by rotating and
+    // shifting above the stack, then dropping. Tucks and spins aren't searched.
+    // Leaves the board untouched, for hints and autoplay.
+    pub fn legal_placements(&self, piece: &PieceInstance) -> Vec<(usize, BoardPosition)> {
+        drop_placements(self.grid(), self.width, self.height, piece.typ)
+            .into_iter()
+            .map(|p| (p.rot_idx, BoardPosition { x: p.x, y: p.y }))
+            .collect()
+    }
+
     // Find the lowest legal place for piece in its current x-position
     // This is the normal route and uses a quick calculation using col_score
     pub fn calculate_drop(&mut self, piece: &PieceInstance) -> (BoardPosition, PlaceResult) {
-        // Use brute force method if piece is below overhang (col_score not useful)
-        if self.is_below_overhang(piece) {
+        // Use brute force method if piece is below overhang (col_score not useful),
+        // or wraps, where its columns aren't a contiguous range
+        if self.wrap_x || self.is_below_overhang(piece) {
             return self.slow_calculate_drop(piece);
         }
 
@@ -293,13 +376,16 @@ impl Board {
     }
 
     // Take a drop location and test for collisions. If collision, move up 1 row
-    // and try again until no collisions remain.
+    // and try again until no collisions remain. A grid the piece can't fit anywhere
+    // above the drop location (e.g. a malformed loaded state) gives up after the
+    // board's height and leaves the piece where it is.
     fn verify_drop_location(
         &mut self,
         piece: &PieceInstance,
         mut pos: BoardPosition,
     ) -> (BoardPosition, PlaceResult) {
-        loop {
+        let start = pos;
+        for _ in 0..=self.height {
             if DEBUG {
                 println!("  Verification: {:?}", pos);
             }
@@ -319,15 +405,18 @@ impl Board {
                 }
             }
         }
+
+        if DEBUG {
+            println!(
+                "Drop verification found no fit for {:?} above {:?}",
+                piece.typ, start
+            );
+        }
+        (piece.position, PlaceResult::PlaceBad)
     }
 
     fn is_below_overhang(&self, piece: &PieceInstance) -> bool {
-        piece.cells().iter().any(|&(dx, dy)| {
-            let cell_pos = BoardPosition {
-                x: piece.position.x + dx,
-                y: piece.position.y + dy,
-            };
-
+        piece.absolute_cells().into_iter().any(|cell_pos| {
             // Check if this cell is below an overhang
             if let Some(score) = self.col_score(cell_pos.x) {
                 return cell_pos.y < (score - 1);
@@ -358,6 +447,139 @@ impl Board {
         }
     }
 
+    pub fn full_rows(&self) -> Vec<isize> {
+        if !self.clear_axis.rows() {
+            return Vec::new();
+        }
+        (0..self.height)
+            .filter(|&y| self.row_score(y) == Some(self.width))
+            .collect()
+    }
+
+    /************************ Column clearing functions ***************************/
+
+    pub fn full_cols(&self) -> Vec<isize> {
+        if !self.clear_axis.cols() {
+            return Vec::new();
+        }
+        (0..self.width).filter(|&x| self.is_col_full(x)).collect()
+    }
+
+    // Rows and columns these cells would complete once filled, on the clear axis,
+    // each bottom or left first
+    pub fn lines_completed_by(&self, cells: &[BoardPosition]) -> (Vec<isize>, Vec<isize>) {
+        let cells: Vec<BoardPosition> = cells.iter().map(|&cell| self.wrap(cell)).collect();
+        let filled = |pos: BoardPosition| cells.contains(&pos) || self.is_cell_filled(pos);
+
+        let mut rows: Vec<isize> = cells.iter().map(|cell| cell.y).collect();
+        rows.sort_unstable();
+        rows.dedup();
+        rows.retain(|&y| {
+            self.clear_axis.rows()
+                && y >= 0
+                && y < self.height
+                && (0..self.width).all(|x| filled(BoardPosition { x, y }))
+        });
+
+        let mut cols: Vec<isize> = cells.iter().map(|cell| cell.x).collect();
+        cols.sort_unstable();
+        cols.dedup();
+        cols.retain(|&x| {
+            self.clear_axis.cols()
+                && x >= 0
+                && x < self.width
+                && (0..self.height).all(|y| filled(BoardPosition { x, y }))
+        });
+
+        (rows, cols)
+    }
+
+    fn is_col_full(&self, x: isize) -> bool {
+        x >= 0
+            && x < self.width
+            && (0..self.height).all(|y| self.is_cell_filled(BoardPosition { x, y }))
+    }
+
+    // Remove the columns; every column to their right slides left to close the gap
+    pub fn clear_cols(&mut self, cols: &[isize]) {
+        if DEBUG {
+            println!("Cols to clear: {:?}", cols)
+        }
+
+        let kept: Vec<isize> = (0..self.width).filter(|x| !cols.contains(x)).collect();
+        let mut grid = vec![false; self.state.grid.len()];
+        for (new_x, &old_x) in kept.iter().enumerate() {
+            for y in 0..self.height {
+                if let Some(idx) = self.idx(new_x as isize, y) {
+                    grid[idx] = self.is_cell_filled(BoardPosition { x: old_x, y });
+                }
+            }
+        }
+        self.state.grid = grid;
+
+        self.rebuild_scores();
+    }
+
+    // Empty the highest `rows` rows that hold any filled cell, without sliding
+    pub fn clear_top_rows(&mut self, rows: usize) {
+        let top = *self.col_score_all().iter().max().unwrap_or(&0);
+        for y in (top - rows as isize).max(0)..top {
+            self.clear_row(y);
+        }
+        self.rebuild_scores();
+    }
+
+    // Turn the stack upside down. The reversed rows settle as whole rows, the way
+    // rows slide after a clear, so the empty rows the stack lands on close up.
+    pub fn flip_vertical(&mut self) {
+        let width = self.width as usize;
+        let rows: Vec<Vec<bool>> = self
+            .state
+            .grid
+            .chunks(width)
+            .rev()
+            .filter(|row| row.contains(&true))
+            .map(<[bool]>::to_vec)
+            .collect();
+
+        self.state.grid = vec![false; self.state.grid.len()];
+        for (y, row) in rows.iter().enumerate() {
+            self.state.grid[y * width..(y + 1) * width].copy_from_slice(row);
+        }
+        self.rebuild_scores();
+    }
+
+    // Push the stack up and fill the bottom rows, leaving one hole column.
+    // Returns false if the stack was pushed out the top.
+    pub fn add_garbage(&mut self, rows: usize, hole: isize) -> bool {
+        let width = self.width as usize;
+        let shift = (rows * width).min(self.state.grid.len());
+        let fits = !self.state.grid[self.state.grid.len() - shift..].contains(&true);
+
+        self.state.grid.rotate_right(shift);
+        for (i, cell) in self.state.grid[..shift].iter_mut().enumerate() {
+            *cell = (i % width) as isize != hole;
+        }
+        self.rebuild_scores();
+        fits
+    }
+
+    // Recount row and column scores from the grid
+    fn rebuild_scores(&mut self) {
+        for y in 0..self.height {
+            self.state.row_score[y as usize] = (0..self.width)
+                .filter(|&x| self.is_cell_filled(BoardPosition { x, y }))
+                .count() as isize;
+        }
+
+        for x in 0..self.width {
+            self.state.col_score[x as usize] = (0..self.height)
+                .rev()
+                .find(|&y| self.is_cell_filled(BoardPosition { x, y }))
+                .map_or(0, |y| y + 1);
+        }
+    }
+
     // Handle row sliding based on a row's position
     fn handle_sliding(&mut self, cleared_rows: &[isize]) {
         if cleared_rows.is_empty() {
@@ -408,9 +630,12 @@ impl Board {
 
             // Move all the cells down
             self.slide_row_down(row, count);
+        }
 
-            // Now that the cells have been moved, clear the row.
-            self.clear_row(row)
+        // The stack is now `count` rows shorter: empty the rows at its old top,
+        // which nothing slid into
+        for row in (highest_filled_row - count).max(0)..highest_filled_row {
+            self.clear_row(row);
         }
     }
 
@@ -436,7 +661,7 @@ impl Board {
     }
 
     // Clear a row completely
-    fn clear_row(&mut self, row: isize) {
+    pub fn clear_row(&mut self, row: isize) {
         for x in 0..self.width {
             if let Some(idx) = self.idx(x, row) {
                 self.state.grid[idx] = false;
@@ -476,24 +701,41 @@ impl Board {
         self.state.player_score()
     }
 
-    pub fn score_cleared_rows(&mut self, number_of_rows: usize) -> usize {
-        let delta: usize = match number_of_rows {
+    // Points for one clear. The base for the number of lines is shared out
+    // per line, and each row's share is scaled by its weight; columns count 1.0.
+    pub fn score_cleared_rows(
+        &mut self,
+        rows: &[isize],
+        cols: usize,
+        row_weights: &[f32],
+    ) -> usize {
+        let lines = (rows.len() + cols).min(4);
+        let base: usize = match lines {
             1 => 100,
             2 => 200,
             3 => 500,
             4 => 800,
             _ => 0, // anything else is not a valid row clearing
         };
+        if base == 0 {
+            return 0;
+        }
 
-        self.add_score(delta)
+        let share = base as f32 / (rows.len() + cols) as f32;
+        let weight = |row: isize| row_weights.get(row as usize).copied().unwrap_or(1.0);
+        let weighted: f32 =
+            rows.iter().map(|&row| share * weight(row)).sum::<f32>() + share * cols as f32;
+
+        self.add_score(weighted.round() as usize)
     }
 
-    pub fn score_piece(&mut self, piece: &PieceInstance, hard_drop: bool) -> usize {
-        if hard_drop {
-            self.add_score(piece.cells().len() * 2)
-        } else {
-            self.add_score(piece.cells().len())
-        }
+    pub fn score_piece(&mut self, piece: &PieceInstance) -> usize {
+        self.add_score(piece.cells().len())
+    }
+
+    // Drop distance points
+    pub fn score_drop(&mut self, cells: usize, points_per_cell: usize) -> usize {
+        self.add_score(cells * points_per_cell)
     }
 
     pub fn add_score(&mut self, delta: usize) -> usize {
@@ -512,6 +754,11 @@ impl Board {
     // row-ordered 2D to 1D indexing
     #[inline]
     fn idx(&self, x: isize, y: isize) -> Option<usize> {
+        let x = if self.wrap_x {
+            x.rem_euclid(self.width)
+        } else {
+            x
+        };
         // Check bounds first (including negative values)
         if x < 0 || y < 0 || x >= self.width || y >= self.height {
             return None;
@@ -549,19 +796,111 @@ impl Board {
         &self.state.col_score
     }
 
-    pub fn save_state(&mut self) {
-        self.saved_state = Some(self.state.clone());
+    // Share of all cells that are filled, 0.0 empty to 1.0 full
+    pub fn fill_ratio(&self) -> f32 {
+        let filled = self.state.grid.iter().filter(|&&cell| cell).count();
+        filled as f32 / self.state.grid.len().max(1) as f32
+    }
+
+    // row-ordered filled state of every cell
+    pub fn grid(&self) -> &[bool] {
+        &self.state.grid
+    }
+
+    // Locked cells that changed since `prev`, with their new state, row by row
+    // from the bottom. A snapshot of another size changes every cell.
+    // The active piece isn't the board's; BoardInstance::diff adds its cells.
+    pub fn diff(&self, prev: &BoardSnapshot) -> Vec<(BoardPosition, bool)> {
+        let resized = (self.width, self.height) != (prev.width, prev.height);
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| BoardPosition { x, y }))
+            .filter_map(|pos| {
+                let filled = self.is_cell_filled(pos);
+                let was_filled = prev.grid.get((pos.y * prev.width + pos.x) as usize);
+                (resized || was_filled != Some(&filled)).then_some((pos, filled))
+            })
+            .collect()
+    }
+
+    pub fn save_state(&mut self, slot: u8) {
+        self.saved_states.insert(slot, self.state.clone());
+    }
+
+    // Returns false if nothing was saved in the slot
+    pub fn resume_state(&mut self, slot: u8) -> bool {
+        let Some(state) = self.saved_states.get(&slot) else {
+            return false;
+        };
+        self.state = state.clone();
+        true
+    }
+
+    pub fn state(&self) -> &BoardState {
+        &self.state
     }
 
-    pub fn resume_state(&mut self) {
-        if let Some(state) = &self.saved_state {
-            self.state = state.clone();
+    // The layout as level editor JSON, see models/level.rs
+    pub fn to_level_json(&self, metadata: BTreeMap<String, String>) -> serde_json::Result<String> {
+        let cells = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| BoardPosition { x, y }))
+            .filter(|&pos| self.is_cell_filled(pos))
+            .map(|pos| LevelCell {
+                x: pos.x,
+                y: pos.y,
+                color: None,
+            })
+            .collect();
+        let level = Level {
+            width: self.width,
+            height: self.height,
+            cells,
+            metadata,
+        };
+        serde_json::to_string_pretty(&level)
+    }
+
+    // Replace the grid with a level editor layout, returning its metadata.
+    // A level of another size, or with cells off the board, is rejected and
+    // the grid left as it was. The score is kept.
+    pub fn from_level_json(&mut self, json: &str) -> io::Result<BTreeMap<String, String>> {
+        let level: Level = serde_json::from_str(json).map_err(invalid_data)?;
+        if (level.width, level.height) != (self.width, self.height) {
+            return Err(invalid_data(format!(
+                "level is {}x{}, board is {}x{}",
+                level.width, level.height, self.width, self.height
+            )));
+        }
+
+        let mut grid = vec![false; self.state.grid.len()];
+        for cell in &level.cells {
+            // Checked here rather than by idx(), which folds x back on with wrap_x
+            if !(0..self.width).contains(&cell.x) || !(0..self.height).contains(&cell.y) {
+                return Err(invalid_data(format!(
+                    "cell ({}, {}) is off the board",
+                    cell.x, cell.y
+                )));
+            }
+            grid[(cell.y * self.width + cell.x) as usize] = true;
+        }
+        self.state.grid = grid;
+        self.rebuild_scores();
+        Ok(level.metadata)
+    }
+
+    // Replace the grid with a saved one. Returns false if its size doesn't match.
+    pub fn restore(&mut self, state: BoardState) -> bool {
+        let fits = state.grid.len() == (self.width * self.height) as usize
+            && state.row_score.len() == self.height as usize
+            && state.col_score.len() == self.width as usize;
+        if fits {
+            self.state = state;
         }
+        fits
     }
 }
 
-#[derive(Debug, Clone)]
-struct BoardState {
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoardState {
     grid: Vec<bool>,       // which cells are filled
     player_score: usize,   // player score
     row_score: Vec<isize>, // how many cells are filled in each row
@@ -603,3 +942,394 @@ impl BoardState {
         delta
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PieceType;
+    use nannou::prelude::*;
+
+    fn piece(typ: PieceType, rot_idx: usize) -> PieceInstance {
+        let mut piece =
+            PieceInstance::new(typ, rgba(1.0, 1.0, 1.0, 1.0), BoardPosition { x: 0, y: 0 });
+        piece.rot_idx = rot_idx;
+        piece
+    }
+
+    fn filled_cells(board: &Board) -> usize {
+        (0..board.height)
+            .flat_map(|y| (0..board.width).map(move |x| BoardPosition { x, y }))
+            .filter(|&pos| board.is_cell_filled(pos))
+            .count()
+    }
+
+    fn fill_rows(board: &mut Board, rows: std::ops::Range<isize>) {
+        for y in rows {
+            for x in 0..board.width {
+                board.fill_cell(BoardPosition { x, y });
+            }
+        }
+    }
+
+    #[test]
+    fn test_diff_has_only_the_placed_cells() {
+        let mut board = Board::new(6, 4);
+        fill_rows(&mut board, 0..1);
+        let prev = BoardSnapshot {
+            width: board.width,
+            height: board.height,
+            grid: board.grid().to_vec(),
+            active_cells: vec![(0, 1)],
+            score: 0,
+        };
+        assert!(board.diff(&prev).is_empty());
+
+        // The snapshot's active cell isn't the board's to report
+        board.commit_piece_at(&piece(PieceType::O, 0), BoardPosition { x: 2, y: 1 });
+        let placed = [(2, 1), (3, 1), (2, 2), (3, 2)]
+            .map(|(x, y)| (BoardPosition { x, y }, true))
+            .to_vec();
+        assert_eq!(board.diff(&prev), placed);
+    }
+
+    #[test]
+    fn test_clear_empties_vacated_top_rows() {
+        // A lone full row has nothing above it to slide in
+        let mut board = Board::new(4, 6);
+        fill_rows(&mut board, 0..1);
+        board.clear_rows(&board.full_rows());
+        assert_eq!(filled_cells(&board), 0);
+
+        // Two full rows under a single cell: the cell lands on the floor
+        let mut board = Board::new(4, 6);
+        fill_rows(&mut board, 0..2);
+        board.fill_cell(BoardPosition { x: 0, y: 2 });
+        board.clear_rows(&board.full_rows());
+        assert!(board.is_cell_filled(BoardPosition { x: 0, y: 0 }));
+        assert_eq!(filled_cells(&board), 1);
+        assert!(board.full_rows().is_empty());
+    }
+
+    // 6x4 board: an O piece in columns 2-3, and a vertical I ready for column 1
+    fn board_with_axis(clear_axis: ClearAxis) -> Board {
+        let mut board = Board::new(6, 4);
+        board.set_clear_axis(clear_axis);
+        board.commit_piece_at(&piece(PieceType::O, 0), BoardPosition { x: 2, y: 0 });
+        board
+    }
+
+    // Rotate a piece resting at `at` on the grid; the landing spot and kick used
+    fn spin(
+        grid: &str,
+        typ: PieceType,
+        rot_idx: usize,
+        at: BoardPosition,
+        dir: RotationDirection,
+    ) -> Option<(BoardPosition, usize)> {
+        let mut board = Board::new(10, 6);
+        board.set_grid_ascii(grid);
+        let mut piece = piece(typ, rot_idx);
+        piece.position = at;
+        board.try_rotation(&piece, &dir)
+    }
+
+    #[test]
+    fn test_z_spin_into_overhang() {
+        // Upright Z hanging over the slot kicks down and right under the overhang
+        let slot = "
+            ##..#####.
+            #..######.
+            ";
+        let landed = spin(
+            slot,
+            PieceType::Z,
+            1,
+            BoardPosition { x: 0, y: 1 },
+            RotationDirection::Ccw,
+        );
+        assert_eq!(landed, Some((BoardPosition { x: 1, y: 0 }, 2)));
+
+        // Same spin completing both rows
+        let double = "
+            ##..######
+            #..#######
+            ";
+        let landed = spin(
+            double,
+            PieceType::Z,
+            1,
+            BoardPosition { x: 0, y: 1 },
+            RotationDirection::Ccw,
+        );
+        assert_eq!(landed, Some((BoardPosition { x: 1, y: 0 }, 2)));
+    }
+
+    #[test]
+    fn test_s_spin_into_overhang() {
+        // Mirror image: upright S kicks down and left under the overhang
+        let slot = "
+            .#####..##
+            .######..#
+            ";
+        let landed = spin(
+            slot,
+            PieceType::S,
+            3,
+            BoardPosition { x: 7, y: 1 },
+            RotationDirection::Cw,
+        );
+        assert_eq!(landed, Some((BoardPosition { x: 6, y: 0 }, 2)));
+
+        let double = "
+            ######..##
+            #######..#
+            ";
+        let landed = spin(
+            double,
+            PieceType::S,
+            3,
+            BoardPosition { x: 7, y: 1 },
+            RotationDirection::Cw,
+        );
+        assert_eq!(landed, Some((BoardPosition { x: 6, y: 0 }, 2)));
+    }
+
+    #[test]
+    fn test_fill_ratio() {
+        let mut board = Board::new(4, 4);
+        assert_eq!(board.fill_ratio(), 0.0);
+
+        board.set_grid_ascii(
+            "
+            #.#.
+            ##..
+            #..#
+            .##.
+            ",
+        );
+        assert_eq!(board.fill_ratio(), 0.5);
+    }
+
+    #[test]
+    fn test_legal_placements_i_piece() {
+        // Flat in 7 columns, upright in 10; the two flat and two upright
+        // rotations cover the same cells
+        let mut board = Board::new(10, 20);
+        let placements = board.legal_placements(&piece(PieceType::I, 0));
+        assert_eq!(placements.len(), 17);
+        assert!(placements.contains(&(0, BoardPosition { x: 6, y: 0 })));
+        assert!(placements.contains(&(1, BoardPosition { x: -2, y: 0 })));
+
+        // Resting on a block: the flat I over column 0 sits a row up
+        board.set_grid_ascii(
+            "
+            #.........
+            ",
+        );
+        let placements = board.legal_placements(&piece(PieceType::I, 0));
+        assert!(placements.contains(&(0, BoardPosition { x: 0, y: 1 })));
+        assert_eq!(placements.len(), 17);
+    }
+
+    #[test]
+    fn test_flip_vertical_mirrors_and_settles() {
+        let mut board = Board::new(4, 6);
+        board.set_grid_ascii(
+            "
+            .#..
+            ....
+            ##.#
+            ###.
+            ",
+        );
+        board.flip_vertical();
+
+        let mut expected = Board::new(4, 6);
+        expected.set_grid_ascii(
+            "
+            ###.
+            ##.#
+            .#..
+            ",
+        );
+        assert_eq!(board.grid(), expected.grid());
+        assert_eq!(board.col_score_all(), &vec![3, 3, 3, 2]);
+        assert_eq!(board.row_score(0), Some(1));
+        assert_eq!(board.row_score(3), Some(0));
+    }
+
+    #[test]
+    fn test_add_garbage_raises_stack() {
+        let mut board = Board::new(4, 4);
+        board.set_grid_ascii(
+            "
+            .##.
+            ",
+        );
+        assert!(board.add_garbage(2, 1));
+
+        let mut expected = Board::new(4, 4);
+        expected.set_grid_ascii(
+            "
+            .##.
+            #.##
+            #.##
+            ",
+        );
+        assert_eq!(board.grid(), expected.grid());
+        assert_eq!(board.col_score_all(), &vec![2, 3, 3, 2]);
+
+        // Two more rows push the top row out
+        assert!(!board.add_garbage(2, 0));
+    }
+
+    #[test]
+    fn test_verify_drop_gives_up_on_unfittable_grid() {
+        let mut board = Board::new(6, 4);
+        board.set_grid_ascii(
+            "
+            ######
+            ######
+            ######
+            ######
+            ",
+        );
+        let mut o_piece = piece(PieceType::O, 0);
+        o_piece.position = BoardPosition { x: 2, y: 2 };
+        let (pos, result) = board.verify_drop_location(&o_piece, BoardPosition { x: 2, y: 0 });
+        assert!(result == PlaceResult::PlaceBad);
+        assert_eq!(pos, o_piece.position);
+    }
+
+    #[test]
+    fn test_height_bonus_favors_high_rows() {
+        use crate::config::RowScoreWeight;
+
+        let weights = RowScoreWeight::HeightBonus.weights(20, &[]);
+        let mut low = Board::new(10, 20);
+        let mut high = Board::new(10, 20);
+        let low_points = low.score_cleared_rows(&[0], 0, &weights);
+        let high_points = high.score_cleared_rows(&[15], 0, &weights);
+        assert_eq!(low_points, 100);
+        assert!(high_points > low_points);
+
+        // Flat weighting keeps the classic table
+        let flat = RowScoreWeight::Flat.weights(20, &[]);
+        assert_eq!(Board::new(10, 20).score_cleared_rows(&[15], 0, &flat), 100);
+        assert_eq!(
+            Board::new(10, 20).score_cleared_rows(&[0, 1, 2, 3], 0, &flat),
+            800
+        );
+    }
+
+    #[test]
+    fn test_rotation_reports_kick_index() {
+        let mut board = Board::new(10, 20);
+
+        // Open space: rotates in place
+        let mut t_piece = piece(PieceType::T, 0);
+        t_piece.position = BoardPosition { x: 4, y: 8 };
+        let (pos, kick_idx) = board
+            .try_rotation(&t_piece, &RotationDirection::Cw)
+            .unwrap();
+        assert_eq!(kick_idx, 0);
+        assert_eq!((pos.x, pos.y), (4, 8));
+
+        // Vertical I in column 0: lying flat in place would leave the board
+        let mut i_piece = piece(PieceType::I, 1);
+        i_piece.position = BoardPosition { x: -2, y: 8 };
+        let (pos, kick_idx) = board
+            .try_rotation(&i_piece, &RotationDirection::Cw)
+            .unwrap();
+        assert!(kick_idx > 0);
+        let (dx, dy) = PieceType::I.wall_kick_offsets(1, 2)[kick_idx];
+        assert_eq!((pos.x, pos.y), (-2 + dx, 8 + dy));
+    }
+
+    #[test]
+    fn test_column_clear_slides_left() {
+        let mut board = board_with_axis(ClearAxis::Cols);
+
+        // The vertical I's cells sit at dx = 2
+        let i_piece = piece(PieceType::I, 1);
+        let i_pos = BoardPosition { x: -1, y: 0 };
+        assert!(board.try_place(&i_piece, i_pos) == PlaceResult::RowFilled);
+
+        // Column clears don't report rows
+        assert_eq!(board.commit_piece_at(&i_piece, i_pos), None);
+        assert_eq!(board.full_cols(), vec![1]);
+
+        board.clear_cols(&[1]);
+        assert!(board.full_cols().is_empty());
+
+        // The O piece slid one column left
+        let filled: Vec<(isize, isize)> = (0..board.height)
+            .flat_map(|y| (0..board.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| board.is_cell_filled(BoardPosition { x, y }))
+            .collect();
+        assert_eq!(filled, vec![(1, 0), (2, 0), (1, 1), (2, 1)]);
+        assert_eq!(board.col_score_all(), &vec![0, 2, 2, 0, 0, 0]);
+        assert_eq!(board.row_score(0), Some(2));
+        assert_eq!(board.row_score(2), Some(0));
+    }
+
+    #[test]
+    fn test_rows_axis_ignores_full_columns() {
+        let mut board = board_with_axis(ClearAxis::Rows);
+        let i_piece = piece(PieceType::I, 1);
+        let i_pos = BoardPosition { x: -1, y: 0 };
+        assert!(board.try_place(&i_piece, i_pos) == PlaceResult::PlaceOk);
+        board.commit_piece_at(&i_piece, i_pos);
+        assert!(board.full_cols().is_empty());
+    }
+
+    #[test]
+    fn test_level_json_round_trip() {
+        let mut board = Board::new(6, 4);
+        board.set_grid_ascii(
+            "
+            #.....
+            ##..#.
+            ",
+        );
+        let metadata = BTreeMap::from([("name".to_string(), "steps".to_string())]);
+        let json = board.to_level_json(metadata.clone()).unwrap();
+
+        let mut loaded = Board::new(6, 4);
+        assert_eq!(loaded.from_level_json(&json).unwrap(), metadata);
+        assert_eq!(loaded.grid(), board.grid());
+        assert_eq!(loaded.row_score(0), Some(3));
+        assert_eq!(loaded.col_score(0), Some(2));
+
+        // Another size is rejected and the grid kept
+        let mut other = Board::new(10, 20);
+        assert!(other.from_level_json(&json).is_err());
+        assert_eq!(other.fill_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_level_cells_off_the_board_are_rejected_with_wrap_x() {
+        let mut board = Board::new(6, 4);
+        board.set_wrap_x(true);
+        board.set_grid_ascii("#.....");
+
+        for x in [-1, 6] {
+            let level = Level {
+                width: 6,
+                height: 4,
+                cells: vec![LevelCell {
+                    x,
+                    y: 0,
+                    color: None,
+                }],
+                metadata: BTreeMap::new(),
+            };
+            let json = serde_json::to_string(&level).unwrap();
+            let err = board.from_level_json(&json).unwrap_err();
+            assert_eq!(err.to_string(), format!("cell ({}, 0) is off the board", x));
+            // The grid is left as it was
+            assert_eq!(filled_cells(&board), 1);
+            assert!(board.grid()[0]);
+        }
+    }
+}
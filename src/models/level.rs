@@ -0,0 +1,35 @@
+// src/models/level.rs
+//
+// Board layouts for external level editors, as JSON:
+//
+//   {
+//     "width": 10,
+//     "height": 20,
+//     "cells": [{ "x": 0, "y": 0 }, { "x": 1, "y": 0, "color": [1.0, 0.5, 0.0, 1.0] }],
+//     "metadata": { "name": "well" }
+//   }
+//
+// Cells are the filled ones, y = 0 the bottom row. `color` is an optional
+// RGBA for editors; the board draws every locked cell in its own color, so
+// it's dropped on import and left out on export. Only the layout is stored:
+// no score or active piece, unlike a Session save.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Level {
+    pub width: isize,
+    pub height: isize,
+    pub cells: Vec<LevelCell>,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelCell {
+    pub x: isize,
+    pub y: isize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<[f32; 4]>,
+}
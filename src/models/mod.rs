@@ -1,8 +1,20 @@
 // src/models/mod.rs
 
 pub mod board;
+pub mod level;
 pub mod piece;
+pub mod placement;
+pub mod randomizer;
+pub mod replay;
+pub mod session;
+pub mod snapshot;
 pub mod wall_kick;
 
-pub use board::{Board, PlaceResult};
+pub use board::{Board, BoardState, PlaceResult};
+pub use level::{Level, LevelCell};
 pub use piece::PieceType;
+pub use placement::{best_placement, creates_hole, drop_placements, Placement};
+pub use randomizer::{new_randomizer, PieceRandomizer, SharedSequence};
+pub use replay::Replay;
+pub use session::{SaveFormat, Session};
+pub use snapshot::BoardSnapshot;
@@ -8,11 +8,13 @@
 use crate::models::wall_kick::{
     get_wall_kick_index, WallKickOffset, I_WALL_KICKS, JLSZT_WALL_KICKS, O_WALL_KICKS,
 };
+use nannou::prelude::*;
+use serde::Deserialize;
 
 // Type alias for a Tetromino block
 type Block = (isize, isize);
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
 pub enum PieceType {
     I,
     J,
@@ -90,6 +92,16 @@ impl PieceType {
         piece.iter().map(|&(_, y)| y).max().unwrap()
     }
 
+    // Each piece type's hue (0.0 - 1.0), spread around the color wheel
+    pub fn hue(&self) -> f32 {
+        let idx = Self::ALL.iter().position(|typ| typ == self).unwrap_or(0);
+        idx as f32 / Self::ALL.len() as f32
+    }
+
+    pub fn color(&self) -> Rgba {
+        hsva(self.hue(), 1.0, 0.75, 1.0).into()
+    }
+
     /******************* Utility Methods ******************/
     const ALL: [PieceType; 7] = [
         PieceType::I,
@@ -113,6 +125,23 @@ impl PieceType {
     pub const fn rotation_count(&self) -> usize {
         self.rotations().len()
     }
+
+    // The point the piece turns about, in cells from the center of block (0,0).
+    // JLSZT turn about their middle block, the O about the corner its blocks
+    // share, and the I about the gap between its middle two blocks.
+    pub fn pivot(&self, rot_idx: usize) -> (f32, f32) {
+        match self {
+            PieceType::I => {
+                let blocks = self.get_rotation(rot_idx);
+                let (sum_x, sum_y) = blocks
+                    .iter()
+                    .fold((0, 0), |(sx, sy), &(x, y)| (sx + x, sy + y));
+                (sum_x as f32 / 4.0, sum_y as f32 / 4.0)
+            }
+            PieceType::O => (0.5, 0.5),
+            _ => (1.0, 1.0),
+        }
+    }
 }
 
 /******************* Piece Rotation Definitions ******************/
@@ -172,6 +201,21 @@ const O_ROTATIONS: [[Block; 4]; 4] = [
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_pivot() {
+        assert_eq!(PieceType::I.pivot(0), (1.5, 0.0));
+        assert_eq!(PieceType::I.pivot(1), (2.0, 1.5));
+        assert_eq!(PieceType::T.pivot(3), (1.0, 1.0));
+        assert_eq!(PieceType::O.pivot(2), (0.5, 0.5));
+
+        // JLSZT keep a block on the pivot through every rotation
+        for piece in [PieceType::J, PieceType::L, PieceType::S, PieceType::Z] {
+            for rot_idx in 0..piece.rotation_count() {
+                assert!(piece.get_rotation(rot_idx).contains(&(1, 1)));
+            }
+        }
+    }
+
     #[test]
     fn test_piece_skirt() {
         // Test each piece type
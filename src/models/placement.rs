@@ -0,0 +1,136 @@
+// src/models/placement.rs
+//
+// Placement search: tries every rotation and column for a piece,
+// drops it straight down and rates the resulting grid
+
+use crate::models::PieceType;
+
+// Heuristic weights, per rated feature of the grid after placing
+const HEIGHT_WEIGHT: f32 = -0.51;
+const LINES_WEIGHT: f32 = 0.76;
+const HOLES_WEIGHT: f32 = -0.36;
+const BUMPINESS_WEIGHT: f32 = -0.18;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Placement {
+    pub rot_idx: usize,
+    pub x: isize, // piece origin, same as BoardPosition
+    pub y: isize,
+}
+
+impl Placement {
+    // Board positions of the placed piece's cells
+    pub fn cells(&self, piece_type: PieceType) -> Vec<(isize, isize)> {
+        piece_type
+            .get_rotation(self.rot_idx)
+            .iter()
+            .map(|&(dx, dy)| (self.x + dx, self.y + dy))
+            .collect()
+    }
+}
+
+// Best resting place for the piece on a row-ordered grid, or None if it fits nowhere.
+// Only straight drops from the top are considered, no tucks or spins.
+pub fn best_placement(
+    grid: &[bool],
+    width: isize,
+    height: isize,
+    piece_type: PieceType,
+) -> Option<Placement> {
+    let mut best: Option<(Placement, f32)> = None;
+
+    for placement in drop_placements(grid, width, height, piece_type) {
+        let rating = rate(grid, width, height, &placement.cells(piece_type));
+        if best.is_none_or(|(_, best_rating)| rating > best_rating) {
+            best = Some((placement, rating));
+        }
+    }
+
+    best.map(|(placement, _)| placement)
+}
+
+// Every resting place reached by rotating and shifting at the top, then dropping
+// straight down. Rotations that cover the same cells are listed once, lowest
+// rotation first.
+pub fn drop_placements(
+    grid: &[bool],
+    width: isize,
+    height: isize,
+    piece_type: PieceType,
+) -> Vec<Placement> {
+    let mut placements: Vec<Placement> = Vec::new();
+    let mut footprints: Vec<Vec<(isize, isize)>> = Vec::new();
+
+    for rot_idx in 0..piece_type.rotation_count() {
+        let (min_x, max_x) = piece_type.minmax_x(rot_idx);
+        let top_y = height - 1 - piece_type.max_y(rot_idx);
+
+        for x in -min_x..width - max_x {
+            let at = |y| Placement { rot_idx, x, y };
+            if !fits(grid, width, height, piece_type, &at(top_y)) {
+                continue;
+            }
+            let mut y = top_y;
+            while fits(grid, width, height, piece_type, &at(y - 1)) {
+                y -= 1;
+            }
+            let placement = at(y);
+
+            let mut cells = placement.cells(piece_type);
+            cells.sort_unstable();
+            if !footprints.contains(&cells) {
+                footprints.push(cells);
+                placements.push(placement);
+            }
+        }
+    }
+
+    placements
+}
+
+// Whether placing the cells would cover an empty cell, leaving a hole under the piece
+pub fn creates_hole(grid: &[bool], width: isize, cells: &[(isize, isize)]) -> bool {
+    cells.iter().any(|&(x, y)| {
+        let below = (x, y - 1);
+        y > 0 && !grid[(below.1 * width + below.0) as usize] && !cells.contains(&below)
+    })
+}
+
+fn fits(grid: &[bool], width: isize, height: isize, piece_type: PieceType, p: &Placement) -> bool {
+    p.cells(piece_type).iter().all(|&(x, y)| {
+        x >= 0 && x < width && y >= 0 && y < height && !grid[(y * width + x) as usize]
+    })
+}
+
+// Rate the grid after filling the cells and clearing full rows
+fn rate(grid: &[bool], width: isize, height: isize, cells: &[(isize, isize)]) -> f32 {
+    let mut grid = grid.to_vec();
+    for &(x, y) in cells {
+        grid[(y * width + x) as usize] = true;
+    }
+
+    let w = width as usize;
+    let rows: Vec<&[bool]> = grid
+        .chunks(w)
+        .filter(|row| !row.iter().all(|&filled| filled))
+        .collect();
+    let lines = height as usize - rows.len();
+
+    let mut heights = vec![0usize; w];
+    let mut holes = 0;
+    for x in 0..w {
+        let top = rows.iter().rposition(|row| row[x]);
+        if let Some(top) = top {
+            heights[x] = top + 1;
+            holes += rows[..top].iter().filter(|row| !row[x]).count();
+        }
+    }
+
+    let aggregate_height: usize = heights.iter().sum();
+    let bumpiness: usize = heights.windows(2).map(|h| h[0].abs_diff(h[1])).sum();
+
+    HEIGHT_WEIGHT * aggregate_height as f32
+        + LINES_WEIGHT * lines as f32
+        + HOLES_WEIGHT * holes as f32
+        + BUMPINESS_WEIGHT * bumpiness as f32
+}
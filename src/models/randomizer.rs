@@ -0,0 +1,236 @@
+// src/models/randomizer.rs
+//
+// Piece randomizers: each picks the next piece from the board's seeded RNG,
+// keeping whatever state its algorithm needs between draws
+
+use crate::{config::Randomizer, models::PieceType};
+use nannou::rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::collections::VecDeque;
+
+const PIECE_COUNT: usize = 7;
+
+// TGM: pieces remembered, and draws tried before settling for a repeat
+const TGM_HISTORY: usize = 4;
+const TGM_ROLLS: usize = 6;
+
+pub trait PieceRandomizer {
+    fn next_piece(&mut self, rng: &mut StdRng) -> PieceType;
+}
+
+pub fn new_randomizer(kind: Randomizer, weights: [f32; 7]) -> Box<dyn PieceRandomizer> {
+    match kind {
+        Randomizer::Uniform => Box::new(Uniform),
+        Randomizer::SevenBag => Box::new(SevenBag::default()),
+        Randomizer::Weighted => Box::new(Weighted { weights }),
+        Randomizer::Tgm => Box::new(Tgm::default()),
+        Randomizer::Nes => Box::new(Nes::default()),
+    }
+}
+
+fn uniform_piece(rng: &mut StdRng) -> PieceType {
+    PieceType::from_idx(rng.gen_range(0..PIECE_COUNT))
+}
+
+// Every piece equally likely on every draw
+pub struct Uniform;
+
+impl PieceRandomizer for Uniform {
+    fn next_piece(&mut self, rng: &mut StdRng) -> PieceType {
+        uniform_piece(rng)
+    }
+}
+
+// Deals all seven pieces in shuffled order before any repeats
+#[derive(Default)]
+pub struct SevenBag {
+    bag: Vec<PieceType>,
+}
+
+impl PieceRandomizer for SevenBag {
+    fn next_piece(&mut self, rng: &mut StdRng) -> PieceType {
+        if self.bag.is_empty() {
+            self.bag = (0..PIECE_COUNT).map(PieceType::from_idx).collect();
+            self.bag.shuffle(rng);
+        }
+        self.bag.pop().unwrap_or(PieceType::I)
+    }
+}
+
+// Relative odds per piece in I, J, L, S, Z, T, O order
+pub struct Weighted {
+    weights: [f32; 7],
+}
+
+impl PieceRandomizer for Weighted {
+    fn next_piece(&mut self, rng: &mut StdRng) -> PieceType {
+        let total: f32 = self.weights.iter().map(|w| w.max(0.0)).sum();
+        if total <= 0.0 {
+            return uniform_piece(rng);
+        }
+
+        let mut pick = rng.gen_range(0.0..total);
+        for (idx, weight) in self.weights.iter().enumerate() {
+            let weight = weight.max(0.0);
+            if pick < weight {
+                return PieceType::from_idx(idx);
+            }
+            pick -= weight;
+        }
+        // float rounding left pick at the very top
+        let last = self.weights.iter().rposition(|&w| w > 0.0).unwrap_or(0);
+        PieceType::from_idx(last)
+    }
+}
+
+// TGM style: redraw a few times to avoid the last four pieces.
+// The first piece is never S, Z or O, which can't start a clean stack.
+pub struct Tgm {
+    history: VecDeque<PieceType>,
+    first: bool,
+}
+
+impl Default for Tgm {
+    fn default() -> Self {
+        Self {
+            history: VecDeque::from([PieceType::Z, PieceType::S, PieceType::S, PieceType::Z]),
+            first: true,
+        }
+    }
+}
+
+impl PieceRandomizer for Tgm {
+    fn next_piece(&mut self, rng: &mut StdRng) -> PieceType {
+        let piece = if std::mem::take(&mut self.first) {
+            [PieceType::I, PieceType::J, PieceType::L, PieceType::T][rng.gen_range(0..4)]
+        } else {
+            let mut piece = uniform_piece(rng);
+            for _ in 1..TGM_ROLLS {
+                if !self.history.contains(&piece) {
+                    break;
+                }
+                piece = uniform_piece(rng);
+            }
+            piece
+        };
+
+        self.history.push_back(piece);
+        if self.history.len() > TGM_HISTORY {
+            self.history.pop_front();
+        }
+        piece
+    }
+}
+
+// One sequence dealt to several boards. Each board reads it at its own pace,
+// so all of them get the same pieces in the same order.
+pub struct SharedSequence {
+    rng: StdRng,
+    randomizer: Box<dyn PieceRandomizer>,
+    pieces: Vec<PieceType>,
+}
+
+impl SharedSequence {
+    pub fn new(seed: u64, kind: Randomizer, weights: [f32; 7]) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            randomizer: new_randomizer(kind, weights),
+            pieces: Vec::new(),
+        }
+    }
+
+    // The nth piece of the sequence, drawing as far ahead as needed
+    pub fn piece(&mut self, n: usize) -> PieceType {
+        while self.pieces.len() <= n {
+            let piece = self.randomizer.next_piece(&mut self.rng);
+            self.pieces.push(piece);
+        }
+        self.pieces[n]
+    }
+}
+
+// NES style: one reroll when the draw repeats the last piece
+// or lands on the eighth, dummy slot
+#[derive(Default)]
+pub struct Nes {
+    last: Option<PieceType>,
+}
+
+impl PieceRandomizer for Nes {
+    fn next_piece(&mut self, rng: &mut StdRng) -> PieceType {
+        let roll = rng.gen_range(0..PIECE_COUNT + 1);
+        let piece = if roll == PIECE_COUNT || Some(PieceType::from_idx(roll)) == self.last {
+            uniform_piece(rng)
+        } else {
+            PieceType::from_idx(roll)
+        };
+        self.last = Some(piece);
+        piece
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DRAWS: usize = 7000;
+
+    fn draw_many(kind: Randomizer, weights: [f32; 7]) -> Vec<PieceType> {
+        let mut randomizer = new_randomizer(kind, weights);
+        let mut rng = StdRng::seed_from_u64(7);
+        (0..DRAWS)
+            .map(|_| randomizer.next_piece(&mut rng))
+            .collect()
+    }
+
+    fn repeats(pieces: &[PieceType]) -> usize {
+        pieces.windows(2).filter(|pair| pair[0] == pair[1]).count()
+    }
+
+    #[test]
+    fn test_uniform_draws_every_piece() {
+        let pieces = draw_many(Randomizer::Uniform, [1.0; 7]);
+        for idx in 0..PIECE_COUNT {
+            assert!(pieces.contains(&PieceType::from_idx(idx)));
+        }
+    }
+
+    #[test]
+    fn test_seven_bag_deals_complete_bags() {
+        let pieces = draw_many(Randomizer::SevenBag, [1.0; 7]);
+        for bag in pieces.chunks(PIECE_COUNT) {
+            for idx in 0..PIECE_COUNT {
+                assert!(bag.contains(&PieceType::from_idx(idx)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_follows_weights() {
+        let pieces = draw_many(Randomizer::Weighted, [3.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        let i_count = pieces.iter().filter(|&&p| p == PieceType::I).count();
+        let j_count = pieces.iter().filter(|&&p| p == PieceType::J).count();
+        assert_eq!(i_count + j_count, DRAWS);
+        assert!(i_count > j_count * 2);
+    }
+
+    #[test]
+    fn test_tgm_avoids_recent_pieces() {
+        let pieces = draw_many(Randomizer::Tgm, [1.0; 7]);
+        assert!(![PieceType::S, PieceType::Z, PieceType::O].contains(&pieces[0]));
+
+        // A piece matching any of the previous four is rare
+        let recent = pieces
+            .windows(TGM_HISTORY + 1)
+            .filter(|w| w[..TGM_HISTORY].contains(&w[TGM_HISTORY]))
+            .count();
+        assert!(recent < DRAWS / 20);
+    }
+
+    #[test]
+    fn test_nes_rerolls_repeats() {
+        // Uniform repeats about 1 in 7; NES about 1 in 28
+        let pieces = draw_many(Randomizer::Nes, [1.0; 7]);
+        assert!(repeats(&pieces) < DRAWS / 14);
+        assert!(repeats(&draw_many(Randomizer::Uniform, [1.0; 7])) > DRAWS / 10);
+    }
+}
@@ -0,0 +1,109 @@
+// src/models/replay.rs
+//
+// A recorded run: the seed it was played with, and every frame kept as a
+// keyframe now and then with the changes since in between
+
+use crate::models::{Board, BoardSnapshot};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+// frames from one keyframe to the next
+const KEYFRAME_INTERVAL: usize = 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ReplayFrame {
+    cells: Vec<(isize, isize, bool)>, // locked cells changed since the frame before
+    active_cells: Vec<(isize, isize)>,
+    score: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    keyframes: Vec<BoardSnapshot>, // every KEYFRAME_INTERVAL-th frame in full
+    frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            keyframes: Vec::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    // Add the next frame: `snapshot` of `board`, stored as what changed on the board
+    // since the last frame unless a keyframe is due
+    pub fn record(&mut self, board: &Board, snapshot: BoardSnapshot) {
+        let index = self.frames.len();
+        let cells = if index.is_multiple_of(KEYFRAME_INTERVAL) {
+            self.keyframes.push(snapshot.clone());
+            Vec::new()
+        } else {
+            let prev = self.frame(index - 1).unwrap_or_else(|| snapshot.clone());
+            board
+                .diff(&prev)
+                .into_iter()
+                .map(|(pos, filled)| (pos.x, pos.y, filled))
+                .collect()
+        };
+        self.frames.push(ReplayFrame {
+            cells,
+            active_cells: snapshot.active_cells,
+            score: snapshot.score,
+        });
+    }
+
+    // The board as it was on a frame: its keyframe with the changes since
+    // applied. None once the run has played out.
+    pub fn frame(&self, index: usize) -> Option<BoardSnapshot> {
+        let frame = self.frames.get(index)?;
+        let keyframe = index - index % KEYFRAME_INTERVAL;
+        let mut snapshot = self.keyframes.get(keyframe / KEYFRAME_INTERVAL)?.clone();
+        for &(x, y, filled) in self.frames[keyframe + 1..=index]
+            .iter()
+            .flat_map(|frame| &frame.cells)
+        {
+            if let Some(cell) = snapshot.grid.get_mut((y * snapshot.width + x) as usize) {
+                *cell = filled;
+            }
+        }
+        snapshot.active_cells = frame.active_cells.clone();
+        snapshot.score = frame.score;
+        Some(snapshot)
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    // Where a board's run is saved: `id` appended to the file stem,
+    // so replay.json becomes replay_board1.json
+    pub fn board_path(path: &Path, id: &str) -> PathBuf {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match path.extension() {
+            Some(ext) => format!("{}_{}.{}", stem, id, ext.to_string_lossy()),
+            None => format!("{}_{}", stem, id),
+        };
+        path.with_file_name(name)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, json)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
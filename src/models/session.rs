@@ -0,0 +1,158 @@
+// src/models/session.rs
+//
+// Saves and restores every board's grid and score.
+// JSON by default; a `.bin` path uses a compact versioned bincode format
+// for installs with many boards.
+
+use crate::models::BoardState;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+// binary saves start with MAGIC followed by the little-endian format version
+const MAGIC: &[u8; 4] = b"GOSV";
+// bump whenever Session or BoardState change shape
+const BINARY_VERSION: u32 = 1;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SaveFormat {
+    Json,
+    Binary,
+}
+
+impl SaveFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("bin") => SaveFormat::Binary,
+            _ => SaveFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub boards: BTreeMap<String, BoardState>,
+}
+
+impl Session {
+    pub fn to_bytes(&self, format: SaveFormat) -> io::Result<Vec<u8>> {
+        match format {
+            SaveFormat::Json => serde_json::to_vec_pretty(self).map_err(invalid_data),
+            SaveFormat::Binary => {
+                let mut bytes = MAGIC.to_vec();
+                bytes.extend_from_slice(&BINARY_VERSION.to_le_bytes());
+                bytes.extend(bincode::serialize(self).map_err(invalid_data)?);
+                Ok(bytes)
+            }
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8], format: SaveFormat) -> io::Result<Self> {
+        match format {
+            SaveFormat::Json => serde_json::from_slice(bytes).map_err(invalid_data),
+            SaveFormat::Binary => {
+                let payload = bytes
+                    .strip_prefix(MAGIC.as_slice())
+                    .ok_or_else(|| invalid_data("not a binary session save"))?;
+                if payload.len() < 4 {
+                    return Err(invalid_data("truncated binary session save"));
+                }
+
+                let (version, payload) = payload.split_at(4);
+                let version = u32::from_le_bytes([version[0], version[1], version[2], version[3]]);
+                if version != BINARY_VERSION {
+                    return Err(invalid_data(format!(
+                        "binary session save version {} is not supported (expected {})",
+                        version, BINARY_VERSION
+                    )));
+                }
+
+                bincode::deserialize(payload).map_err(invalid_data)
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_bytes(SaveFormat::from_path(path))?)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_bytes(&fs::read(path)?, SaveFormat::from_path(path))
+    }
+}
+
+// An io error for bytes or text that don't decode to what was expected
+pub fn invalid_data<E>(e: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::{Board, PieceType},
+        views::{BoardPosition, PieceInstance},
+    };
+    use nannou::prelude::*;
+
+    fn test_session() -> Session {
+        let mut board = Board::new(10, 20);
+        let piece = PieceInstance::new(
+            PieceType::T,
+            rgba(1.0, 1.0, 1.0, 1.0),
+            BoardPosition { x: 0, y: 0 },
+        );
+        board.commit_piece_at(&piece, BoardPosition { x: 3, y: 0 });
+        board.add_score(420);
+
+        let mut session = Session::default();
+        session
+            .boards
+            .insert("board1".to_owned(), board.state().clone());
+        session
+            .boards
+            .insert("board2".to_owned(), Board::new(10, 20).state().clone());
+        session
+    }
+
+    #[test]
+    fn test_json_and_binary_round_trip() {
+        let session = test_session();
+
+        let json = session.to_bytes(SaveFormat::Json).unwrap();
+        let binary = session.to_bytes(SaveFormat::Binary).unwrap();
+        assert!(binary.len() < json.len());
+
+        let from_json = Session::from_bytes(&json, SaveFormat::Json).unwrap();
+        let from_binary = Session::from_bytes(&binary, SaveFormat::Binary).unwrap();
+        assert_eq!(from_json, session);
+        assert_eq!(from_binary, from_json);
+
+        // Restoring either gives the same board
+        let mut restored = Board::new(10, 20);
+        assert!(restored.restore(from_binary.boards["board1"].clone()));
+        assert_eq!(restored.state(), &session.boards["board1"]);
+        assert_eq!(restored.score(), 420);
+    }
+
+    #[test]
+    fn test_binary_version_rejected() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&(BINARY_VERSION + 1).to_le_bytes());
+        bytes.extend_from_slice(&[0; 16]);
+        let err = Session::from_bytes(&bytes, SaveFormat::Binary).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("version"));
+
+        let err = Session::from_bytes(b"{}", SaveFormat::Binary).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        assert_eq!(
+            SaveFormat::from_path(Path::new("s.bin")),
+            SaveFormat::Binary
+        );
+        assert_eq!(SaveFormat::from_path(Path::new("s.json")), SaveFormat::Json);
+    }
+}
@@ -0,0 +1,26 @@
+// src/models/snapshot.rs
+//
+// A plain-data copy of a board's visible state,
+// used for streaming boards outside the app
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoardSnapshot {
+    pub width: isize,
+    pub height: isize,
+    pub grid: Vec<bool>,                   // row-ordered, same indexing as Board
+    pub active_cells: Vec<(isize, isize)>, // board positions of the active piece
+    pub score: usize,
+}
+
+impl BoardSnapshot {
+    // Whether a cell shows as filled, by a locked cell or the active piece
+    pub fn is_visible(&self, x: isize, y: isize) -> bool {
+        let locked = x >= 0
+            && x < self.width
+            && y >= 0
+            && self.grid.get((y * self.width + x) as usize) == Some(&true);
+        locked || self.active_cells.contains(&(x, y))
+    }
+}
@@ -0,0 +1,7 @@
+// src/net/mod.rs
+
+pub mod osc;
+pub mod spectator;
+
+pub use osc::OscReceiver;
+pub use spectator::{SpectatorFrame, SpectatorMessage, SpectatorServer};
@@ -0,0 +1,164 @@
+// src/net/osc.rs
+//
+// Receives OSC messages on its own thread. If the socket can't be bound
+// (port in use) or fails while receiving, the thread logs, backs off and
+// rebinds rather than giving up. Packets that don't decode are just skipped.
+
+use nannou_osc as osc;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+// first retry delay; doubles with each consecutive failure
+const BACKOFF_BASE: Duration = Duration::from_millis(250);
+const BACKOFF_MAX: Duration = Duration::from_secs(8);
+
+// Delay before the next bind after `failures` consecutive failures
+pub fn backoff_delay(failures: u32) -> Duration {
+    BACKOFF_BASE
+        .saturating_mul(2u32.saturating_pow(failures))
+        .min(BACKOFF_MAX)
+}
+
+// Connection status shared with the UI, and the failure count behind the backoff
+struct Connection {
+    connected: Arc<AtomicBool>,
+    failures: u32,
+}
+
+impl Connection {
+    fn new(connected: Arc<AtomicBool>) -> Self {
+        Self {
+            connected,
+            failures: 0,
+        }
+    }
+
+    fn bound(&mut self) {
+        self.connected.store(true, Ordering::Relaxed);
+        self.failures = 0;
+    }
+
+    // Returns how long to wait before binding again
+    fn failed(&mut self) -> Duration {
+        self.connected.store(false, Ordering::Relaxed);
+        let delay = backoff_delay(self.failures);
+        self.failures = self.failures.saturating_add(1);
+        delay
+    }
+}
+
+pub struct OscReceiver {
+    rx: Receiver<osc::Message>,
+    connected: Arc<AtomicBool>,
+}
+
+impl OscReceiver {
+    pub fn start(port: u16) -> Self {
+        let connected = Arc::new(AtomicBool::new(false));
+        let connection = Connection::new(connected.clone());
+
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || run_receiver(port, tx, connection));
+
+        Self { rx, connected }
+    }
+
+    pub fn osc_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    // Messages received since the last call
+    pub fn drain(&self) -> Vec<osc::Message> {
+        self.rx.try_iter().collect()
+    }
+}
+
+fn run_receiver(port: u16, tx: Sender<osc::Message>, mut connection: Connection) {
+    loop {
+        let receiver = match osc::receiver(port) {
+            Ok(receiver) => {
+                println!("OSC listening on port {}", port);
+                connection.bound();
+                receiver
+            }
+            Err(e) => {
+                let delay = connection.failed();
+                println!(
+                    "OSC: can't bind port {}: {}. Retrying in {:?}",
+                    port, e, delay
+                );
+                thread::sleep(delay);
+                continue;
+            }
+        };
+
+        // Deliver messages until the socket fails
+        let delay = loop {
+            match receiver.recv() {
+                Ok((packet, _)) => {
+                    for message in packet.into_msgs() {
+                        // The app has shut down
+                        if tx.send(message).is_err() {
+                            return;
+                        }
+                    }
+                }
+                // One bad packet; the socket itself is fine
+                Err(osc::CommunicationError::Osc(e)) => {
+                    println!("OSC: skipping undecodable packet: {}", e);
+                }
+                Err(e) => {
+                    let delay = connection.failed();
+                    println!("OSC: receive failed: {}. Rebinding in {:?}", e, delay);
+                    break delay;
+                }
+            }
+        };
+
+        // Release the port before trying to bind it again
+        drop(receiver);
+        thread::sleep(delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_schedule() {
+        let schedule: Vec<u64> = (0..8)
+            .map(|failures| backoff_delay(failures).as_millis() as u64)
+            .collect();
+        assert_eq!(schedule, vec![250, 500, 1000, 2000, 4000, 8000, 8000, 8000]);
+
+        // Long outages stay capped
+        assert_eq!(backoff_delay(u32::MAX), BACKOFF_MAX);
+    }
+
+    #[test]
+    fn test_connection_status_transitions() {
+        let connected = Arc::new(AtomicBool::new(false));
+        let mut connection = Connection::new(connected.clone());
+
+        // Port in use twice, then bound
+        assert_eq!(connection.failed(), backoff_delay(0));
+        assert_eq!(connection.failed(), backoff_delay(1));
+        assert!(!connected.load(Ordering::Relaxed));
+        connection.bound();
+        assert!(connected.load(Ordering::Relaxed));
+
+        // A later failure starts the backoff over
+        assert_eq!(connection.failed(), backoff_delay(0));
+        assert!(!connected.load(Ordering::Relaxed));
+        connection.bound();
+        assert!(connected.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,287 @@
+// src/net/spectator.rs
+//
+// Streams boards to remote spectators over WebSocket: a full snapshot as a
+// keyframe now and then, and only the changed cells in between.
+// The server runs on its own thread and is fed by a channel from update,
+// so a slow or stalled client never holds up the frame.
+
+use crate::{models::BoardSnapshot, views::BoardInstance};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, SyncSender},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+use tungstenite::{Message, WebSocket};
+
+// frames waiting to be sent; newer frames are dropped while this is full
+const QUEUE_LEN: usize = 8;
+// how often the server thread checks for new spectators when idle
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+// give up on a spectator that can't keep up
+const WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+// give up on a spectator that connects but never completes the handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_millis(500);
+// frames sent as diffs between two keyframes of a board
+const KEYFRAME_INTERVAL: usize = 60;
+
+// What a spectator gets for one board in one frame
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SpectatorFrame {
+    Keyframe(BoardSnapshot),
+    Diff {
+        cells: Vec<(isize, isize, bool)>, // changed cells and whether they show now
+        score: usize,
+    },
+}
+
+// The wire format: one JSON text message per changed board per frame
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpectatorMessage {
+    pub board_id: String,
+    pub frame: SpectatorFrame,
+}
+
+impl SpectatorMessage {
+    pub fn to_wire(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_wire(text: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(text)
+    }
+}
+
+// What spectators were last brought up to for one board
+struct BoardStream {
+    sent: BoardSnapshot,
+    since_keyframe: usize, // diffs sent since the last keyframe
+}
+
+impl BoardStream {
+    // A keyframe of `board`, and the stream that starts from it
+    fn keyframe(board: &BoardInstance) -> (Self, SpectatorFrame) {
+        let snapshot = board.snapshot();
+        let stream = Self {
+            sent: snapshot.clone(),
+            since_keyframe: 0,
+        };
+        (stream, SpectatorFrame::Keyframe(snapshot))
+    }
+
+    // The cells changed since the last frame sent, None if nothing did
+    fn diff(&mut self, board: &BoardInstance) -> Option<SpectatorFrame> {
+        let snapshot = board.snapshot();
+        let cells: Vec<(isize, isize, bool)> = board
+            .diff(&self.sent)
+            .into_iter()
+            .map(|(pos, visible)| (pos.x, pos.y, visible))
+            .collect();
+        if cells.is_empty() && snapshot.score == self.sent.score {
+            return None;
+        }
+
+        self.since_keyframe += 1;
+        let score = snapshot.score;
+        self.sent = snapshot;
+        Some(SpectatorFrame::Diff { cells, score })
+    }
+}
+
+// What to send for a board this frame: a keyframe if it has no stream yet or
+// is due one, otherwise a diff if anything changed
+fn next_frame(
+    streams: &mut HashMap<String, BoardStream>,
+    board_id: &str,
+    board: &BoardInstance,
+) -> Option<SpectatorFrame> {
+    match streams.get_mut(board_id) {
+        Some(stream) if stream.since_keyframe < KEYFRAME_INTERVAL => stream.diff(board),
+        _ => {
+            let (stream, frame) = BoardStream::keyframe(board);
+            streams.insert(board_id.to_owned(), stream);
+            Some(frame)
+        }
+    }
+}
+
+pub struct SpectatorServer {
+    tx: SyncSender<String>,
+    streams: HashMap<String, BoardStream>,
+    joined: Arc<AtomicBool>, // a spectator connected and needs keyframes
+}
+
+impl SpectatorServer {
+    pub fn start(port: u16) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        listener.set_nonblocking(true)?;
+
+        let (tx, rx) = mpsc::sync_channel(QUEUE_LEN);
+        let joined = Arc::new(AtomicBool::new(false));
+        let server_joined = joined.clone();
+        thread::spawn(move || run_server(listener, rx, server_joined));
+
+        println!("Spectator server listening on port {}", port);
+        Ok(Self {
+            tx,
+            streams: HashMap::new(),
+            joined,
+        })
+    }
+
+    // Queue what changed on a board for all connected spectators: a keyframe
+    // every KEYFRAME_INTERVAL diffs and after a spectator joins, diffs between
+    pub fn send(&mut self, board_id: &str, board: &BoardInstance) {
+        if self.joined.swap(false, Ordering::Relaxed) {
+            self.streams.clear();
+        }
+
+        let Some(frame) = next_frame(&mut self.streams, board_id, board) else {
+            return;
+        };
+        let message = SpectatorMessage {
+            board_id: board_id.to_owned(),
+            frame,
+        };
+
+        match message.to_wire() {
+            // A full queue means the server is behind; skip this frame, and
+            // since spectators won't see it, start the board over from a keyframe
+            Ok(text) => {
+                if self.tx.try_send(text).is_err() {
+                    self.streams.remove(board_id);
+                }
+            }
+            Err(e) => {
+                println!("Spectator: failed to serialize frame: {}", e);
+                self.streams.remove(board_id);
+            }
+        }
+    }
+}
+
+fn run_server(listener: TcpListener, rx: Receiver<String>, joined: Arc<AtomicBool>) {
+    let mut clients: Vec<WebSocket<TcpStream>> = Vec::new();
+
+    loop {
+        if accept_clients(&listener, &mut clients) {
+            joined.store(true, Ordering::Relaxed);
+        }
+
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(text) => {
+                // Drop any spectator whose connection has failed
+                clients.retain_mut(|client| client.send(Message::Text(text.clone())).is_ok());
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            // The app has shut down
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+// Returns true if any spectator joined
+fn accept_clients(listener: &TcpListener, clients: &mut Vec<WebSocket<TcpStream>>) -> bool {
+    let mut joined = false;
+    while let Ok((stream, addr)) = listener.accept() {
+        if stream.set_nonblocking(false).is_err()
+            || stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_err()
+            || stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT)).is_err()
+        {
+            continue;
+        }
+
+        match tungstenite::accept(stream) {
+            Ok(client) => {
+                println!("Spectator connected: {}", addr);
+                clients.push(client);
+                joined = true;
+            }
+            Err(e) => println!("Spectator handshake failed for {}: {}", addr, e),
+        }
+    }
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::BoardConfig, views::PlayerInput};
+    use nannou::prelude::vec2;
+
+    #[test]
+    fn test_wire_round_trip() {
+        let mut grid = vec![false; 4 * 3];
+        grid[0] = true;
+        grid[5] = true;
+
+        let keyframe = SpectatorMessage {
+            board_id: "board1".to_owned(),
+            frame: SpectatorFrame::Keyframe(BoardSnapshot {
+                width: 4,
+                height: 3,
+                grid,
+                active_cells: vec![(1, 2), (2, 2), (3, 2), (2, 1)],
+                score: 1200,
+            }),
+        };
+        let diff = SpectatorMessage {
+            board_id: "board1".to_owned(),
+            frame: SpectatorFrame::Diff {
+                cells: vec![(0, 0, false), (1, 1, true)],
+                score: 1300,
+            },
+        };
+
+        for message in [keyframe, diff] {
+            let wire = message.to_wire().unwrap();
+            assert!(wire.contains("\"board_id\":\"board1\""));
+
+            let decoded = SpectatorMessage::from_wire(&wire).unwrap();
+            assert_eq!(decoded, message);
+        }
+    }
+
+    #[test]
+    fn test_diffs_between_keyframes() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        let mut streams = HashMap::new();
+
+        // First frame is a keyframe; an unchanged board then sends nothing
+        let frame = next_frame(&mut streams, "test", &board);
+        assert!(matches!(frame, Some(SpectatorFrame::Keyframe(_))));
+        assert_eq!(next_frame(&mut streams, "test", &board), None);
+
+        // The spawned piece arrives as its own cells
+        board.update(0.0, &[]);
+        let Some(SpectatorFrame::Diff { cells, .. }) = next_frame(&mut streams, "test", &board)
+        else {
+            panic!("expected a diff");
+        };
+        assert_eq!(cells.len(), 4);
+
+        // Moving it every frame sends diffs until the next keyframe is due
+        for i in 1..=KEYFRAME_INTERVAL {
+            let input = if i % 2 == 0 {
+                PlayerInput::L
+            } else {
+                PlayerInput::R
+            };
+            board.update(0.0, &[input]);
+            let frame = next_frame(&mut streams, "test", &board);
+            let is_keyframe = matches!(frame, Some(SpectatorFrame::Keyframe(_)));
+            assert_eq!(is_keyframe, i == KEYFRAME_INTERVAL);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+// src/utils/cell_texture.rs
+//
+// Loading the optional cell sprite. A missing or unreadable image leaves
+// cells drawn in flat color rather than stopping the app.
+
+use std::{fmt::Display, path::Path};
+
+// Opacity of the sprite copy drawn in ghost and preview cells
+pub const FAINT_CELL_OPACITY: f32 = 0.4;
+
+// Load the image at `path` with `load`, or None to draw flat cells
+pub fn load_cell_texture<T, E: Display>(
+    path: Option<&Path>,
+    load: impl FnOnce(&Path) -> Result<T, E>,
+) -> Option<T> {
+    let path = path?;
+    load(path)
+        .map_err(|e| {
+            println!(
+                "Failed to load cell texture {}: {} -- using flat color",
+                path.display(),
+                e
+            )
+        })
+        .ok()
+}
+
+// Scale the alpha of every pixel in 8-bit RGBA data, for the faint sprite
+pub fn fade_pixels(rgba: &mut [u8], opacity: f32) {
+    let opacity = opacity.clamp(0.0, 1.0);
+    for alpha in rgba.iter_mut().skip(3).step_by(4) {
+        *alpha = (*alpha as f32 * opacity).round() as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_texture_falls_back_to_flat() {
+        let missing = Path::new("no/such/cell_texture.png");
+        let read = |path: &Path| std::fs::read(path);
+        assert_eq!(load_cell_texture(Some(missing), read), None);
+        assert_eq!(load_cell_texture(None, read), None);
+
+        let loaded = load_cell_texture(Some(missing), |_| Ok::<_, String>(7));
+        assert_eq!(loaded, Some(7));
+    }
+
+    #[test]
+    fn test_fade_pixels_scales_only_alpha() {
+        let mut pixels = [255, 128, 0, 255, 10, 20, 30, 100];
+        fade_pixels(&mut pixels, FAINT_CELL_OPACITY);
+        assert_eq!(pixels, [255, 128, 0, 102, 10, 20, 30, 40]);
+    }
+}
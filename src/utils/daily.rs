@@ -0,0 +1,68 @@
+// src/utils/daily.rs
+//
+// Daily challenge: a piece sequence seed derived from the UTC date,
+// so every install plays the same sequence on the same day.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECS_PER_DAY: u64 = 86_400;
+
+// Today's date in UTC as YYYY-MM-DD
+pub fn today_utc() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    utc_date_string(secs)
+}
+
+// Unix seconds to a YYYY-MM-DD UTC date (civil-from-days conversion).
+// Works purely on UTC, so the local timezone never shifts the date.
+pub fn utc_date_string(unix_secs: u64) -> String {
+    let days = (unix_secs / SECS_PER_DAY) as i64;
+
+    // Shift the epoch to 0000-03-01 so leap days fall at the end of each year
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Hash a date string into an RNG seed. FNV-1a is used rather than std's
+// DefaultHasher because its output must never change between builds.
+pub fn daily_seed(date: &str) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    date.bytes().fold(FNV_OFFSET, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_daily_seed() {
+        // The same date always yields the same seed
+        assert_eq!(daily_seed("2025-04-15"), daily_seed("2025-04-15"));
+        assert_ne!(daily_seed("2025-04-15"), daily_seed("2025-04-16"));
+        assert_ne!(daily_seed("2025-04-15"), daily_seed("2024-04-15"));
+
+        // Any time during a UTC day maps to that day
+        assert_eq!(utc_date_string(0), "1970-01-01");
+        assert_eq!(utc_date_string(1_744_675_200), "2025-04-15");
+        assert_eq!(utc_date_string(1_744_761_599), "2025-04-15");
+        assert_eq!(utc_date_string(951_825_600), "2000-02-29");
+    }
+}
@@ -0,0 +1,53 @@
+// src/utils/debounce.rs
+//
+// Drops repeats of the same input arriving too close together, for noisy
+// buttons and flooding OSC senders
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct Debouncer<T> {
+    window: Duration,
+    last: Option<(T, Instant)>, // last input let through and when
+}
+
+impl<T: Copy + PartialEq> Debouncer<T> {
+    pub fn new(window: Duration) -> Self {
+        Self { window, last: None }
+    }
+
+    // True if the input should apply. A repeat of the last input let through is
+    // dropped until the window has passed since it; a different input always applies.
+    pub fn accept(&mut self, input: T, at: Instant) -> bool {
+        if let Some((last, since)) = self.last {
+            if last == input && at.saturating_duration_since(since) < self.window {
+                return false;
+            }
+        }
+        self.last = Some((input, at));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debounce_window() {
+        let mut debounce = Debouncer::new(Duration::from_millis(50));
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+
+        // Two identical inputs within the window collapse to one
+        assert!(debounce.accept('L', ms(0)));
+        assert!(!debounce.accept('L', ms(20)));
+
+        // A different input passes straight away
+        assert!(debounce.accept('R', ms(30)));
+
+        // Identical inputs across the window both apply
+        assert!(debounce.accept('R', ms(90)));
+        assert!(debounce.accept('R', ms(150)));
+    }
+}
@@ -0,0 +1,69 @@
+// src/utils/fixed_step.rs
+//
+// Fixed timestep accumulator: real frame time goes in, a whole
+// number of equal game steps comes out, so play doesn't depend on frame rate.
+
+#[derive(Debug)]
+pub struct FixedStep {
+    step: f32,        // seconds per step
+    accumulator: f32, // real time not yet stepped
+    max_steps: usize, // steps per frame before the backlog is dropped
+}
+
+impl FixedStep {
+    pub fn new(hz: u32, max_steps: usize) -> Self {
+        Self {
+            step: 1.0 / hz.max(1) as f32,
+            accumulator: 0.0,
+            max_steps: max_steps.max(1),
+        }
+    }
+
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    // Add a frame's real time and return how many steps to run. After a very
+    // long frame only max_steps run and the rest is dropped, so the game slows
+    // down instead of spiralling.
+    pub fn advance(&mut self, dt: f32) -> usize {
+        self.accumulator += dt.max(0.0);
+        let steps = (self.accumulator / self.step).floor() as usize;
+        if steps > self.max_steps {
+            self.accumulator = 0.0;
+            return self.max_steps;
+        }
+        self.accumulator -= steps as f32 * self.step;
+        steps
+    }
+
+    // Fraction of a step left over, for drawing between steps
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.step
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_step_counts() {
+        let mut fixed = FixedStep::new(100, 5);
+
+        // Frames shorter than a step accumulate
+        assert_eq!(fixed.advance(0.004), 0);
+        assert_eq!(fixed.advance(0.004), 0);
+        assert_eq!(fixed.advance(0.004), 1);
+        assert!((fixed.alpha() - 0.2).abs() < 1e-3);
+
+        // A 30ms frame is three steps, with the remainder carried over
+        assert_eq!(fixed.advance(0.030), 3);
+        assert_eq!(fixed.advance(0.009), 1);
+
+        // A long hitch is clamped and the backlog dropped
+        assert_eq!(fixed.advance(1.0), 5);
+        assert_eq!(fixed.advance(0.0), 0);
+        assert_eq!(fixed.alpha(), 0.0);
+    }
+}
@@ -0,0 +1,36 @@
+// src/utils/input_buffer.rs
+//
+// A small bounded queue of inputs received between frames,
+// drained once per update so fast key presses aren't lost
+
+use std::collections::VecDeque;
+
+#[derive(Debug)]
+pub struct InputBuffer<T> {
+    queue: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> InputBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            queue: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    // Queue an input. Returns false if the buffer is full and the input was dropped.
+    pub fn push(&mut self, input: T) -> bool {
+        if self.queue.len() >= self.capacity {
+            return false;
+        }
+        self.queue.push_back(input);
+        true
+    }
+
+    // Take every queued input in the order it arrived
+    pub fn drain(&mut self) -> Vec<T> {
+        self.queue.drain(..).collect()
+    }
+}
@@ -0,0 +1,65 @@
+// src/utils/latency.rs
+//
+// Rolling average of input latency: the time from a key press being queued
+// to the end of the frame that applied it
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug)]
+pub struct LatencyMeter {
+    samples: VecDeque<Duration>,
+    window: usize, // samples averaged
+}
+
+impl LatencyMeter {
+    pub fn new(window: usize) -> Self {
+        let window = window.max(1);
+        Self {
+            samples: VecDeque::with_capacity(window),
+            window,
+        }
+    }
+
+    // Record the inputs queued at `queued`, applied in the frame ending at `applied`
+    pub fn record(&mut self, queued: &[Instant], applied: Instant) {
+        for &time in queued {
+            if self.samples.len() == self.window {
+                self.samples.pop_front();
+            }
+            self.samples
+                .push_back(applied.saturating_duration_since(time));
+        }
+    }
+
+    // Mean over the last `window` inputs, None before any input
+    pub fn average(&self) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        Some(self.samples.iter().sum::<Duration>() / self.samples.len() as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_rolling_average() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let mut meter = LatencyMeter::new(3);
+        assert_eq!(meter.average(), None);
+
+        // Two presses applied at 20ms: 20ms and 10ms late
+        meter.record(&[ms(0), ms(10)], ms(20));
+        assert_eq!(meter.average(), Some(Duration::from_millis(15)));
+
+        // The window keeps the last three: 10, 30 and 50ms
+        meter.record(&[ms(30), ms(10)], ms(60));
+        assert_eq!(meter.average(), Some(Duration::from_millis(30)));
+    }
+}
@@ -0,0 +1,38 @@
+// src/utils/letterbox.rs
+//
+// Fits the render texture inside the window without stretching.
+
+use nannou::geom::Rect;
+
+// Largest rect with the texture's aspect that fits the window, centered at
+// the origin. The uncovered strips become letterbox/pillarbox bars.
+pub fn letterbox_rect(window_w: f32, window_h: f32, texture_w: f32, texture_h: f32) -> Rect {
+    if window_w <= 0.0 || window_h <= 0.0 || texture_w <= 0.0 || texture_h <= 0.0 {
+        return Rect::from_w_h(window_w.max(0.0), window_h.max(0.0));
+    }
+
+    let scale = (window_w / texture_w).min(window_h / texture_h);
+    Rect::from_w_h(texture_w * scale, texture_h * scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letterbox_rect() {
+        // Matching aspect fills the window
+        let r = letterbox_rect(1900.0, 540.0, 3800.0, 1080.0);
+        assert_eq!((r.w(), r.h()), (1900.0, 540.0));
+
+        // Window taller than texture: bars top and bottom
+        let r = letterbox_rect(1000.0, 1000.0, 2000.0, 1000.0);
+        assert_eq!((r.w(), r.h()), (1000.0, 500.0));
+        assert_eq!((r.x(), r.y()), (0.0, 0.0));
+
+        // Window wider than texture: bars left and right
+        let r = letterbox_rect(2000.0, 500.0, 1000.0, 1000.0);
+        assert_eq!((r.w(), r.h()), (500.0, 500.0));
+        assert_eq!((r.x(), r.y()), (0.0, 0.0));
+    }
+}
@@ -1,3 +1,21 @@
+pub mod cell_texture;
+pub mod daily;
+pub mod debounce;
+pub mod fixed_step;
+pub mod input_buffer;
+pub mod latency;
+pub mod letterbox;
+pub mod score_format;
+pub mod texture_size;
 pub mod timer;
 
+pub use cell_texture::{fade_pixels, load_cell_texture, FAINT_CELL_OPACITY};
+pub use daily::{daily_seed, today_utc};
+pub use debounce::Debouncer;
+pub use fixed_step::FixedStep;
+pub use input_buffer::InputBuffer;
+pub use latency::LatencyMeter;
+pub use letterbox::letterbox_rect;
+pub use score_format::format_score;
+pub use texture_size::{scaled_texture_size, texture_limit, TextureSize};
 pub use timer::Timer;
@@ -0,0 +1,74 @@
+// src/utils/score_format.rs
+//
+// Score text for the HUD: digit grouping and arcade-style fixed widths,
+// the same whatever the system locale
+
+use crate::config::ScoreFormat;
+
+pub fn format_score(score: usize, format: &ScoreFormat) -> String {
+    let digits = score.to_string();
+    let mut text = group_digits(&digits, &format.separator);
+
+    if format.leading_zeros {
+        // Add zeros before grouping so they're grouped like any other digit
+        let mut padded = digits;
+        while text.chars().count() < format.width {
+            padded.insert(0, '0');
+            text = group_digits(&padded, &format.separator);
+        }
+    }
+
+    format!("{:>width$}", text, width = format.width)
+}
+
+// Insert the separator between groups of three, counting from the right
+fn group_digits(digits: &str, separator: &str) -> String {
+    let mut grouped = String::with_capacity(digits.len() * (1 + separator.len()));
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(separator: &str, width: usize, leading_zeros: bool) -> ScoreFormat {
+        ScoreFormat {
+            separator: separator.to_owned(),
+            width,
+            leading_zeros,
+        }
+    }
+
+    #[test]
+    fn test_format_score() {
+        let grouped = format(",", 0, false);
+        assert_eq!(format_score(1234567, &grouped), "1,234,567");
+        assert_eq!(format_score(123, &grouped), "123");
+        assert_eq!(format_score(0, &grouped), "0");
+
+        let plain = format("", 0, false);
+        assert_eq!(format_score(1234567, &plain), "1234567");
+
+        // Arcade: zero padded to a fixed width
+        let arcade = format("", 10, true);
+        assert_eq!(format_score(1234567, &arcade), "0001234567");
+        assert_eq!(format_score(0, &arcade), "0000000000");
+
+        // Zeros are grouped too; space padding right aligns
+        assert_eq!(format_score(1234, &format(",", 9, true)), "0,001,234");
+        assert_eq!(
+            format_score(1234567, &format(",", 12, false)),
+            "   1,234,567"
+        );
+
+        // Scores wider than the width aren't cut
+        assert_eq!(format_score(1234567, &format("", 4, true)), "1234567");
+        assert_eq!(format_score(1234567, &format(" ", 0, false)), "1 234 567");
+    }
+}
@@ -0,0 +1,76 @@
+// src/utils/texture_size.rs
+//
+// Render texture size for supersampling: the configured size times
+// the render scale, kept within what the device can allocate.
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TextureSize {
+    pub width: u32,
+    pub height: u32,
+    pub scale: f32,    // scale actually applied, lower than asked for when clamped
+    pub clamped: bool, // the requested size was over the device limit
+}
+
+// Largest texture side to allow: the configured cap if set, never over the device's
+pub fn texture_limit(configured: u32, device_max: u32) -> u32 {
+    match configured {
+        0 => device_max,
+        cap => cap.min(device_max),
+    }
+}
+
+// Scale width/height, shrinking the scale uniformly if either side would exceed max_dim
+pub fn scaled_texture_size(
+    width: u32,
+    height: u32,
+    render_scale: f32,
+    max_dim: u32,
+) -> TextureSize {
+    let requested = if render_scale > 0.0 {
+        render_scale
+    } else {
+        1.0
+    };
+    let largest = width.max(height).max(1) as f32;
+    let limit = max_dim as f32 / largest;
+    let scale = requested.min(limit);
+
+    TextureSize {
+        width: ((width as f32 * scale).round() as u32).clamp(1, max_dim),
+        height: ((height as f32 * scale).round() as u32).clamp(1, max_dim),
+        scale,
+        clamped: scale < requested,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaled_texture_size() {
+        // Fits: both sides doubled
+        let size = scaled_texture_size(1920, 1080, 2.0, 8192);
+        assert_eq!((size.width, size.height), (3840, 2160));
+        assert!(!size.clamped);
+
+        // Too wide: scaled down to the limit, aspect kept
+        let size = scaled_texture_size(3800, 1080, 4.0, 8192);
+        assert!(size.clamped);
+        assert_eq!(size.width, 8192);
+        assert_eq!(size.height, (1080.0 * 8192.0_f32 / 3800.0).round() as u32);
+        assert!((size.scale - 8192.0 / 3800.0).abs() < 1e-6);
+
+        // Configured size alone over a smaller cap
+        let max = texture_limit(4096, 8192);
+        let size = scaled_texture_size(6000, 3000, 1.0, max);
+        assert!(size.clamped);
+        assert_eq!((size.width, size.height), (4096, 2048));
+        assert_eq!(texture_limit(0, 8192), 8192);
+        assert_eq!(texture_limit(16384, 8192), 8192);
+
+        // Nonsense scale falls back to 1
+        let size = scaled_texture_size(800, 600, 0.0, 8192);
+        assert_eq!((size.width, size.height, size.scale), (800, 600, 1.0));
+    }
+}
@@ -48,4 +48,16 @@ impl Timer {
     pub fn progress(&self) -> f32 {
         self.elapsed / self.duration
     }
+
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    // Change the duration, keeping the same fraction of it elapsed
+    pub fn set_duration(&mut self, duration: f32) {
+        if self.duration > 0.0 {
+            self.elapsed *= duration / self.duration;
+        }
+        self.duration = duration;
+    }
 }
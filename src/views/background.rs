@@ -11,6 +11,7 @@ pub struct BackgroundManager {
     current_color: Rgb,
     flasher: BackgroundFlash,
     color_fader: BackgroundColorFade,
+    radial_flashes: Vec<RadialFlash>,
 }
 
 impl BackgroundManager {
@@ -19,6 +20,7 @@ impl BackgroundManager {
             current_color,
             flasher: BackgroundFlash::default(),
             color_fader: BackgroundColorFade::default(),
+            radial_flashes: Vec::new(),
         }
     }
 
@@ -33,6 +35,24 @@ impl BackgroundManager {
         }
     }
 
+    // A flash of light spreading from a point in the scene, e.g. a line clear
+    pub fn radial_flash(
+        &mut self,
+        center: Vec2,
+        radius: f32,
+        flash_color: Rgb,
+        duration: f32,
+        current_time: f32,
+    ) {
+        self.radial_flashes.push(RadialFlash::new(
+            center,
+            radius,
+            flash_color,
+            duration,
+            current_time,
+        ));
+    }
+
     pub fn color_fade(&mut self, target_color: Rgb, duration: f32, current_time: f32) {
         self.color_fader
             .start(self.current_color, target_color, duration, current_time);
@@ -54,6 +74,24 @@ impl BackgroundManager {
     pub fn draw(&mut self, draw: &Draw, current_time: f32) {
         self.update_color(current_time);
         draw.background().color(self.current_color);
+
+        for flash in self.radial_flashes.iter_mut() {
+            if let Some((center, radius, alpha)) = flash.update(current_time) {
+                let color = flash.color();
+                draw.ellipse().xy(center).radius(radius).color(rgba(
+                    color.red,
+                    color.green,
+                    color.blue,
+                    alpha * 0.5,
+                ));
+            }
+        }
+        self.radial_flashes.retain(RadialFlash::is_active);
+    }
+
+    // A fade or flash is still running
+    pub fn is_animating(&self) -> bool {
+        self.flasher.is_active() || self.color_fader.is_active() || !self.radial_flashes.is_empty()
     }
 
     pub fn get_current_color(&self) -> Rgb {
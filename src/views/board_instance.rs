@@ -4,13 +4,32 @@
 // handles game state, player input
 
 use crate::{
-    models::{Board, PieceType, PlaceResult},
+    config::{
+        BoardConfig, ClearAxis, ClearingInput, ComboOrder, FrozenInput, GlowConfig, GroundedLock,
+        LockDelayCurve, OppositeInputs, Randomizer, ReseedOnReset, RowFillLock, SpawnRule,
+    },
+    effects::{burst, BackgroundColorFade, BackgroundEffect, Particle},
+    models::{
+        best_placement, creates_hole, new_randomizer, Board, BoardSnapshot, BoardState,
+        PieceRandomizer, PieceType, PlaceResult, Placement, Replay, SharedSequence,
+    },
     utils::Timer,
-    views::{BoardPosition, PieceInstance, RotationDirection},
+    views::{
+        BoardPosition, CellMesh, Orientation, Palette, PieceInstance, PreviewLayout,
+        RotationDirection,
+    },
 };
 use nannou::{
+    image::{self, RgbaImage},
     prelude::*,
-    rand::{rngs::ThreadRng, Rng},
+    rand::{random, rngs::StdRng, Rng, SeedableRng},
+};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    io,
+    path::Path,
+    rc::Rc,
 };
 
 // helps visualize grid for debugging
@@ -21,6 +40,39 @@ const CLEAR_DURATION: f32 = 1.0;
 const SLIDE_DURATION: f32 = 0.15;
 const GAME_OVER_DURATION: f32 = 3.0;
 
+// cell shadows are slightly larger than the cell, to read as soft
+const SHADOW_SCALE: f32 = 1.08;
+
+// length of the debug origin crosshair's arms, in cells
+const CROSSHAIR_CELLS: f32 = 2.0;
+
+// shortest gravity interval settable at runtime
+const MIN_GRAVITY_INTERVAL: f32 = 0.001;
+
+// Floor for the handicap multiplier so gravity never stops outright
+const MIN_GRAVITY_MULTIPLIER: f32 = 0.01;
+
+// fewest upcoming pieces kept in the next queue; a longer preview list keeps more
+const NEXT_QUEUE_LEN: usize = 1;
+
+// fraction of the way the boundary moves toward its target color per second
+const BOUNDARY_BLEND_RATE: f32 = 2.0;
+
+// Opacity of the cell color laid over a cell texture
+const CELL_TEXTURE_TINT: f32 = 0.5;
+
+// Boundary color counts as settled within this of its target, per channel
+const BOUNDARY_SETTLED: f32 = 1.0 / 255.0;
+
+// Background clear flash radius per line cleared, in cells
+const CLEAR_FLASH_CELLS: f32 = 3.0;
+
+// Hue of the board at level 0, in degrees
+const BASE_HUE: f32 = 40.0;
+
+// Mixed into the seed for the garbage RNG, so its holes don't mirror the pieces
+const GARBAGE_SEED_SALT: u64 = 0x9e37_79b9_7f4a_7c15;
+
 #[derive(Debug, Copy, Clone)]
 pub enum GameState {
     Ready,                                  // ready to spawn a new piece
@@ -29,186 +81,842 @@ pub enum GameState {
     // "now" field allow for timer bypass; "hard_drop" is for scoring
     Clearing, // Clearing the completed rows
     GameOver, // Game over transition
+    Finale,   // grayed stack clearing from the bottom after Game Over
     Frozen,   // frozen after Game Over
+    GameWon,  // reached the winning score first; frozen like Game Over
     Paused,
 }
 
-#[derive(PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum PlayerInput {
     L,
     R,
+    SoftDrop,
     HardDrop,
+    Hold,
+    ConfirmClear,
+    Flip,
     Rotate,
     Pause,
-    SaveState,
-    ResumeState,
+    SaveSlot(u8), // paused: bookmark the board and piece
+    LoadSlot(u8), // paused: jump back to a bookmark
+}
+
+// Settle a frame's Left/Right pair by the configured rule.
+// Frames with only one direction pass through unchanged.
+pub fn resolve_opposite_inputs(
+    mut inputs: Vec<PlayerInput>,
+    rule: OppositeInputs,
+) -> Vec<PlayerInput> {
+    let is_horizontal = |input: &PlayerInput| matches!(input, PlayerInput::L | PlayerInput::R);
+    let has_left = inputs.contains(&PlayerInput::L);
+    let has_right = inputs.contains(&PlayerInput::R);
+    if !(has_left && has_right) {
+        return inputs;
+    }
+
+    let keep = match rule {
+        OppositeInputs::FirstWins => inputs.iter().find(|input| is_horizontal(input)),
+        OppositeInputs::LastWins => inputs.iter().rev().find(|input| is_horizontal(input)),
+        OppositeInputs::Cancel => None,
+    };
+    let keep_left = keep == Some(&PlayerInput::L);
+    let keep_right = keep == Some(&PlayerInput::R);
+
+    inputs.retain(|input| match input {
+        PlayerInput::L => keep_left,
+        PlayerInput::R => keep_right,
+        _ => true,
+    });
+    inputs
+}
+
+// Pair a frame's move or soft drop with its held partner, so holding
+// Left+Down moves and drops together. The partner goes next to the frame's
+// input in combo order; held Left and Right both come along, so resolve
+// opposite inputs afterwards.
+pub fn apply_input_combos(
+    mut inputs: Vec<PlayerInput>,
+    held: &[PlayerInput],
+    order: ComboOrder,
+) -> Vec<PlayerInput> {
+    let is_move = |input: &PlayerInput| matches!(input, PlayerInput::L | PlayerInput::R);
+    let is_drop = |input: &PlayerInput| *input == PlayerInput::SoftDrop;
+
+    let first_move = inputs.iter().position(is_move);
+    let first_drop = inputs.iter().position(is_drop);
+    let (at, partners): (usize, Vec<PlayerInput>) = match (first_move, first_drop) {
+        (Some(at), None) => (at, held.iter().copied().filter(is_drop).collect()),
+        (None, Some(at)) => (at, held.iter().copied().filter(is_move).collect()),
+        _ => return inputs,
+    };
+
+    // Only the new pair is ordered; everything else keeps its arrival order
+    let partner_first = match order {
+        ComboOrder::MoveFirst => partners.iter().any(is_move),
+        ComboOrder::DropFirst => partners.iter().any(is_drop),
+    };
+    let at = if partner_first { at } else { at + 1 };
+    inputs.splice(at..at, partners);
+    inputs
+}
+
+// Whether a press should apply, given the keys already down. A press of a held
+// key is the keyboard's auto-repeat: moves and drops repeat, but rotation only
+// when enabled, since a spinning piece keeps resetting its lock delay.
+pub fn accept_key_repeat(input: PlayerInput, held: &[PlayerInput], rotation_repeats: bool) -> bool {
+    rotation_repeats || input != PlayerInput::Rotate || !held.contains(&input)
+}
+
+// Hold every board's gravity while any of them is clearing, release it once none are
+pub fn sync_clear_pause<'a>(boards: impl IntoIterator<Item = &'a mut BoardInstance>) {
+    let boards: Vec<&mut BoardInstance> = boards.into_iter().collect();
+    let any_clearing = boards
+        .iter()
+        .any(|board| board.game_state == GameState::Clearing);
+    for board in boards {
+        board.set_gravity_held(any_clearing);
+    }
+}
+
+// Competitive mode: the first board to reach score_to_win wins and every other
+// board loses. Returns the winner's id on the update it happens.
+pub fn settle_score_race<'a>(
+    boards: impl IntoIterator<Item = &'a mut BoardInstance>,
+    score_to_win: usize,
+) -> Option<String> {
+    let mut boards: Vec<&mut BoardInstance> = boards.into_iter().collect();
+    if score_to_win == 0
+        || boards
+            .iter()
+            .any(|board| board.game_state == GameState::GameWon)
+    {
+        return None;
+    }
+
+    let winner = boards
+        .iter()
+        .filter(|board| board.score() >= score_to_win)
+        .max_by_key(|board| board.score())
+        .map(|board| board.id.clone())?;
+    for board in boards.iter_mut() {
+        if board.id == winner {
+            board.win();
+        } else {
+            board.lose();
+        }
+    }
+    Some(winner)
+}
+
+// Sound triggers for an external audio layer, one per occurrence
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SoundCue {
+    Move,
+    Rotate,
+    Lock,
+    Clear(usize), // number of rows cleared
+    Tetris,       // four or more rows at once
+    GameOver,
+    StackHigh,                // stack reached the warning height
+    StackCleared,             // stack fell back below the warning band
+    LevelUp(usize),           // new level reached
+    ChainMeterChanged(usize), // chain meter crossed a threshold, now this many reached
+    AutoHold,                 // the survival assist held a piece that would have topped out
 }
 
+// Callback for cues as they fire, for apps embedding the boards
+pub type EventHandler = Box<dyn FnMut(&SoundCue)>;
+
 pub struct BoardInstance {
     pub id: String,
-    pub board: Board,   // the internal board logic
-    pub location: Vec2, // screen location of the BoardInstance
-    pub cell_size: f32, // size of the grid cells
+    pub board: Board,         // the internal board logic
+    clear_axis: ClearAxis,    // which full lines clear: rows, columns or both
+    row_weights: Vec<f32>,    // clear points multiplier per row, bottom first
+    pub location: Vec2,       // screen location of the BoardInstance
+    pub cell_size: f32,       // size of the grid cells
+    orientation: Orientation, // on-screen rotation, presentation only
 
     screen_height: f32,
     screen_width: f32,
 
-    color: Rgba,          // color of cells
-    boundary_color: Rgba, // color of outer boundary
+    color: Rgba,                      // color of cells
+    boundary_color: Rgba,             // color of outer boundary
+    base_boundary_color: Rgba,        // boundary color when not tinted by the next piece
+    color_from_next: bool,            // tint the boundary toward the next piece's color
+    level_lines: usize,               // lines per level, 0 for no levels
+    lock_delay: f32,                  // lock delay at level 0
+    lock_delay_curve: LockDelayCurve, // how lock delay shortens per level
+    level_hue_step: f32,              // hue rotation per level, in degrees
+    level_fade_duration: f32,         // seconds to fade to a new level's hue
+    level_fade: BackgroundColorFade,  // base boundary color heading to the level's hue
+    lines_cleared: usize,             // lines cleared this game, sets the level
+
+    chain_meter: f32,             // builds with clears, drains over time
+    chain_per_line: f32,          // meter added per line cleared
+    chain_max: f32,               // meter cap, 0.0 = no meter
+    chain_decay: f32,             // meter drained per second
+    chain_thresholds: Vec<f32>,   // meter levels that fire ChainMeterChanged
+    panel_color: Option<Rgba>,    // fill behind the playfield, None when disabled
+    shadow: Option<(Vec2, Rgba)>, // cell shadow offset and color, None when disabled
+
+    // from, to, and progress of a palette change
+    palette_fade: Option<(Palette, Palette, Timer)>,
 
     game_state: GameState,              // state of the game loops
     prev_game_state: Option<GameState>, // used to come back from pause, for example
     timers: GameTimers,                 // timers used in the game
 
-    rows_to_clear: Option<Vec<isize>>, // rows idxs for the Clearing state to clear
+    gravity_per_frame: f32,   // sub-cell gravity, cells per frame; 0.0 = timer
+    gravity_accumulator: f32, // fractional rows waiting to be applied
+    gravity_held: bool,       // gravity stopped from outside, e.g. another board clearing
+    gravity_enabled: bool,    // false in sandbox mode
+    gravity_multiplier: f32,  // handicap: scales fall speed on top of everything else
+
+    idle_reset: f32,                   // frozen seconds before reset, 0.0 = never
+    frozen_input: FrozenInput,         // what input does once frozen
+    spawn_rule: SpawnRule,             // spawn columns and orientations
+    clearing_input: ClearingInput,     // what moves during a clear do
+    clearing_buffer: Vec<PlayerInput>, // moves made during a clear, for the next piece
+    playtime: f32,                     // seconds of play, excluding pause and game over
+
+    game_over_finale: bool, // clear the stack row by row after game over
+    finale_rows: isize,     // rows cleared so far by the finale
+
+    mercy_count: usize,                      // top-outs forgiven per game
+    mercy_rows: usize,                       // stack rows cleared by each mercy
+    mercy_used: usize,                       // mercies spent this game
+    auto_hold_assist: bool,                  // hold for the player rather than top out
+    garbage_delay: f32,                      // seconds incoming garbage waits before it can rise
+    pending_garbage: VecDeque<(usize, f32)>, // incoming rows and seconds until they can rise
+    garbage_queued: bool,                    // garbage arrived since the last update
+    piece_limit: usize,                      // end the game after this many pieces, 0 = no limit
+    time_limit: f32,                         // end the game after this many seconds, 0 = no limit
+    pieces_placed: usize,                    // pieces locked this game
+    dig_rows: usize,                         // dig mode: garbage rows kept under the stack, 0 = off
+    dig_points: usize,                       // points per garbage row dug
+    garbage_rows: usize,                     // garbage rows at the bottom of the stack
+    max_dig_depth: usize,                    // garbage rows dug this game
+
+    soft_drop_cells: usize,  // rows the active piece was soft dropped
+    hard_drop_cells: usize,  // rows the active piece was hard dropped
+    soft_drop_points: usize, // points per soft dropped row
+    hard_drop_points: usize, // points per hard dropped row
+
+    lock_grace: f32,         // soft landing window, 0.0 = off
+    since_move: f32,         // seconds since the active piece last moved sideways
+    grace_left: Option<f32>, // lock deferred, counting down the grace
+    grace_used: bool,        // the active piece already had its deferral
+    hard_drop_lockout: f32,  // hard drops ignored this long after a soft drop, 0.0 = off
+    since_soft_drop: f32,    // seconds since the last soft drop input
+
+    last_kick: Option<usize>, // wall kick of the active piece's last rotation, 0 = none
+
+    stack_warning: isize,      // stack height that fires StackHigh, 0 = off
+    stack_warning_band: isize, // hysteresis below the warning height
+    stack_high: bool,          // StackHigh fired, StackCleared not yet
+    since_stack_high: f32,     // seconds since StackHigh last fired
+    near_miss_window: f32,     // StackCleared this soon after StackHigh is a near miss, 0 = off
+    slow_mo_factor: f32,       // game speed as a near-miss slow motion starts
+    slow_mo_duration: f32,     // seconds it takes to ease back to full speed
+    slow_mo: Option<Timer>,    // running near-miss slow motion
+
+    beat_interval: Option<f32>, // seconds per beat when pieces lock on the beat
+    beat_clock: f32,            // unpaused seconds, for finding beat boundaries
+    on_beat: bool,              // a beat boundary fell in this frame
+    awaiting_beat: bool,        // lock delay is over, waiting for the beat
+
+    entry_delay: f32,               // delay before a new piece appears (ARE)
+    irs: bool,                      // initial rotation system enabled
+    ihs: bool,                      // initial hold system enabled
+    initial_rotate: bool,           // rotate buffered during the entry delay
+    initial_hold: bool,             // hold buffered during the entry delay
+    spawn_telegraph: bool,          // show the next piece at its spawn during the entry delay
+    preview: Option<PreviewLayout>, // next-piece preview box, None when disabled
+    hold_layout: PreviewLayout,     // hold boxes, opposite the preview
+
+    held: VecDeque<PieceType>, // held pieces, front is the next to come back
+    hold_capacity: usize,      // how many pieces can be held, 0 = no hold
+    hold_used: bool,           // hold already used for the current piece
+    dim_used_hold: bool,       // gray out the hold box while hold_used
+
+    saved_pieces: HashMap<u8, PieceInstance>, // active piece of each save slot
+
+    batch_cells: bool,                    // draw filled cells as one mesh
+    cell_texture: Option<wgpu::Texture>,  // sprite drawn in opaque cells
+    faint_texture: Option<wgpu::Texture>, // translucent sprite for ghost and preview cells
+    glow: GlowConfig,                     // cells brightened past the bloom threshold
+
+    soft_drop_resets_lock: bool, // grounded soft drop restarts the lock delay
+
+    clear_preview: bool,            // highlight the lines a drop would complete
+    landing: Option<BoardPosition>, // where the active piece would land
+    clear_flash_background: bool,   // clears flash the background around them
+    manual_clear: bool,             // filled rows wait for ConfirmClear
+    flip_input: bool,               // Flip turns the stack upside down
+    row_fill_lock: RowFillLock,     // lock at once on a filled row, or after the delay
+    grounded_lock: GroundedLock,    // whether the stack counts as ground, or only the floor
+
+    fill_meter: bool,         // bar showing how full the board is
+    clear_particles: usize,   // sparks per line cleared
+    max_particles: usize,     // cap on live sparks
+    particles: Vec<Particle>, // live sparks, oldest first
+
+    debug_axes: bool, // origin crosshair in the debug display
+
+    show_hint: bool,         // highlight the suggested placement
+    show_pivot: bool,        // mark the active piece's rotation pivot
+    hint: Option<Placement>, // suggested placement, found when the piece spawns
+
+    placement_overlay: bool,         // show every legal placement
+    placement_overlay_max: usize,    // most placements drawn
+    overlay: Vec<(Placement, bool)>, // legal placements, each with whether it leaves a hole
+
+    rows_to_clear: Option<Vec<isize>>,   // row idxs to clear
+    cols_to_clear: Option<Vec<isize>>,   // col idxs to clear
+    pre_clear_flashing: bool,            // Clearing, but cells still flashing before the animation
     active_piece: Option<PieceInstance>, // the currently active piece
+    next_queue: VecDeque<PieceType>,     // upcoming pieces, front is next to spawn
+    next_queue_len: usize,               // pieces kept queued, enough to fill the preview
+    scripted_len: usize,                 // leading pieces of next_queue set by script
+
+    seed: u64,                                   // seed of the piece sequence
+    rng: StdRng,                                 // own RNG, so the sequence replays from the seed
+    garbage_rng: StdRng,                         // garbage holes, apart from the piece sequence
+    randomizer: Box<dyn PieceRandomizer>,        // picks each next piece from rng
+    randomizer_kind: Randomizer,                 // rebuilds the randomizer on reset
+    shared: Option<Rc<RefCell<SharedSequence>>>, // sequence dealt to every board
+    shared_pos: usize,                           // next piece to take from it
+    piece_weights: [f32; 7],                     // per-piece odds for the weighted randomizer
+    reseed_on_reset: ReseedOnReset,              // what seed a reset starts from
+    drill_piece: Option<PieceType>,              // practice mode: the only piece dealt
+
+    sound_cues: Vec<SoundCue>,         // cues fired since the last drain
+    event_handlers: Vec<EventHandler>, // called with each cue as it fires
+    emit: bool,                        // pass cues on when drained; muted boards still fire them
+    soloed_out: bool,                  // another board is soloed: drop cues as if muted
+
+    frame: usize,              // updates since the game started
+    recording: Option<Replay>, // this run, frame by frame
+    record: bool,              // start a new recording with every game
+    ghost: Option<Replay>,     // past run drawn faintly alongside this one
+    dirty: bool,               // the last update changed what's drawn
+    track_changes: bool,       // work out dirty each update, for render_on_change
+
+    // active piece before the last update: pieces placed, type, rotation, position
+    prev_piece: Option<(usize, PieceType, usize, BoardPosition)>,
+    render_alpha: f32, // how far the piece is drawn through its last move, 1.0 = there
 }
 
 impl BoardInstance {
-    pub fn new(
-        id: &str,
-        location: Vec2,
-        width: usize,
-        height: usize,
-        cell_size: f32,
-        gravity_interval: f32,
-        lock_delay: f32,
-    ) -> Self {
+    pub fn new(id: &str, location: Vec2, config: &BoardConfig) -> Self {
+        let seed = config.seed.unwrap_or_else(random);
         //let boundary_color = rgba(0.22, 0.902, 0.082, 1.0);
         //let piece_color = rgba(0.235, 0.851, 0.11, 1.0);
 
-        let boundary_color: Rgba = hsva(40.0 / 360.0, 1.0, 0.75, 1.0).into();
-        let piece_color: Rgba = hsva(40.0 / 360.0, 1.0, 0.7, 1.0).into();
+        let boundary_color: Rgba = hsva(BASE_HUE / 360.0, 1.0, 0.75, 1.0).into();
+        let piece_color: Rgba = hsva(BASE_HUE / 360.0, 1.0, 0.7, 1.0).into();
 
-        let screen_height = height as f32 * cell_size;
-        let screen_width = width as f32 * cell_size;
+        let cell_size = config.cell_size.pixels();
+        let screen_height = config.height as f32 * cell_size;
+        let screen_width = config.width as f32 * cell_size;
+        let next_queue_len = if config.preview.enabled {
+            config.preview.count.max(NEXT_QUEUE_LEN)
+        } else {
+            NEXT_QUEUE_LEN
+        };
 
-        Self {
+        let mut instance = Self {
             id: id.to_owned(),
-            board: Board::new(width, height),
+            board: {
+                let mut board = Board::new(config.width, config.height);
+                board.set_clear_axis(config.clear_axis);
+                board.set_wrap_x(config.wrap_x);
+                board
+            },
+            clear_axis: config.clear_axis,
+            row_weights: config
+                .row_score_weight
+                .weights(config.height, &config.row_score_weights),
             location,
             cell_size,
+            orientation: Orientation::from_degrees(config.orientation),
 
             screen_height,
             screen_width,
 
             color: piece_color,
             boundary_color,
+            base_boundary_color: boundary_color,
+            color_from_next: config.color_from_next,
+            level_lines: config.level_lines,
+            lock_delay: config.lock_delay,
+            lock_delay_curve: config.lock_delay_curve,
+            level_hue_step: config.level_hue_step,
+            level_fade_duration: config.level_fade,
+            level_fade: BackgroundColorFade::new(),
+            lines_cleared: 0,
+            chain_meter: 0.0,
+            chain_per_line: config.chain_per_line,
+            chain_max: config.chain_max,
+            chain_decay: config.chain_decay,
+            chain_thresholds: config.chain_thresholds.clone(),
+            shadow: config.shadow.then(|| {
+                let [x, y] = config.shadow_offset;
+                let [r, g, b, a] = config.shadow_color;
+                (vec2(x, y), rgba(r, g, b, a))
+            }),
+            palette_fade: None,
+            panel_color: config.panel.then(|| {
+                let [r, g, b, a] = config.panel_color;
+                rgba(r, g, b, a)
+            }),
 
             game_state: GameState::Ready,
             prev_game_state: None,
             timers: GameTimers::new(
-                gravity_interval,
-                lock_delay,
+                config.gravity_interval,
+                config.lock_delay,
+                config.entry_delay.max(0.0),
                 CLEAR_DURATION,
                 SLIDE_DURATION,
                 GAME_OVER_DURATION,
-            ),
+                config.idle_reset.max(0.0),
+            )
+            .with_finale(config.game_over_finale.max(0.0))
+            .with_pre_clear(config.pre_clear_flash.max(0.0)),
+
+            idle_reset: config.idle_reset.max(0.0),
+            frozen_input: config.frozen_input,
+            spawn_rule: config.spawn_rule,
+            clearing_input: config.clearing_input,
+            clearing_buffer: Vec::new(),
+            playtime: 0.0,
+
+            game_over_finale: config.game_over_finale > 0.0,
+            finale_rows: 0,
+            mercy_count: config.mercy_count,
+            mercy_rows: config.mercy_rows,
+            mercy_used: 0,
+            auto_hold_assist: config.auto_hold_assist,
+            garbage_delay: config.garbage_delay.max(0.0),
+            pending_garbage: VecDeque::new(),
+            garbage_queued: false,
+            piece_limit: config.piece_limit,
+            time_limit: config.time_limit.max(0.0),
+            pieces_placed: 0,
+            dig_rows: config.dig_rows,
+            dig_points: config.dig_points,
+            garbage_rows: 0,
+            max_dig_depth: 0,
+
+            soft_drop_cells: 0,
+            hard_drop_cells: 0,
+            soft_drop_points: config.soft_drop_points,
+            hard_drop_points: config.hard_drop_points,
+
+            lock_grace: config.lock_grace.max(0.0),
+            since_move: 0.0,
+            hard_drop_lockout: config.hard_drop_lockout.max(0.0),
+            since_soft_drop: f32::INFINITY,
+            grace_left: None,
+            grace_used: false,
+
+            last_kick: None,
+
+            stack_warning: config.stack_warning as isize,
+            stack_warning_band: config.stack_warning_band as isize,
+            stack_high: false,
+            since_stack_high: f32::INFINITY,
+            near_miss_window: config.near_miss_window,
+            slow_mo_factor: config.slow_mo_factor.clamp(0.0, 1.0),
+            slow_mo_duration: config.slow_mo_duration,
+            slow_mo: None,
+
+            beat_interval: None,
+            beat_clock: 0.0,
+            on_beat: false,
+            awaiting_beat: false,
+
+            gravity_per_frame: config.gravity_per_frame.max(0.0),
+            gravity_accumulator: 0.0,
+            gravity_held: false,
+            gravity_enabled: config.gravity_enabled,
+            gravity_multiplier: config.gravity_multiplier.max(MIN_GRAVITY_MULTIPLIER),
+
+            entry_delay: config.entry_delay.max(0.0),
+            irs: config.irs,
+            ihs: config.ihs,
+            initial_rotate: false,
+            initial_hold: false,
+            spawn_telegraph: config.spawn_telegraph,
+            glow: config.glow.clone(),
+            preview: config
+                .preview
+                .enabled
+                .then(|| PreviewLayout::new(&config.preview, cell_size)),
+            hold_layout: PreviewLayout::new(&config.preview, cell_size).mirrored(),
+
+            held: VecDeque::with_capacity(config.hold_capacity),
+            hold_capacity: config.hold_capacity,
+            hold_used: false,
+            dim_used_hold: config.dim_used_hold,
+
+            batch_cells: false,
+            soft_drop_resets_lock: false,
+
+            clear_preview: config.clear_preview,
+            landing: None,
+            clear_flash_background: config.clear_flash_background,
+            manual_clear: config.manual_clear,
+            flip_input: config.flip_input,
+            row_fill_lock: config.row_fill_lock,
+            grounded_lock: config.grounded_lock,
+
+            fill_meter: config.fill_meter,
+            clear_particles: config.clear_particles,
+            max_particles: config.max_particles,
+            particles: Vec::new(),
+
+            show_hint: config.show_hint,
+            show_pivot: config.show_pivot,
+            debug_axes: config.debug_axes,
+            hint: None,
+
+            placement_overlay: config.placement_overlay,
+            placement_overlay_max: config.placement_overlay_max,
+            overlay: Vec::new(),
+
+            saved_pieces: HashMap::new(),
 
             rows_to_clear: None,
+            cols_to_clear: None,
+            pre_clear_flashing: false,
             active_piece: None,
-        }
+            next_queue: VecDeque::with_capacity(next_queue_len),
+            next_queue_len,
+            scripted_len: 0,
+
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            garbage_rng: StdRng::seed_from_u64(seed ^ GARBAGE_SEED_SALT),
+            randomizer: new_randomizer(config.randomizer, config.piece_weights),
+            randomizer_kind: config.randomizer,
+            shared: None,
+            shared_pos: 0,
+            piece_weights: config.piece_weights,
+            reseed_on_reset: config.reseed_on_reset,
+            drill_piece: config.drill_piece,
+
+            sound_cues: Vec::new(),
+            event_handlers: Vec::new(),
+            emit: true,
+            soloed_out: false,
+
+            frame: 0,
+            recording: None,
+            record: false,
+            ghost: None,
+            cell_texture: None,
+            faint_texture: None,
+            dirty: true,
+            track_changes: false,
+
+            prev_piece: None,
+            render_alpha: 1.0,
+        };
+        instance.fill_garbage();
+        instance
     }
 
     /************************ Update orchestrator *******************************/
 
+    pub fn update(&mut self, dt: f32, inputs: &[PlayerInput]) {
+        let before = self
+            .track_changes
+            .then(|| (self.snapshot(), self.game_state, self.garbage_waiting()));
+        self.prev_piece = self
+            .active_piece
+            .as_ref()
+            .map(|p| (self.pieces_placed, p.typ, p.rot_idx, p.position));
+        let dt = self.update_slow_mo(dt);
+
+        self.update_palette(dt);
+        self.update_boundary_color(dt);
+
+        if matches!(
+            self.game_state,
+            GameState::Ready | GameState::Falling | GameState::Locking { .. } | GameState::Clearing
+        ) {
+            self.playtime += dt;
+            for (_, wait) in self.pending_garbage.iter_mut() {
+                *wait -= dt;
+            }
+            self.set_chain_meter(self.chain_meter - self.chain_decay * dt);
+        }
+
+        // Sparks hang in the air while paused
+        if self.game_state != GameState::Paused {
+            self.particles.retain_mut(|particle| particle.update(dt));
+        }
+
+        self.update_beat(dt);
+        self.update_state(dt, inputs);
+        if self.clear_preview {
+            self.refresh_landing();
+        }
+        self.update_stack_warning();
+        self.check_game_limits();
+
+        self.frame += 1;
+        if self.recording.is_some() {
+            let snapshot = self.snapshot();
+            if let Some(recording) = self.recording.as_mut() {
+                recording.record(&self.board, snapshot);
+            }
+        }
+
+        // Without render_on_change every frame is drawn, so skip the snapshots
+        self.dirty = match before {
+            Some((snapshot, state, waiting)) => {
+                !inputs.is_empty()
+                    || self.game_state != state
+                    || self.snapshot() != snapshot
+                    || self.garbage_waiting() != waiting
+                    || self.garbage_queued
+                    || self.is_animating()
+            }
+            None => true,
+        };
+        self.garbage_queued = false;
+    }
+
+    // Fixed timestep: how far real time is from the last step to the next,
+    // 0.0 to 1.0. The active piece is drawn that far along its last step's move.
+    pub fn set_render_alpha(&mut self, alpha: f32) {
+        let before = self.piece_draw_offset();
+        self.render_alpha = alpha.clamp(0.0, 1.0);
+        self.dirty |= self.piece_draw_offset() != before;
+    }
+
+    // Screen offset from the active piece's cells to where it's drawn. Only a
+    // slide or fall is eased; a new piece, a rotation or a wrap shows at once.
+    fn piece_draw_offset(&self) -> Vec2 {
+        let (Some(piece), Some((placed, typ, rot_idx, from))) =
+            (&self.active_piece, self.prev_piece)
+        else {
+            return Vec2::ZERO;
+        };
+        let dx = from.x - piece.position.x;
+        let dy = from.y - piece.position.y;
+        if placed != self.pieces_placed
+            || typ != piece.typ
+            || rot_idx != piece.rot_idx
+            || dx.abs() > 1
+        {
+            return Vec2::ZERO;
+        }
+        vec2(dx as f32, dy as f32) * self.cell_size * (1.0 - self.render_alpha)
+    }
+
+    // Whether the last update changed anything on screen, so a still board
+    // can skip the redraw
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    // Something is drawn differently from frame to frame without the board changing
+    fn is_animating(&self) -> bool {
+        let boundary = self.boundary_color;
+        let target = self.boundary_target();
+        let settling = [
+            boundary.red - target.red,
+            boundary.green - target.green,
+            boundary.blue - target.blue,
+        ]
+        .iter()
+        .any(|d| d.abs() > BOUNDARY_SETTLED);
+
+        settling
+            || self.palette_fade.is_some()
+            || !self.particles.is_empty()
+            || self.game_state == GameState::Clearing
+            || self.game_state == GameState::GameOver
+            || (self.effective_state() == GameState::Ready && self.spawn_telegraph().is_some())
+            || self.ghost_has_frame()
+    }
+
+    // Fire StackHigh/StackCleared as the tallest column crosses the warning
+    // height, with a band between them so a stack sitting on the line doesn't flap
+    fn update_stack_warning(&mut self) {
+        if self.stack_warning <= 0 {
+            return;
+        }
+
+        let height = self.stack_height();
+        if !self.stack_high && height >= self.stack_warning {
+            self.stack_high = true;
+            self.since_stack_high = 0.0;
+            self.fire(SoundCue::StackHigh);
+        } else if self.stack_high && height < self.stack_warning - self.stack_warning_band {
+            self.stack_high = false;
+            self.fire(SoundCue::StackCleared);
+            if self.near_miss_window > 0.0 && self.since_stack_high <= self.near_miss_window {
+                self.slow_mo = Some(Timer::new(self.slow_mo_duration));
+            }
+        }
+    }
+
+    // Run the near-miss slow motion on real time and return the game's dt
+    fn update_slow_mo(&mut self, dt: f32) -> f32 {
+        let scaled = dt * self.time_scale();
+        if self.game_state != GameState::Paused {
+            self.since_stack_high += scaled;
+            if self.slow_mo.as_mut().is_some_and(|timer| timer.tick(dt)) {
+                self.slow_mo = None;
+            }
+        }
+        scaled
+    }
+
+    // Game speed, below 1.0 during near-miss slow motion: it starts at
+    // slow_mo_factor and eases back to full speed
+    pub fn time_scale(&self) -> f32 {
+        let Some(timer) = &self.slow_mo else {
+            return 1.0;
+        };
+        let t = timer.progress();
+        let eased = t * t * (3.0 - 2.0 * t);
+        self.slow_mo_factor + (1.0 - self.slow_mo_factor) * eased
+    }
+
+    // Timed and fixed-length games end between pieces, after any clear is scored.
+    // A piece still in play when time runs out locks and scores first.
+    fn check_game_limits(&mut self) {
+        if self.game_state != GameState::Ready {
+            return;
+        }
+
+        let pieces_up = self.piece_limit > 0 && self.pieces_placed >= self.piece_limit;
+        let time_up = self.time_limit > 0.0 && self.playtime >= self.time_limit;
+        if pieces_up || time_up {
+            if DEBUG {
+                let limit = if pieces_up {
+                    "piece limit"
+                } else {
+                    "time limit"
+                };
+                println!(
+                    "<{}> reached the {} with {} points",
+                    self.id,
+                    limit,
+                    self.score()
+                );
+            }
+            self.win();
+        }
+    }
+
+    // Height of the tallest column, in rows
+    pub fn stack_height(&self) -> isize {
+        self.board
+            .col_score_all()
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+
     // Game State Machine
-    pub fn update(&mut self, dt: f32, input: &Option<PlayerInput>, rng: &mut ThreadRng) {
+    fn update_state(&mut self, dt: f32, inputs: &[PlayerInput]) {
+        self.since_move += dt;
+        self.since_soft_drop += dt;
+
         match self.game_state {
             GameState::Ready => {
+                self.fill_next_queue();
+                self.buffer_initial_actions(inputs);
+
+                // Wait out the entry delay; pause is the only meaningful input here
+                if !self.timers.entry.tick(dt) {
+                    self.handle_inputs(inputs);
+                    return;
+                }
+
                 // Spawn a new piece
-                if self.spawn_new_piece(rng) {
+                if self.spawn_new_piece() {
                     self.timers.reset_all();
+                    self.gravity_accumulator = 0.0;
                     self.game_state = GameState::Falling;
+                    self.apply_initial_actions();
+                    let buffered = std::mem::take(&mut self.clearing_buffer);
+                    self.handle_inputs(&buffered);
                 } else {
                     self.timers.reset_all();
                     self.game_state = GameState::GameOver;
+                    self.fire(SoundCue::GameOver);
                 }
             }
 
             GameState::Falling => {
                 // Handle an active piece
-                if let Some(input) = input {
-                    self.handle_input(input);
+                self.handle_inputs(inputs);
+
+                // Input may have locked (hard drop, filled row) or paused the piece
+                if self.game_state != GameState::Falling || self.gravity_held {
+                    return;
                 }
 
-                if self.timers.gravity.tick(dt) {
-                    // Apply gravity and check the result
-                    if let Some(piece) = self.active_piece.as_mut() {
-                        if Self::is_piece_at_bottom(piece) {
-                            // Don't attempt to move below the bottom of the board
-                            if DEBUG {
-                                println!("Piece fell to bottom. Transition to Locking");
-                            }
-                            self.game_state = GameState::Locking {
-                                now: false,
-                                hard_drop: false,
-                            };
-                        } else {
-                            let next_pos = BoardPosition {
-                                x: piece.position.x,
-                                y: piece.position.y - 1,
-                            };
-
-                            let result = self.board.try_place(piece, next_pos);
-                            match result {
-                                PlaceResult::PlaceOk => {
-                                    // Piece moved down successfully, continue in Falling state
-                                    piece.position = next_pos;
-                                    self.timers.gravity.reset();
-                                    self.game_state = GameState::Falling;
-                                }
-                                PlaceResult::RowFilled => {
-                                    // Row was filled by gravity, immediately commit and clear
-                                    piece.position = next_pos;
-                                    self.game_state = GameState::Locking {
-                                        now: true,
-                                        hard_drop: false,
-                                    };
-                                }
-                                _ => {
-                                    if DEBUG {
-                                        println!("No valid falling position, now locking.");
-                                    }
-                                    self.game_state = GameState::Locking {
-                                        now: false,
-                                        hard_drop: false,
-                                    };
-                                }
-                            }
-                        }
+                // Sandbox: nothing falls by itself; a piece resting on something locks
+                if !self.gravity_enabled {
+                    if self.is_grounded() {
+                        self.game_state = GameState::Locking {
+                            now: false,
+                            hard_drop: false,
+                        };
                     }
+                    return;
+                }
+
+                if self.gravity_per_frame > 0.0 {
+                    self.apply_frame_gravity();
+                } else if self.timers.gravity.tick(dt * self.gravity_multiplier) {
+                    self.apply_gravity();
                 }
             }
 
-            GameState::Locking { now, hard_drop } => {
+            GameState::Locking { now, .. } => {
                 // Immediate piece commit if "now"
                 if now {
+                    // Rhythm mode: hold the piece until the beat
+                    if !self.lock_on_beat() {
+                        return;
+                    }
+
                     if DEBUG {
                         println!("Immediate lock");
                     }
 
-                    self.score_piece(hard_drop);
-                    self.rows_to_clear = self.commit_piece();
-                    if self.rows_to_clear.is_some() {
-                        self.game_state = GameState::Clearing;
-                    } else {
-                        self.game_state = GameState::Ready;
-                    }
+                    self.lock_active_piece();
                     return;
                 }
 
                 // Last-minute adjustment period for piece
-                if let Some(input) = input {
-                    self.handle_input(input);
+                self.handle_inputs(inputs);
+
+                // A filled row commits on the next frame, same as when reached by gravity
+                if !matches!(self.game_state, GameState::Locking { now: false, .. }) {
+                    return;
                 }
 
                 // Check if the piece can now fall because of some input during the Locking period
+                let defers_filled_rows = self.defers_filled_rows();
+                let grounded = self.is_grounded();
                 if let Some(piece) = self.active_piece.as_mut() {
-                    if Self::is_piece_at_bottom(piece) {
-                        // Don't attempt to move below the bottom of the board
+                    if grounded {
+                        // Don't attempt to move below what it rests on
                         if DEBUG {
-                            println!("Piece at bottom. Lock timer at {:?}", self.timers.lock);
+                            println!("Piece grounded. Lock timer at {:?}", self.timers.lock);
                         }
                     } else {
                         // Try move the piece 1 row down
@@ -217,40 +925,35 @@ impl BoardInstance {
                             y: piece.position.y - 1,
                         };
 
-                        if self.board.try_place(piece, next_pos) == PlaceResult::PlaceOk {
+                        let result = self.board.try_place(piece, next_pos);
+                        if result == PlaceResult::PlaceOk
+                            || (result == PlaceResult::RowFilled && defers_filled_rows)
+                        {
                             piece.position = next_pos;
                             self.timers.lock.reset();
                             self.timers.gravity.reset();
+                            self.awaiting_beat = false;
                             self.game_state = GameState::Falling;
 
                             if DEBUG {
                                 println!("Was Locking but now Falling again");
                                 println!("Piece is now at {:?}", next_pos);
                             }
+                        } else if self.grounded_lock == GroundedLock::Floor
+                            && matches!(result, PlaceResult::OutOfBounds | PlaceResult::PlaceBad)
+                        {
+                            // Moved onto the stack, which isn't ground for it: fall in place
+                            // until gravity finds it held up and starts a fresh lock delay
+                            self.timers.lock.reset();
+                            self.awaiting_beat = false;
+                            self.game_state = GameState::Falling;
                         }
                     }
                 }
 
                 // Commit the piece, check for filled rows, return to Ready state.
-                if self.timers.lock.tick(dt) {
-                    self.score_piece(hard_drop);
-                    self.rows_to_clear = self.commit_piece();
-
-                    if self.rows_to_clear.is_some() {
-                        self.game_state = GameState::Clearing;
-
-                        if DEBUG {
-                            println!("Was Locked but now Clearing");
-                        }
-
-                    // Piece is locked and return to Ready state
-                    } else {
-                        self.game_state = GameState::Ready;
-
-                        if DEBUG {
-                            println!("Was Locked but now Ready");
-                        }
-                    }
+                if (self.awaiting_beat || self.lock_timer_done(dt)) && self.lock_on_beat() {
+                    self.lock_active_piece();
 
                     if DEBUG {
                         print_col_score(self.board.col_score_all());
@@ -259,13 +962,17 @@ impl BoardInstance {
             }
 
             GameState::Clearing => {
+                self.buffer_clearing_inputs(inputs);
+
                 // Give the game a chance to pause
-                if let Some(input) = input {
-                    self.handle_input(input);
-                }
+                self.handle_inputs(inputs);
 
-                // Let the animation run
-                if self.timers.clear_animation.tick(dt) {
+                // Flash the cleared cells, then let the animation run
+                if self.pre_clear_flashing {
+                    if self.timers.pre_clear.tick(dt) {
+                        self.pre_clear_flashing = false;
+                    }
+                } else if self.timers.clear_animation.tick(dt) {
                     // Animation done, now update the model
 
                     if DEBUG {
@@ -273,10 +980,23 @@ impl BoardInstance {
                         print_col_score(self.board.col_score_all());
                     }
 
-                    if let Some(rows) = self.rows_to_clear.take() {
-                        self.score_row_clear(rows.len());
+                    // Columns first: sideways slides keep row indices valid
+                    let cols = self.cols_to_clear.take().unwrap_or_default();
+                    let rows = self.rows_to_clear.take().unwrap_or_default();
+                    let dug = self.garbage_rows_in(&rows);
+                    if self.dig_rows > 0 {
+                        self.board.add_score(dug * self.dig_points);
+                    } else {
+                        self.score_row_clear(&rows, cols.len());
+                    }
+                    self.count_lines(rows.len() + cols.len());
+                    if !cols.is_empty() {
+                        self.board.clear_cols(&cols);
+                    }
+                    if !rows.is_empty() {
                         self.clear_rows(&rows)
                     }
+                    self.dig(dug);
 
                     // Reset timer and return to Ready state
                     self.timers.clear_animation.reset();
@@ -287,561 +1007,5008 @@ impl BoardInstance {
             GameState::GameOver => {
                 // Grid has been filled to the top
                 self.commit_piece();
-                if let Some(input) = input {
-                    self.handle_input(input);
-                }
+                self.handle_inputs(inputs);
                 if self.timers.game_over_animation.tick(dt) {
+                    self.timers.idle.reset();
+                    self.finale_rows = 0;
+                    self.game_state = if self.game_over_finale {
+                        GameState::Finale
+                    } else {
+                        GameState::Frozen
+                    };
+                }
+            }
+
+            GameState::Finale => {
+                self.handle_inputs(inputs);
+                if self.game_state != GameState::Finale {
+                    return;
+                }
+
+                // Clear rows from the bottom in step with the finale timer
+                let done = self.timers.finale.tick(dt);
+                let progress = if done {
+                    1.0
+                } else {
+                    self.timers.finale.progress()
+                };
+                let height = self.board.height;
+                let target = ((progress * height as f32).ceil() as isize).min(height);
+                while self.finale_rows < target {
+                    self.board.clear_row(self.finale_rows);
+                    self.finale_rows += 1;
+                }
+
+                if done {
+                    self.timers.idle.reset();
                     self.game_state = GameState::Frozen;
                 }
             }
 
-            GameState::Frozen => {
+            GameState::Frozen | GameState::GameWon => {
+                let ended = self.game_state;
+
+                // Arcade mode: any key plays again
+                if self.frozen_input == FrozenInput::Restart && !inputs.is_empty() {
+                    self.reset();
+                    return;
+                }
+
                 // Game Over, freeze the game.
-                if let Some(input) = input {
-                    self.handle_input(input);
+                self.handle_inputs(inputs);
+
+                // Unattended: start a fresh game
+                if self.game_state == ended && self.idle_reset > 0.0 && self.timers.idle.tick(dt) {
+                    self.reset();
                 }
             }
 
             GameState::Paused => {
                 // Pause the game
-                if let Some(input) = input {
-                    self.handle_pause_input(input);
-                }
+                self.handle_inputs(inputs);
             }
         }
     }
 
-    /************************ Update loop methods ***************************/
-    fn spawn_new_piece(&mut self, rng: &mut ThreadRng) -> bool {
-        // Randomize new piece properties and create
-        let piece_type = self.get_random_piece_type(rng);
-        let color = self.get_piece_color();
-
-        let spawn_pos = BoardPosition {
-            x: self.board.midpoint_x() - piece_type.max_x(0) / 2,
-            y: self.board.height - piece_type.max_y(0) - 1,
-        };
-
-        let new_piece = PieceInstance::new(piece_type, color, spawn_pos);
-
-        // Verify that piece can be placed
-        let can_place = matches!(
-            self.board.try_place(&new_piece, spawn_pos),
-            PlaceResult::PlaceOk | PlaceResult::RowFilled
-        );
-
-        if can_place && DEBUG {
-            spawn_new_piece_msg(&new_piece);
+    // Start a fresh game on an empty board. The piece sequence carries on
+    // unless reseed_on_reset says otherwise.
+    pub fn reset(&mut self) {
+        match self.reseed_on_reset {
+            ReseedOnReset::Continue => {}
+            // a shared sequence can't be reseeded by one board, only started over
+            _ if self.shared.is_some() => {
+                self.shared_pos = 0;
+                self.next_queue.truncate(self.scripted_len);
+            }
+            ReseedOnReset::Same => self.reseed(self.seed),
+            ReseedOnReset::Increment => self.reseed(self.seed.wrapping_add(1)),
+            ReseedOnReset::Random => self.reseed(random()),
+        }
+        // A ghost is only raced on the sequence it played
+        if let Some(seed) = self.ghost.as_ref().map(|ghost| ghost.seed) {
+            if self.shared.is_none() {
+                self.reseed(seed);
+            }
+        }
+        let wrap_x = self.board.wrap_x();
+        self.board = Board::new(self.board.width as usize, self.board.height as usize);
+        self.board.set_clear_axis(self.clear_axis);
+        self.board.set_wrap_x(wrap_x);
+        self.game_state = GameState::Ready;
+        self.prev_game_state = None;
+        self.timers.reset_all();
+        self.timers.resume_all();
+        self.gravity_accumulator = 0.0;
+        self.playtime = 0.0;
+        self.rows_to_clear = None;
+        self.cols_to_clear = None;
+        self.active_piece = None;
+        self.awaiting_beat = false;
+        self.finale_rows = 0;
+        self.mercy_used = 0;
+        self.pending_garbage.clear();
+        self.pieces_placed = 0;
+        self.chain_meter = 0.0;
+        self.slow_mo = None;
+        self.since_stack_high = f32::INFINITY;
+        self.garbage_rows = 0;
+        self.max_dig_depth = 0;
+        self.fill_garbage();
+        self.stack_high = false;
+        self.initial_rotate = false;
+        self.initial_hold = false;
+        self.clearing_buffer.clear();
+        self.particles.clear();
+        self.held.clear();
+        self.hold_used = false;
+        self.frame = 0;
+        if self.record {
+            self.start_recording();
+        }
+        if self.level() > 0 {
+            self.lines_cleared = 0;
+            self.start_level_fade();
+            self.apply_lock_delay_curve();
         }
-
-        self.active_piece = Some(new_piece);
-        can_place
     }
 
-    // Freeze a piece in place
-    fn commit_piece(&mut self) -> Option<Vec<isize>> {
-        self.active_piece
-            .take()
-            .and_then(|piece| self.board.commit_piece(&piece))
+    // Test helper: jump straight to a state over a grid laid out as in
+    // Board::set_grid_ascii. Clearing picks up the grid's full lines.
+    #[cfg(test)]
+    pub fn set_state_for_test(&mut self, state: GameState, grid_ascii: &str) {
+        self.board.set_grid_ascii(grid_ascii);
+        self.active_piece = None;
+        self.timers.reset_all();
+        self.rows_to_clear = None;
+        self.cols_to_clear = None;
+        self.pre_clear_flashing = false;
+        if state == GameState::Clearing {
+            self.rows_to_clear = Some(self.board.full_rows()).filter(|rows| !rows.is_empty());
+            self.cols_to_clear = Some(self.board.full_cols()).filter(|cols| !cols.is_empty());
+        }
+        self.game_state = state;
     }
 
-    fn clear_rows(&mut self, rows: &[isize]) {
-        self.board.clear_rows(rows);
-        if DEBUG {
-            print_col_score(self.board.col_score_all());
+    // Continue from a saved grid with a fresh piece
+    pub fn restore(&mut self, state: BoardState) -> bool {
+        if !self.board.restore(state) {
+            return false;
         }
+        self.garbage_rows = 0; // saves don't say which rows are garbage
+        self.game_state = GameState::Ready;
+        self.prev_game_state = None;
+        self.timers.reset_all();
+        self.timers.resume_all();
+        self.rows_to_clear = None;
+        self.cols_to_clear = None;
+        self.active_piece = None;
+        self.awaiting_beat = false;
+        true
     }
 
-    /**************** Player input methods that affect GameState ******************/
+    /************************ Update loop methods ***************************/
 
-    // Player-induced drop down to lowest legal position
-    fn hard_drop(&mut self) {
-        //Calculate a valid drop position
-        if let Some((drop_pos, result)) = self.get_drop_position() {
+    // Move the active piece down one row, or start locking if it can't move.
+    // Returns true if the piece moved and is still falling.
+    fn apply_gravity(&mut self) -> bool {
+        if self.is_grounded() {
+            // Don't attempt to move below what the piece rests on
             if DEBUG {
-                println!("Drop location y is {:?}", drop_pos);
+                println!("Piece landed. Transition to Locking");
             }
-
-            let Some(piece) = self.active_piece.as_mut() else {
-                return;
+            self.game_state = GameState::Locking {
+                now: false,
+                hard_drop: false,
             };
+            return false;
+        }
 
-            match result {
-                PlaceResult::PlaceOk => {
-                    piece.position = drop_pos;
-                    self.timers.lock.reset();
-                    self.game_state = GameState::Locking {
-                        now: false,
-                        hard_drop: true,
-                    };
-                    if DEBUG {
-                        println!("Hard Drop - PlaceOk at {:?}", drop_pos);
-                    }
-                }
-                PlaceResult::RowFilled => {
-                    piece.position = drop_pos;
-                    self.game_state = GameState::Locking {
-                        now: true,
-                        hard_drop: true,
-                    };
-                    if DEBUG {
-                        println!("Hard Drop - RowFilled");
-                    }
-                }
-                PlaceResult::OutOfBounds | PlaceResult::PlaceBad => {
-                    if DEBUG {
-                        println!("Hard Drop - PlaceBad / OOB");
-                    }
-                }
-            }
-        }
-    }
-
-    // Generalized function to handle moving a piece to any position
-    fn move_active_piece(&mut self, new_pos: BoardPosition) {
-        let Some(result) = self.try_piece_movement(new_pos) else {
-            return;
+        let Some(piece) = self.active_piece.as_mut() else {
+            return false;
         };
 
-        let Some(piece) = self.active_piece.as_mut() else {
-            return;
+        let next_pos = BoardPosition {
+            x: piece.position.x,
+            y: piece.position.y - 1,
         };
 
+        let result = self.board.try_place(piece, next_pos);
         match result {
             PlaceResult::PlaceOk => {
-                piece.position = new_pos;
+                // Piece moved down successfully, continue in Falling state
+                piece.position = next_pos;
+                self.timers.gravity.reset();
+                self.game_state = GameState::Falling;
+                true
             }
             PlaceResult::RowFilled => {
-                piece.position = new_pos;
+                // Row was filled by gravity: commit and clear now, or fall on if deferred
+                piece.position = next_pos;
+                self.timers.gravity.reset();
+                self.lock_filled_row(false)
+            }
+            _ => {
+                if DEBUG {
+                    println!("No valid falling position, now locking.");
+                }
                 self.game_state = GameState::Locking {
-                    now: true,
+                    now: false,
                     hard_drop: false,
                 };
+                false
             }
-            PlaceResult::OutOfBounds | PlaceResult::PlaceBad => {}
         }
     }
 
-    fn rotate_active_piece(&mut self) {
-        if let Some(piece) = &mut self.active_piece {
-            // Only clockwise rotations supported
-            let rotation_direction = RotationDirection::Cw;
-
-            // Try to find a valid position with wall kicks
-            if let Some(new_pos) = self.board.try_rotation(piece, &rotation_direction) {
-                // Apply rotation and position
-                piece.rotate(&rotation_direction);
-                piece.position = new_pos;
+    // Sub-cell gravity: accumulate fractional rows each frame and step the piece
+    // down one row at a time so every intermediate row is collision checked.
+    fn apply_frame_gravity(&mut self) {
+        self.gravity_accumulator += self.gravity_per_frame * self.gravity_multiplier;
+
+        while self.gravity_accumulator >= 1.0 && self.game_state == GameState::Falling {
+            self.gravity_accumulator -= 1.0;
+            if !self.apply_gravity() {
+                // Landed: leftover fractional gravity shouldn't carry to the next piece
+                self.gravity_accumulator = 0.0;
             }
         }
     }
 
-    /**************** Piece movement helper methods ******************/
+    fn spawn_new_piece(&mut self) -> bool {
+        self.hold_used = false;
+        let piece_type = self.take_next_piece();
+        if self.place_new_piece(piece_type) {
+            return true;
+        }
+        if self.auto_hold_assist && self.auto_hold() {
+            return true;
+        }
 
-    // Test movement validity
-    fn try_piece_movement(&mut self, new_pos: BoardPosition) -> Option<PlaceResult> {
-        self.active_piece
-            .as_ref()
-            .map(|piece| self.board.try_place(piece, new_pos))
+        // Topped out: spend a mercy on clearing the top of the stack and retry
+        if self.mercy_used >= self.mercy_count {
+            return false;
+        }
+        self.mercy_used += 1;
+        self.board.clear_top_rows(self.mercy_rows);
+        self.boundary_color = rgba(1.0, 1.0, 1.0, 1.0); // white flash, eases back to the target
+        self.place_new_piece(piece_type)
     }
 
-    // Obtain the valid hard drop position of the currently active piece
-    fn get_drop_position(&mut self) -> Option<(BoardPosition, PlaceResult)> {
-        self.active_piece
-            .as_ref()
-            .map(|piece| self.board.calculate_drop(piece))
+    // The active piece can't spawn: hold it if the piece coming back (or the
+    // next one) can, and land somewhere. False leaves the top-out to the caller.
+    fn auto_hold(&mut self) -> bool {
+        if self.hold_capacity == 0 || self.hold_used {
+            return false;
+        }
+        let swap = if self.held.len() >= self.hold_capacity {
+            self.held.front()
+        } else {
+            self.next_queue.front()
+        };
+        let Some(&swap) = swap else {
+            return false;
+        };
+        let piece = self.spawn_piece(swap);
+        let fits = matches!(
+            self.board.try_place(&piece, piece.position),
+            PlaceResult::PlaceOk | PlaceResult::RowFilled
+        );
+        let lands = !self.board.legal_placements(&piece).is_empty();
+        if !fits || !lands {
+            return false;
+        }
+
+        self.hold();
+        self.fire(SoundCue::AutoHold);
+        self.boundary_color = rgba(0.0, 1.0, 1.0, 1.0); // cyan flash, eases back to the target
+        true
     }
 
-    // Checks that a piece is at the bottom of the grid
-    fn is_piece_at_bottom(piece: &PieceInstance) -> bool {
-        // Check if any cell is at y=0
-        piece.cells().iter().any(|&(_dx, dy)| {
-            let cell_y = piece.position.y + dy;
-            cell_y == 0
-        })
+    // Incoming garbage from an opponent, telegraphed before it rises
+    pub fn queue_garbage(&mut self, rows: usize) {
+        if rows > 0 {
+            self.pending_garbage.push_back((rows, self.garbage_delay));
+            self.garbage_queued = true;
+        }
     }
 
-    /************************ Piece creation methods ************************/
-    // Obtain a random PieceType
-    fn get_random_piece_type(&self, rng: &mut ThreadRng) -> PieceType {
-        let idx = rng.gen_range(0.0..7.0).trunc() as usize;
-        PieceType::from_idx(idx)
+    pub fn pending_garbage(&self) -> usize {
+        self.pending_garbage.iter().map(|&(rows, _)| rows).sum()
     }
 
-    // Get the piece's color; currently all pieces are the same color so just returns
-    // the board's filled cell color.
-    fn get_piece_color(&self) -> Rgba {
-        self.color
+    // Batches the meter still shows dimmed; one brightens as its wait runs out
+    fn garbage_waiting(&self) -> usize {
+        self.pending_garbage
+            .iter()
+            .filter(|&&(_, wait)| wait > 0.0)
+            .count()
     }
 
-    /************************ Scoring methods **************************************/
-    fn score_piece(&mut self, hard_drop: bool) {
-        if let Some(piece) = &self.active_piece {
-            self.board.score_piece(piece, hard_drop);
+    // Knock cleared lines off the oldest pending garbage first
+    fn cancel_garbage(&mut self, mut lines: usize) {
+        while lines > 0 {
+            let Some((rows, _)) = self.pending_garbage.front_mut() else {
+                return;
+            };
+            let cancelled = lines.min(*rows);
+            *rows -= cancelled;
+            lines -= cancelled;
+            if *rows == 0 {
+                self.pending_garbage.pop_front();
+            }
         }
     }
 
-    fn score_row_clear(&mut self, number_of_rows: usize) {
-        self.board.score_cleared_rows(number_of_rows);
+    // Raise every batch whose delay is over. Returns false on a top-out.
+    fn raise_due_garbage(&mut self) -> bool {
+        while let Some(&(rows, wait)) = self.pending_garbage.front() {
+            if wait > 0.0 {
+                break;
+            }
+            self.pending_garbage.pop_front();
+            let hole = self.garbage_rng.gen_range(0..self.board.width);
+            self.garbage_rows += rows;
+            if !self.board.add_garbage(rows, hole) {
+                return false;
+            }
+        }
+        true
     }
 
-    pub fn score(&self) -> usize {
-        self.board.score()
+    // Dig mode: lay dig_rows rows of garbage under the stack, a random gap in each
+    fn fill_garbage(&mut self) {
+        for _ in 0..self.dig_rows {
+            let hole = self.garbage_rng.gen_range(0..self.board.width);
+            self.board.add_garbage(1, hole);
+            self.garbage_rows += 1;
+        }
     }
 
-    /************************ Input handling methods *******************************/
-
-    fn handle_input(&mut self, input: &PlayerInput) {
-        match input {
-            PlayerInput::L => {
-                if let Some(piece) = self.active_piece.as_mut() {
-                    let new_pos = BoardPosition {
-                        x: piece.position.x - 1,
-                        y: piece.position.y,
-                    };
-
-                    self.move_active_piece(new_pos);
-                }
-            }
-            PlayerInput::R => {
-                if let Some(piece) = self.active_piece.as_mut() {
-                    let new_pos = BoardPosition {
-                        x: piece.position.x + 1,
-                        y: piece.position.y,
-                    };
+    // How many of the cleared rows are garbage; it sits at the bottom of the stack
+    fn garbage_rows_in(&self, rows: &[isize]) -> usize {
+        rows.iter()
+            .filter(|&&row| row < self.garbage_rows as isize)
+            .count()
+    }
 
-                    self.move_active_piece(new_pos);
-                }
-            }
-            PlayerInput::Rotate => {
-                self.rotate_active_piece();
-            }
-            PlayerInput::HardDrop => {
-                self.hard_drop();
+    // Garbage rows were cleared: count the depth, and in dig mode replace them
+    // from below. The stack ends up no higher than before, so it can't top out.
+    fn dig(&mut self, rows: usize) {
+        self.garbage_rows -= rows;
+        self.max_dig_depth += rows;
+        if self.dig_rows > 0 {
+            for _ in 0..rows {
+                let hole = self.garbage_rng.gen_range(0..self.board.width);
+                self.board.add_garbage(1, hole);
+                self.garbage_rows += 1;
             }
-            PlayerInput::Pause => {
-                self.handle_pause();
-            }
-            _ => {}
         }
     }
 
-    fn handle_pause_input(&mut self, input: &PlayerInput) {
-        // ignore everything except Pause
-        match input {
-            PlayerInput::Pause => {
-                self.handle_pause();
-            }
-            PlayerInput::SaveState => {
-                self.board.save_state();
-                self.active_piece = None;
-                self.game_state = GameState::Ready
-            }
-            PlayerInput::ResumeState => {
-                self.board.resume_state();
-                self.active_piece = None;
-                self.game_state = GameState::Ready
+    // Garbage rows dug this game, for the dig mode HUD
+    pub fn max_dig_depth(&self) -> usize {
+        self.max_dig_depth
+    }
+
+    pub fn is_dig_mode(&self) -> bool {
+        self.dig_rows > 0
+    }
+
+    pub fn mercies_left(&self) -> usize {
+        self.mercy_count.saturating_sub(self.mercy_used)
+    }
+
+    // IRS/IHS: remember a rotate or hold pressed before the piece is out
+    fn buffer_initial_actions(&mut self, inputs: &[PlayerInput]) {
+        self.initial_rotate |= self.irs && inputs.contains(&PlayerInput::Rotate);
+        self.initial_hold |= self.ihs && inputs.contains(&PlayerInput::Hold);
+    }
+
+    // Keep moves and rotations made during a clear for the next piece, up to
+    // a board's width of them
+    fn buffer_clearing_inputs(&mut self, inputs: &[PlayerInput]) {
+        if self.clearing_input != ClearingInput::Buffer {
+            return;
+        }
+        let max = self.board.width as usize;
+        for &input in inputs {
+            if matches!(input, PlayerInput::L | PlayerInput::R | PlayerInput::Rotate)
+                && self.clearing_buffer.len() < max
+            {
+                self.clearing_buffer.push(input);
             }
-            _ => {}
         }
     }
 
-    // When paused, ignore piece movement inputs
-    fn handle_pause(&mut self) {
-        if self.game_state == GameState::Paused {
-            // Exiting pause state
-            self.game_state = self.prev_game_state.take().unwrap_or(GameState::Ready);
-            self.timers.resume_all();
-            // Restore timers if pause state exists
-        } else {
-            // Entering pause state
-            self.prev_game_state = Some(self.game_state);
-            self.game_state = GameState::Paused;
-            self.timers.pause_all();
+    // Apply buffered IHS then IRS to the piece that just spawned
+    fn apply_initial_actions(&mut self) {
+        if std::mem::take(&mut self.initial_hold) {
+            self.hold();
+        }
+        if std::mem::take(&mut self.initial_rotate) && self.game_state == GameState::Falling {
+            self.rotate_active_piece();
         }
     }
 
-    /************************ Drawing methods *******************************/
+    // Take the next piece from the queue
+    fn take_next_piece(&mut self) -> PieceType {
+        self.fill_next_queue();
+        let piece_type = self
+            .next_queue
+            .pop_front()
+            .unwrap_or_else(|| self.get_random_piece_type());
+        self.scripted_len = self.scripted_len.saturating_sub(1);
+        self.fill_next_queue();
+        piece_type
+    }
 
-    // Draw orchestrator
-    pub fn draw(&self, draw: &Draw) {
-        // Allow for pausing during clearing animation
-        let effective_state = if self.game_state == GameState::Paused {
-            self.prev_game_state.unwrap_or(self.game_state)
-        } else {
-            self.game_state
-        };
+    // Create a piece at the spawn position. Returns false if it doesn't fit.
+    fn place_new_piece(&mut self, piece_type: PieceType) -> bool {
+        let new_piece = self.spawn_piece(piece_type);
+        let spawn_pos = new_piece.position;
 
-        // GameOver animation handling
-        let mut game_over_line_pos = f32::MIN;
-        if effective_state == GameState::GameOver {
-            game_over_line_pos = {
-                let progress = self.timers.game_over_animation.progress();
-                let top_bound = self.screen_height / 2.0 + self.location.y;
-                let bottom_bound = self.location.y - self.screen_height / 2.0;
-                let max_distance = top_bound - bottom_bound;
-                let separation = max_distance * progress;
-                top_bound - separation
-            };
-        }
+        // Verify that piece can be placed
+        let can_place = matches!(
+            self.board.try_place(&new_piece, spawn_pos),
+            PlaceResult::PlaceOk | PlaceResult::RowFilled
+        );
 
-        let mut altered_color = self.color;
-        if matches!(effective_state, GameState::GameOver | GameState::Frozen) {
-            let avg = (self.color.red + self.color.green + self.color.blue) / 3.0;
-            altered_color = rgba(avg, avg, avg, self.color.alpha);
+        if can_place && DEBUG {
+            spawn_new_piece_msg(&new_piece);
         }
 
-        // Draw the board
-        for y in 0..self.board.height {
-            for x in 0..self.board.width {
-                let pos = BoardPosition { x, y };
-                if self.board.is_cell_filled(pos) {
-                    let screen_pos = pos.to_screen(self);
+        self.active_piece = Some(new_piece);
+        self.grace_left = None;
+        self.grace_used = false;
+        self.last_kick = None;
+        self.soft_drop_cells = 0;
+        self.hard_drop_cells = 0;
+        self.hint = self.show_hint.then(|| self.best_placement_hint()).flatten();
+        self.refresh_overlay();
+        can_place
+    }
 
-                    // Handle GameOver modified cell color
-                    if matches!(effective_state, GameState::GameOver | GameState::Frozen)
-                        && screen_pos.y > game_over_line_pos
-                    {
-                        self.draw_cell(draw, pos, altered_color);
-                    } else {
-                        // Draw the cell normally
-                        self.draw_cell(draw, pos, self.color);
-                    }
-                } else if DEBUG {
-                    self.draw_unfilled_cell(draw, pos)
-                }
+    // Tick the lock delay. A sideways move within the grace window of the
+    // deadline pushes the lock back by the grace, once per piece.
+    fn lock_timer_done(&mut self, dt: f32) -> bool {
+        if let Some(left) = self.grace_left.as_mut() {
+            *left -= dt;
+            if *left > 0.0 {
+                return false;
             }
+            self.grace_left = None;
+            return true;
         }
 
-        // Draw the active piece
-        if let Some(piece) = &self.active_piece {
-            for &(dx, dy) in piece.cells() {
-                let pos = BoardPosition {
-                    x: piece.position.x + dx,
-                    y: piece.position.y + dy,
-                };
+        if !self.timers.lock.tick(dt) {
+            return false;
+        }
+        if self.lock_grace > 0.0 && !self.grace_used && self.since_move <= self.lock_grace {
+            self.grace_used = true;
+            self.grace_left = Some(self.lock_grace);
+            return false;
+        }
+        true
+    }
 
-                if pos.x >= 0 && pos.x < self.board.width && pos.y >= 0 && pos.y < self.board.height
-                {
-                    self.draw_cell(draw, pos, piece.color);
-                }
-            }
+    // Lock pieces on the beat of bpm; 0 turns rhythm locking off
+    pub fn set_beat_lock(&mut self, bpm: u32) {
+        self.beat_interval = (bpm > 0).then(|| 60.0 / bpm as f32);
+    }
+
+    // Advance the beat clock and note whether a beat boundary was crossed
+    fn update_beat(&mut self, dt: f32) {
+        let Some(interval) = self.beat_interval else {
+            return;
+        };
+        if self.game_state == GameState::Paused {
+            self.on_beat = false;
+            return;
         }
 
-        // Draw the clearing animation if effective state is Clearing state
-        if effective_state == GameState::Clearing {
-            self.draw_clear_animation(draw);
+        let prev_beat = (self.beat_clock / interval).floor();
+        self.beat_clock += dt;
+        self.on_beat = (self.beat_clock / interval).floor() > prev_beat;
+    }
+
+    // A piece that is ready to lock may only do so on a beat.
+    // Returns true if it can lock now; otherwise it waits for the next one.
+    fn lock_on_beat(&mut self) -> bool {
+        if self.beat_interval.is_none() || self.on_beat {
+            self.awaiting_beat = false;
+            return true;
+        }
+        self.awaiting_beat = true;
+        false
+    }
+
+    // Score and commit the active piece, then move on to Clearing or Ready
+    fn lock_active_piece(&mut self) {
+        self.pieces_placed += 1;
+        self.score_piece();
+        self.score_drop_distance();
+        self.rows_to_clear = self.commit_piece();
+        self.cols_to_clear = Some(self.board.full_cols()).filter(|cols| !cols.is_empty());
+        self.fire(SoundCue::Lock);
+
+        // Manual clear: filled lines stay on the board until confirmed
+        if self.manual_clear {
+            self.rows_to_clear = None;
+            self.cols_to_clear = None;
         }
 
-        // Draw the game over animation if effective state is GameOver state
-        if effective_state == GameState::GameOver {
-            self.draw_game_over(draw, game_over_line_pos);
+        // Clears counter pending garbage; otherwise garbage that's waited long enough rises
+        let lines = self.rows_to_clear.as_ref().map_or(0, Vec::len)
+            + self.cols_to_clear.as_ref().map_or(0, Vec::len);
+        if lines > 0 {
+            self.cancel_garbage(lines);
+        } else if !self.raise_due_garbage() {
+            self.timers.reset_all();
+            self.game_state = GameState::GameOver;
+            self.fire(SoundCue::GameOver);
+            return;
         }
 
-        // Draw boundary around the board
-        if effective_state == GameState::Frozen {
-            self.draw_boundary(draw, altered_color);
+        if self.start_clearing() {
+            if DEBUG {
+                println!("Was Locked but now Clearing");
+            }
+
+        // Piece is locked and return to Ready state
         } else {
-            self.draw_boundary(draw, self.boundary_color);
+            self.game_state = GameState::Ready;
+
+            if DEBUG {
+                println!("Was Locked but now Ready");
+            }
         }
     }
 
-    // Draw a filled cell
-    fn draw_cell(&self, draw: &Draw, pos: BoardPosition, color: Rgba) {
-        // Draw block
-        draw.rect()
-            .xy(pos.to_screen(self))
-            .w_h(self.cell_size, self.cell_size) // cell size
-            .color(color) // color
-            .stroke_weight(1.5)
-            .stroke(BLACK);
-    }
+    // Enter Clearing if any lines are queued, with their sound cue
+    fn start_clearing(&mut self) -> bool {
+        let lines = self.rows_to_clear.as_ref().map_or(0, Vec::len)
+            + self.cols_to_clear.as_ref().map_or(0, Vec::len);
+        if lines == 0 {
+            return false;
+        }
 
-    // For debug, draw the unfilled cell's outline
-    fn draw_unfilled_cell(&self, draw: &Draw, pos: BoardPosition) {
-        // Draw block
-        draw.rect()
-            .xy(pos.to_screen(self))
-            .w_h(self.cell_size, self.cell_size) // cell size
-            .color(BLACK) // color
-            .stroke_weight(1.5)
-            .stroke(rgba(0.2, 0.2, 0.2, 1.0));
+        let cue = match lines {
+            n if n >= 4 => SoundCue::Tetris,
+            n => SoundCue::Clear(n),
+        };
+        self.fire(cue);
+        self.game_state = GameState::Clearing;
+        self.pre_clear_flashing = self.timers.pre_clear.duration() > 0.0;
+        self.spawn_clear_particles(lines);
+        true
     }
 
-    fn draw_clear_animation(&self, draw: &Draw) {
-        let Some(rows) = &self.rows_to_clear else {
+    // Throw sparks off the cells being cleared, up to the cap
+    fn spawn_clear_particles(&mut self, lines: usize) {
+        let room = self.max_particles.saturating_sub(self.particles.len());
+        let count = (self.clear_particles * lines).min(room);
+        if count == 0 {
             return;
-        };
-
-        let progress = self.timers.clear_animation.progress();
-        let alpha = 0.5 * progress.powf(1.4);
+        }
 
-        // Find row bounds
-        let top_row = *rows.iter().max().unwrap_or(&0);
-        let bottom_row = *rows.iter().min().unwrap_or(&0);
+        let mut cells = Vec::new();
+        for &y in self.rows_to_clear.iter().flatten() {
+            for x in 0..self.board.width {
+                cells.push(BoardPosition { x, y }.to_screen(self));
+            }
+        }
+        for &x in self.cols_to_clear.iter().flatten() {
+            for y in 0..self.board.height {
+                cells.push(BoardPosition { x, y }.to_screen(self));
+            }
+        }
+        let sparks = burst(&cells, count, self.cell_size, self.color);
+        self.particles.extend(sparks);
+    }
+
+    // Manual clear: detonate every filled line at once. A piece in play goes
+    // back to the front of the queue and spawns again after the clear.
+    fn confirm_clear(&mut self) {
+        if !self.manual_clear
+            || !matches!(
+                self.game_state,
+                GameState::Ready | GameState::Falling | GameState::Locking { .. }
+            )
+        {
+            return;
+        }
+
+        self.rows_to_clear = Some(self.board.full_rows()).filter(|rows| !rows.is_empty());
+        self.cols_to_clear = Some(self.board.full_cols()).filter(|cols| !cols.is_empty());
+        if self.rows_to_clear.is_none() && self.cols_to_clear.is_none() {
+            return;
+        }
+
+        if let Some(piece) = self.active_piece.take() {
+            self.next_queue.push_front(piece.typ);
+            self.scripted_len += 1;
+        }
+        self.awaiting_beat = false;
+        self.timers.lock.reset();
+        self.start_clearing();
+    }
+
+    // Gimmick: turn the stack upside down. A piece in play that the flipped stack
+    // lands on is pushed up clear of it; if it can't fit the flip is undone.
+    fn flip_board(&mut self) {
+        if !self.flip_input
+            || !matches!(
+                self.game_state,
+                GameState::Ready | GameState::Falling | GameState::Locking { .. }
+            )
+        {
+            return;
+        }
+
+        let before = self.board.state().clone();
+        self.board.flip_vertical();
+        let Some(piece) = self.active_piece.as_ref() else {
+            return;
+        };
+
+        let free = (piece.position.y..self.board.height)
+            .map(|y| BoardPosition {
+                x: piece.position.x,
+                y,
+            })
+            .find(|&pos| {
+                matches!(
+                    self.board.try_place(piece, pos),
+                    PlaceResult::PlaceOk | PlaceResult::RowFilled
+                )
+            });
+        match free {
+            Some(pos) => {
+                if let Some(piece) = self.active_piece.as_mut() {
+                    piece.position = pos;
+                }
+            }
+            None => {
+                self.board.restore(before);
+            }
+        }
+    }
+
+    // Freeze a piece in place
+    fn commit_piece(&mut self) -> Option<Vec<isize>> {
+        self.active_piece
+            .take()
+            .and_then(|piece| self.board.commit_piece(&piece))
+    }
+
+    fn clear_rows(&mut self, rows: &[isize]) {
+        self.board.clear_rows(rows);
+        if DEBUG {
+            print_col_score(self.board.col_score_all());
+        }
+    }
+
+    /**************** Player input methods that affect GameState ******************/
+
+    // Player-induced drop down to lowest legal position
+    fn hard_drop(&mut self) {
+        //Calculate a valid drop position
+        if let Some((drop_pos, result)) = self.get_drop_position() {
+            if DEBUG {
+                println!("Drop location y is {:?}", drop_pos);
+            }
+
+            let Some(piece) = self.active_piece.as_mut() else {
+                return;
+            };
+            let distance = (piece.position.y - drop_pos.y).max(0) as usize;
+
+            match result {
+                PlaceResult::PlaceOk => {
+                    piece.position = drop_pos;
+                    self.hard_drop_cells += distance;
+                    self.timers.lock.reset();
+                    self.game_state = GameState::Locking {
+                        now: false,
+                        hard_drop: true,
+                    };
+                    if DEBUG {
+                        println!("Hard Drop - PlaceOk at {:?}", drop_pos);
+                    }
+                }
+                PlaceResult::RowFilled => {
+                    piece.position = drop_pos;
+                    self.hard_drop_cells += distance;
+                    self.lock_filled_row(true);
+                    if DEBUG {
+                        println!("Hard Drop - RowFilled");
+                    }
+                }
+                PlaceResult::OutOfBounds | PlaceResult::PlaceBad => {
+                    if DEBUG {
+                        println!("Hard Drop - PlaceBad / OOB");
+                    }
+                }
+            }
+        }
+    }
+
+    // Put the active piece in the hold queue. While the queue has room the
+    // next piece comes in; once full, the oldest held piece comes back.
+    fn hold(&mut self) {
+        if self.hold_capacity == 0 || self.hold_used {
+            return;
+        }
+        let Some(piece) = self.active_piece.take() else {
+            return;
+        };
+
+        self.held.push_back(piece.typ);
+        let piece_type = if self.held.len() > self.hold_capacity {
+            self.held.pop_front().unwrap_or(piece.typ)
+        } else {
+            self.take_next_piece()
+        };
+        self.hold_used = true;
+
+        self.timers.gravity.reset();
+        self.timers.lock.reset();
+        self.gravity_accumulator = 0.0;
+        self.awaiting_beat = false;
+        if self.place_new_piece(piece_type) {
+            self.game_state = GameState::Falling;
+        } else {
+            self.timers.reset_all();
+            self.game_state = GameState::GameOver;
+            self.fire(SoundCue::GameOver);
+        }
+    }
+
+    // Stop gravity without pausing the board; input still works
+    pub fn set_gravity_held(&mut self, held: bool) {
+        self.gravity_held = held;
+    }
+
+    // Sandbox toggle: with gravity off, pieces move only by input
+    pub fn set_gravity_enabled(&mut self, enabled: bool) {
+        self.gravity_enabled = enabled;
+    }
+
+    pub fn gravity_enabled(&self) -> bool {
+        self.gravity_enabled
+    }
+
+    // The active piece rests on ground and locks without waiting for gravity: on
+    // the floor, or with GroundedLock::Contact also on any stack cell right below
+    // one of its cells. Otherwise a failed gravity step starts the lock.
+    fn is_grounded(&self) -> bool {
+        let Some(piece) = self.active_piece.as_ref() else {
+            return false;
+        };
+        if Self::is_piece_at_bottom(piece) {
+            return true;
+        }
+        self.grounded_lock == GroundedLock::Contact && self.is_on_stack(piece)
+    }
+
+    // A filled cell is right below one of the piece's cells
+    fn is_on_stack(&self, piece: &PieceInstance) -> bool {
+        piece.absolute_cells().iter().any(|cell| {
+            self.board.is_cell_filled(BoardPosition {
+                x: cell.x,
+                y: cell.y - 1,
+            })
+        })
+    }
+
+    // Wall kick index of the active piece's last rotation, None if it hasn't rotated.
+    // A non-zero kick on a T piece is the usual T-spin signal.
+    pub fn last_rotation_kick(&self) -> Option<usize> {
+        self.last_kick
+    }
+
+    // Only render_on_change needs dirty worked out; otherwise every update is dirty
+    pub fn set_render_on_change(&mut self, enabled: bool) {
+        self.track_changes = enabled;
+    }
+
+    pub fn set_batch_cells(&mut self, batch_cells: bool) {
+        self.batch_cells = batch_cells;
+    }
+
+    // The cell sprite, and a translucent copy for ghost and preview cells
+    pub fn set_cell_texture(&mut self, texture: wgpu::Texture, faint: wgpu::Texture) {
+        self.cell_texture = Some(texture);
+        self.faint_texture = Some(faint);
+    }
+
+    pub fn set_soft_drop_resets_lock(&mut self, resets: bool) {
+        self.soft_drop_resets_lock = resets;
+    }
+
+    // Where the active piece would land if dropped straight down now.
+    // Kept by update while the clear preview is on, None otherwise.
+    pub fn landing_position(&self) -> Option<BoardPosition> {
+        self.landing
+    }
+
+    // Find the landing position the way a hard drop would
+    fn refresh_landing(&mut self) {
+        self.landing = self
+            .get_drop_position()
+            .filter(|(_, result)| matches!(result, PlaceResult::PlaceOk | PlaceResult::RowFilled))
+            .map(|(pos, _)| pos);
+    }
+
+    // Rows and columns the active piece would complete at its landing position
+    pub fn lines_cleared_on_drop(&self) -> (Vec<isize>, Vec<isize>) {
+        let (Some(piece), Some(landing)) = (self.active_piece.as_ref(), self.landing) else {
+            return (Vec::new(), Vec::new());
+        };
+        self.board.lines_completed_by(&piece.cells_at(landing))
+    }
+
+    // Suggested rotation and column for the active piece
+    pub fn best_placement_hint(&self) -> Option<Placement> {
+        let piece = self.active_piece.as_ref()?;
+        best_placement(
+            self.board.grid(),
+            self.board.width,
+            self.board.height,
+            piece.typ,
+        )
+    }
+
+    pub fn set_placement_overlay(&mut self, enabled: bool) {
+        self.placement_overlay = enabled;
+        self.refresh_overlay();
+    }
+
+    pub fn placement_overlay(&self) -> bool {
+        self.placement_overlay
+    }
+
+    // Work out the overlay for the active piece, when it's on
+    fn refresh_overlay(&mut self) {
+        self.overlay.clear();
+        let Some(piece) = self
+            .active_piece
+            .as_ref()
+            .filter(|_| self.placement_overlay)
+        else {
+            return;
+        };
+        let grid = self.board.grid();
+        self.overlay = self
+            .board
+            .legal_placements(piece)
+            .into_iter()
+            .take(self.placement_overlay_max)
+            .map(|(rot_idx, pos)| {
+                let placement = Placement {
+                    rot_idx,
+                    x: pos.x,
+                    y: pos.y,
+                };
+                let hole = creates_hole(grid, self.board.width, &placement.cells(piece.typ));
+                (placement, hole)
+            })
+            .collect();
+    }
+
+    pub fn held_pieces(&self) -> impl Iterator<Item = &PieceType> {
+        self.held.iter()
+    }
+
+    // Player-induced move down one row
+    fn soft_drop(&mut self) {
+        let Some(piece) = self.active_piece.as_ref() else {
+            return;
+        };
+        if Self::is_piece_at_bottom(piece) {
+            self.soft_drop_grounded();
+            return;
+        }
+        let new_pos = BoardPosition {
+            x: piece.position.x,
+            y: piece.position.y - 1,
+        };
+
+        let Some(result) = self.try_piece_movement(new_pos) else {
+            return;
+        };
+        let Some(piece) = self.active_piece.as_mut() else {
+            return;
+        };
+
+        match result {
+            PlaceResult::PlaceOk => {
+                piece.position = new_pos;
+                self.soft_drop_cells += 1;
+                self.timers.gravity.reset();
+                self.timers.lock.reset();
+                self.game_state = GameState::Falling;
+            }
+            PlaceResult::RowFilled => {
+                piece.position = new_pos;
+                self.soft_drop_cells += 1;
+                self.lock_filled_row(false);
+            }
+            PlaceResult::OutOfBounds | PlaceResult::PlaceBad => self.soft_drop_grounded(),
+        }
+    }
+
+    // Soft drop on a landed piece: optionally restart the lock delay
+    fn soft_drop_grounded(&mut self) {
+        if self.soft_drop_resets_lock
+            && matches!(self.game_state, GameState::Locking { now: false, .. })
+        {
+            self.timers.lock.reset();
+        }
+    }
+
+    // Generalized function to handle moving a piece to any position
+    fn move_active_piece(&mut self, new_pos: BoardPosition) {
+        let new_pos = self.board.wrap(new_pos);
+        let Some(result) = self.try_piece_movement(new_pos) else {
+            return;
+        };
+
+        let Some(piece) = self.active_piece.as_mut() else {
+            return;
+        };
+
+        match result {
+            PlaceResult::PlaceOk => {
+                piece.position = new_pos;
+                self.since_move = 0.0;
+                self.fire(SoundCue::Move);
+            }
+            PlaceResult::RowFilled => {
+                piece.position = new_pos;
+                self.fire(SoundCue::Move);
+                self.lock_filled_row(false);
+            }
+            PlaceResult::OutOfBounds | PlaceResult::PlaceBad => {}
+        }
+    }
+
+    // Gravity, moves, rotations and hard drop all commit a row-filling piece at once,
+    // unless filled rows are deferred. Returns true if the piece plays on.
+    fn lock_filled_row(&mut self, hard_drop: bool) -> bool {
+        // A filled row is no reason to lock: the piece keeps its lock delay
+        if self.defers_filled_rows() {
+            if hard_drop {
+                self.game_state = GameState::Locking {
+                    now: false,
+                    hard_drop,
+                };
+            }
+            return true;
+        }
+
+        self.game_state = GameState::Locking {
+            now: true,
+            hard_drop,
+        };
+        false
+    }
+
+    fn defers_filled_rows(&self) -> bool {
+        self.manual_clear || self.row_fill_lock == RowFillLock::Delay
+    }
+
+    fn rotate_active_piece(&mut self) {
+        let Some(piece) = &mut self.active_piece else {
+            return;
+        };
+        // Only clockwise rotations supported
+        let rotation_direction = RotationDirection::Cw;
+
+        // Try to find a valid position with wall kicks
+        let Some((new_pos, kick_idx)) = self.board.try_rotation(piece, &rotation_direction) else {
+            return;
+        };
+        // Apply rotation and position
+        piece.rotate(&rotation_direction);
+        piece.position = self.board.wrap(new_pos);
+        let position = piece.position;
+        self.last_kick = Some(kick_idx);
+        self.fire(SoundCue::Rotate);
+
+        // A rotation that completes a row locks just as a move would
+        if matches!(
+            self.try_piece_movement(position),
+            Some(PlaceResult::RowFilled)
+        ) {
+            self.lock_filled_row(false);
+        }
+    }
+
+    /**************** Piece movement helper methods ******************/
+
+    // Test movement validity
+    fn try_piece_movement(&mut self, new_pos: BoardPosition) -> Option<PlaceResult> {
+        self.active_piece
+            .as_ref()
+            .map(|piece| self.board.try_place(piece, new_pos))
+    }
+
+    // Obtain the valid hard drop position of the currently active piece
+    fn get_drop_position(&mut self) -> Option<(BoardPosition, PlaceResult)> {
+        self.active_piece
+            .as_ref()
+            .map(|piece| self.board.calculate_drop(piece))
+    }
+
+    // Checks that a piece is on the floor of the grid
+    fn is_piece_at_bottom(piece: &PieceInstance) -> bool {
+        // Check if any cell is at y=0
+        piece.absolute_cells().iter().any(|cell| cell.y == 0)
+    }
+
+    /************************ Piece creation methods ************************/
+
+    // Top up the next queue with random pieces
+    fn fill_next_queue(&mut self) {
+        while self.next_queue.len() < self.next_queue_len {
+            let piece_type = self.get_random_piece_type();
+            self.next_queue.push_back(piece_type);
+        }
+    }
+
+    // Where a new piece of this type enters the board
+    fn spawn_position(&self, piece_type: PieceType) -> BoardPosition {
+        let rot_idx = self.spawn_rotation(piece_type);
+        let x = match self.spawn_rule {
+            SpawnRule::Srs => self.board.midpoint_x() - piece_type.max_x(0) / 2,
+            // NES columns 4-6 on a 10-wide board, 3-6 for the I and 4-5 for the O
+            SpawnRule::Nes if piece_type == PieceType::I => self.board.midpoint_x() - 2,
+            SpawnRule::Nes => self.board.midpoint_x() - 1,
+        };
+        BoardPosition {
+            x,
+            y: self.board.height - piece_type.max_y(rot_idx) - 1,
+        }
+    }
+
+    fn spawn_rotation(&self, piece_type: PieceType) -> usize {
+        match (self.spawn_rule, piece_type) {
+            (SpawnRule::Nes, PieceType::T | PieceType::J | PieceType::L) => 2,
+            _ => 0,
+        }
+    }
+
+    // A new piece of this type at its spawn position and orientation
+    fn spawn_piece(&self, piece_type: PieceType) -> PieceInstance {
+        let mut piece = PieceInstance::new(
+            piece_type,
+            self.get_piece_color(),
+            self.spawn_position(piece_type),
+        );
+        piece.rot_idx = self.spawn_rotation(piece_type);
+        piece
+    }
+
+    // Queue a piece to spawn after any already scripted ones.
+    // Random pieces waiting in the queue are discarded.
+    pub fn push_next(&mut self, piece: PieceType) {
+        self.next_queue.truncate(self.scripted_len);
+        self.next_queue.push_back(piece);
+        self.scripted_len += 1;
+    }
+
+    // Spawn exactly these pieces next, then go back to random
+    pub fn force_sequence(&mut self, seq: &[PieceType]) {
+        self.next_queue.clear();
+        self.scripted_len = 0;
+        for &piece in seq {
+            self.push_next(piece);
+        }
+    }
+
+    pub fn next_piece(&self) -> Option<PieceType> {
+        self.next_queue.front().copied()
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // Restart the piece sequence from `seed`. Scripted pieces stay queued.
+    fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+        self.garbage_rng = StdRng::seed_from_u64(seed ^ GARBAGE_SEED_SALT);
+        self.randomizer = new_randomizer(self.randomizer_kind, self.piece_weights);
+        self.next_queue.truncate(self.scripted_len);
+    }
+
+    // Draw pieces from a sequence other boards share, from its start.
+    // Scripted pieces stay queued.
+    pub fn share_sequence(&mut self, sequence: Rc<RefCell<SharedSequence>>) {
+        self.shared = Some(sequence);
+        self.shared_pos = 0;
+        self.next_queue.truncate(self.scripted_len);
+    }
+
+    // Obtain a random PieceType
+    fn get_random_piece_type(&mut self) -> PieceType {
+        if let Some(piece) = self.drill_piece {
+            return piece;
+        }
+        if let Some(shared) = &self.shared {
+            let piece = shared.borrow_mut().piece(self.shared_pos);
+            self.shared_pos += 1;
+            return piece;
+        }
+        self.randomizer.next_piece(&mut self.rng)
+    }
+
+    // Get the piece's color; currently all pieces are the same color so just returns
+    // the board's filled cell color.
+    fn get_piece_color(&self) -> Rgba {
+        self.color
+    }
+
+    /************************ Scoring methods **************************************/
+    fn score_piece(&mut self) {
+        if let Some(piece) = &self.active_piece {
+            self.board.score_piece(piece);
+        }
+    }
+
+    fn score_drop_distance(&mut self) {
+        self.board
+            .score_drop(self.soft_drop_cells, self.soft_drop_points);
+        self.board
+            .score_drop(self.hard_drop_cells, self.hard_drop_points);
+        self.soft_drop_cells = 0;
+        self.hard_drop_cells = 0;
+    }
+
+    fn score_row_clear(&mut self, rows: &[isize], cols: usize) {
+        self.board.score_cleared_rows(rows, cols, &self.row_weights);
+    }
+
+    pub fn score(&self) -> usize {
+        self.board.score()
+    }
+
+    pub fn level(&self) -> usize {
+        match self.level_lines {
+            0 => 0,
+            n => self.lines_cleared / n,
+        }
+    }
+
+    // Tally cleared lines and fire LevelUp when they pass the next level
+    fn count_lines(&mut self, lines: usize) {
+        self.set_chain_meter(self.chain_meter + lines as f32 * self.chain_per_line);
+
+        let before = self.level();
+        self.lines_cleared += lines;
+        if self.level() > before {
+            self.fire(SoundCue::LevelUp(self.level()));
+            self.start_level_fade();
+            self.apply_lock_delay_curve();
+        }
+    }
+
+    pub fn chain_meter(&self) -> f32 {
+        self.chain_meter
+    }
+
+    // Move the chain meter, capped at chain_max, firing ChainMeterChanged
+    // when it crosses a threshold either way
+    fn set_chain_meter(&mut self, meter: f32) {
+        if self.chain_max <= 0.0 {
+            return;
+        }
+        let reached = |meter: f32| {
+            self.chain_thresholds
+                .iter()
+                .filter(|&&t| meter >= t)
+                .count()
+        };
+        let before = reached(self.chain_meter);
+        self.chain_meter = meter.clamp(0.0, self.chain_max);
+        let after = reached(self.chain_meter);
+        if after != before {
+            self.fire(SoundCue::ChainMeterChanged(after));
+        }
+    }
+
+    // Shorten the lock delay to suit the current level
+    fn apply_lock_delay_curve(&mut self) {
+        let delay = self.lock_delay_curve.delay(self.lock_delay, self.level());
+        self.timers.lock.set_duration(delay);
+    }
+
+    pub fn lock_delay(&self) -> f32 {
+        self.timers.lock.duration()
+    }
+
+    pub fn playtime(&self) -> f32 {
+        self.playtime
+    }
+
+    /************************ Telemetry methods *****************************/
+
+    pub fn game_state(&self) -> GameState {
+        self.game_state
+    }
+
+    pub fn active_piece(&self) -> Option<&PieceInstance> {
+        self.active_piece.as_ref()
+    }
+
+    // Retune gravity live. The current fall keeps its progress toward the next row.
+    pub fn set_gravity_interval(&mut self, secs: f32) {
+        self.timers
+            .gravity
+            .set_duration(secs.max(MIN_GRAVITY_INTERVAL));
+    }
+
+    pub fn gravity_interval(&self) -> f32 {
+        self.timers.gravity.duration()
+    }
+
+    // Handicap a board by scaling its fall speed, whatever sets the interval
+    pub fn set_gravity_multiplier(&mut self, multiplier: f32) {
+        self.gravity_multiplier = multiplier.max(MIN_GRAVITY_MULTIPLIER);
+    }
+
+    pub fn gravity_multiplier(&self) -> f32 {
+        self.gravity_multiplier
+    }
+
+    pub fn gravity_progress(&self) -> f32 {
+        self.timers.gravity.progress()
+    }
+
+    pub fn lock_progress(&self) -> f32 {
+        self.timers.lock.progress()
+    }
+
+    // Register a callback run on each cue as it fires, alongside the drained queue.
+    // Muted boards don't call it.
+    pub fn on_event(&mut self, handler: EventHandler) {
+        self.event_handlers.push(handler);
+    }
+
+    fn fire(&mut self, cue: SoundCue) {
+        if self.emits() {
+            for handler in self.event_handlers.iter_mut() {
+                handler(&cue);
+            }
+        }
+        self.sound_cues.push(cue);
+    }
+
+    // Take the sound cues fired since the last call. Muted boards drop them.
+    pub fn drain_sound_cues(&mut self) -> Vec<SoundCue> {
+        let cues = std::mem::take(&mut self.sound_cues);
+        if self.emits() {
+            cues
+        } else {
+            Vec::new()
+        }
+    }
+
+    // Mute or unmute this board's outgoing events. Gameplay is unaffected.
+    pub fn set_emit(&mut self, enabled: bool) {
+        self.emit = enabled;
+    }
+
+    // Solo another board: this one goes quiet without losing its mute setting
+    pub fn set_soloed_out(&mut self, soloed_out: bool) {
+        self.soloed_out = soloed_out;
+    }
+
+    pub fn emits(&self) -> bool {
+        self.emit && !self.soloed_out
+    }
+
+    pub fn telemetry(&self) -> String {
+        format_telemetry(
+            &self.game_state,
+            self.active_piece(),
+            self.gravity_progress(),
+            self.lock_progress(),
+        )
+    }
+
+    /************************ Input handling methods *******************************/
+
+    // Apply a frame's inputs in the order received, routing each by the current state
+    fn handle_inputs(&mut self, inputs: &[PlayerInput]) {
+        let mut dropped = false;
+        for &input in inputs {
+            // Inputs arrive in screen directions
+            let input = self.orientation.remap_input(input);
+
+            // A hard drop ends the piece; later moves this frame aren't for it
+            let moves_piece = matches!(
+                input,
+                PlayerInput::L
+                    | PlayerInput::R
+                    | PlayerInput::Rotate
+                    | PlayerInput::SoftDrop
+                    | PlayerInput::HardDrop
+                    | PlayerInput::Hold
+            );
+            if dropped && moves_piece {
+                continue;
+            }
+
+            // When paused, ignore piece movement inputs
+            if self.game_state == GameState::Paused {
+                self.handle_pause_input(&input);
+            } else {
+                self.handle_input(&input);
+            }
+            dropped |= input == PlayerInput::HardDrop && self.game_state != GameState::Falling;
+        }
+    }
+
+    fn handle_input(&mut self, input: &PlayerInput) {
+        match input {
+            PlayerInput::L => {
+                if let Some(piece) = self.active_piece.as_mut() {
+                    let new_pos = BoardPosition {
+                        x: piece.position.x - 1,
+                        y: piece.position.y,
+                    };
+
+                    self.move_active_piece(new_pos);
+                }
+            }
+            PlayerInput::R => {
+                if let Some(piece) = self.active_piece.as_mut() {
+                    let new_pos = BoardPosition {
+                        x: piece.position.x + 1,
+                        y: piece.position.y,
+                    };
+
+                    self.move_active_piece(new_pos);
+                }
+            }
+            PlayerInput::Rotate => {
+                self.rotate_active_piece();
+            }
+            PlayerInput::SoftDrop => {
+                self.soft_drop();
+                self.since_soft_drop = 0.0;
+            }
+            // Too soon after a soft drop, likely a slip of the finger
+            PlayerInput::HardDrop if self.since_soft_drop < self.hard_drop_lockout => {}
+            PlayerInput::HardDrop => {
+                self.hard_drop();
+            }
+            PlayerInput::Hold => {
+                self.hold();
+            }
+            PlayerInput::ConfirmClear => {
+                self.confirm_clear();
+            }
+            PlayerInput::Flip => {
+                self.flip_board();
+            }
+            PlayerInput::Pause => {
+                self.handle_pause();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_pause_input(&mut self, input: &PlayerInput) {
+        // ignore everything except Pause
+        match input {
+            PlayerInput::Pause => {
+                self.handle_pause();
+            }
+            PlayerInput::SaveSlot(slot) => self.save_slot(*slot),
+            PlayerInput::LoadSlot(slot) => self.load_slot(*slot),
+            _ => {}
+        }
+    }
+
+    // Bookmark the paused position, active piece included. Mid-clear or
+    // after game over there's no position worth returning to.
+    fn save_slot(&mut self, slot: u8) {
+        if !matches!(
+            self.prev_game_state,
+            Some(GameState::Ready | GameState::Falling | GameState::Locking { .. })
+        ) {
+            return;
+        }
+        self.board.save_state(slot);
+        match &self.active_piece {
+            Some(piece) => self.saved_pieces.insert(slot, piece.clone()),
+            None => self.saved_pieces.remove(&slot),
+        };
+    }
+
+    // Restore a bookmark, still paused; unpausing plays on from there
+    fn load_slot(&mut self, slot: u8) {
+        if !self.board.resume_state(slot) {
+            return;
+        }
+        self.active_piece = self.saved_pieces.get(&slot).cloned();
+        self.prev_game_state = Some(if self.active_piece.is_some() {
+            GameState::Falling
+        } else {
+            GameState::Ready
+        });
+        self.rows_to_clear = None;
+        self.cols_to_clear = None;
+        self.timers.gravity.reset();
+        self.timers.lock.reset();
+    }
+
+    // End the game as the winner of a score race
+    pub fn win(&mut self) {
+        self.end_game(GameState::GameWon);
+    }
+
+    // End the game because another board won, through the usual game over
+    pub fn lose(&mut self) {
+        if matches!(
+            self.effective_state(),
+            GameState::GameOver | GameState::Finale | GameState::Frozen | GameState::GameWon
+        ) {
+            return;
+        }
+        self.end_game(GameState::GameOver);
+        self.fire(SoundCue::GameOver);
+    }
+
+    fn end_game(&mut self, state: GameState) {
+        self.prev_game_state = None;
+        self.timers.reset_all();
+        self.timers.resume_all();
+        self.timers.idle.reset();
+        self.game_state = state;
+    }
+
+    // The state underneath a pause
+    fn effective_state(&self) -> GameState {
+        if self.game_state == GameState::Paused {
+            self.prev_game_state.unwrap_or(self.game_state)
+        } else {
+            self.game_state
+        }
+    }
+
+    // Pause or resume from outside the input stream. Returns true if the state changed.
+    pub fn set_paused(&mut self, paused: bool) -> bool {
+        if paused == (self.game_state == GameState::Paused) {
+            return false;
+        }
+        self.handle_pause();
+        true
+    }
+
+    fn handle_pause(&mut self) {
+        if self.game_state == GameState::Paused {
+            // Exiting pause state
+            self.game_state = self.prev_game_state.take().unwrap_or(GameState::Ready);
+            self.timers.resume_all();
+            // Restore timers if pause state exists
+        } else {
+            // Entering pause state
+            self.prev_game_state = Some(self.game_state);
+            self.game_state = GameState::Paused;
+            self.timers.pause_all();
+        }
+    }
+
+    /************************ Color methods *******************************/
+
+    // The board's current colors
+    pub fn palette(&self) -> Palette {
+        Palette {
+            cell: self.color,
+            boundary: self.base_boundary_color,
+            panel: self.panel_color,
+        }
+    }
+
+    // Change the board's colors, fading over duration seconds (0.0 for at once)
+    pub fn set_palette(&mut self, palette: Palette, duration: f32) {
+        if duration <= 0.0 {
+            self.palette_fade = None;
+            self.apply_palette(palette);
+        } else {
+            self.palette_fade = Some((self.palette(), palette, Timer::new(duration)));
+        }
+    }
+
+    fn apply_palette(&mut self, palette: Palette) {
+        self.color = palette.cell;
+        self.base_boundary_color = palette.boundary;
+        self.panel_color = palette.panel;
+        if let Some(piece) = self.active_piece.as_mut() {
+            piece.color = palette.cell;
+        }
+    }
+
+    fn update_palette(&mut self, dt: f32) {
+        let Some((from, to, timer)) = self.palette_fade.as_mut() else {
+            return;
+        };
+        let (from, to) = (*from, *to);
+        if timer.tick(dt) {
+            self.palette_fade = None;
+            self.apply_palette(to);
+        } else {
+            let palette = from.blend(&to, timer.progress());
+            self.apply_palette(palette);
+        }
+    }
+
+    // The boundary color for a level: the base hue rotated a step per level
+    fn level_color(&self, level: usize) -> Rgba {
+        let hue = (BASE_HUE + level as f32 * self.level_hue_step).rem_euclid(360.0);
+        hsva(hue / 360.0, 1.0, 0.75, 1.0).into()
+    }
+
+    // Fade the base boundary color to the current level's hue
+    fn start_level_fade(&mut self) {
+        let from = self.base_boundary_color;
+        let to = self.level_color(self.level());
+        self.level_fade.start(
+            rgb(from.red, from.green, from.blue),
+            rgb(to.red, to.green, to.blue),
+            self.level_fade_duration,
+            self.playtime,
+        );
+    }
+
+    // The color the boundary is heading toward
+    fn boundary_target(&self) -> Rgba {
+        match self.next_piece() {
+            Some(piece_type) if self.color_from_next => piece_type.color(),
+            _ => self.base_boundary_color,
+        }
+    }
+
+    // Ease the boundary toward its target so the change isn't jarring
+    fn update_boundary_color(&mut self, dt: f32) {
+        if let Some(c) = self.level_fade.update(self.playtime) {
+            self.base_boundary_color = rgba(c.red, c.green, c.blue, 1.0);
+        }
+        let t = (BOUNDARY_BLEND_RATE * dt).min(1.0);
+        self.boundary_color = blend_rgba(self.boundary_color, self.boundary_target(), t);
+    }
+
+    /************************ Drawing methods *******************************/
+
+    // Draw orchestrator
+    pub fn draw(&self, draw: &Draw) {
+        let draw = self.orientation.transform(draw, self.location);
+        self.draw_board(&draw);
+    }
+
+    // Screen position of a cell once the board's orientation is applied
+    pub fn to_view(&self, pos: BoardPosition) -> Vec2 {
+        self.orientation
+            .rotate_point(pos.to_screen(self), self.location)
+    }
+
+    // Draws in the board's upright frame; draw() applies the orientation
+    fn draw_board(&self, draw: &Draw) {
+        // Allow for pausing during clearing animation
+        let effective_state = self.effective_state();
+
+        // GameOver animation handling
+        let mut game_over_line_pos = f32::MIN;
+        if effective_state == GameState::GameOver {
+            game_over_line_pos = {
+                let progress = self.timers.game_over_animation.progress();
+                let top_bound = self.screen_height / 2.0 + self.location.y;
+                let bottom_bound = self.location.y - self.screen_height / 2.0;
+                let max_distance = top_bound - bottom_bound;
+                let separation = max_distance * progress;
+                top_bound - separation
+            };
+        }
+
+        let game_over = matches!(
+            effective_state,
+            GameState::GameOver | GameState::Finale | GameState::Frozen
+        );
+        let mut altered_color = self.color;
+        if game_over {
+            altered_color = desaturate(self.color);
+        }
+
+        // Draw the panel behind the playfield
+        if let Some(panel_color) = self.panel_color {
+            let color = if game_over {
+                desaturate(panel_color)
+            } else {
+                panel_color
+            };
+            self.draw_panel(draw, color);
+        }
+
+        // Draw the board
+        if self.batch_cells {
+            self.cell_mesh(game_over, game_over_line_pos, altered_color)
+                .draw(draw);
+        } else {
+            self.draw_shadows(draw, self.filled_positions());
+        }
+        for y in 0..self.board.height {
+            for x in 0..self.board.width {
+                let pos = BoardPosition { x, y };
+                if self.board.is_cell_filled(pos) {
+                    if self.batch_cells {
+                        continue;
+                    }
+                    let screen_pos = pos.to_screen(self);
+
+                    // Handle GameOver modified cell color
+                    if self.is_flashing_cell(pos) {
+                        self.draw_cell(draw, pos, self.pre_clear_color());
+                    } else if game_over && screen_pos.y > game_over_line_pos {
+                        self.draw_cell(draw, pos, altered_color);
+                    } else {
+                        // Draw the cell normally
+                        self.draw_cell(draw, pos, self.glow_color(self.color, None, pos));
+                    }
+                } else if DEBUG {
+                    self.draw_unfilled_cell(draw, pos)
+                }
+            }
+        }
+
+        // Draw the ghost run's piece under the live one
+        if let Some(ghost) = self.ghost_frame() {
+            self.draw_ghost(draw, &ghost);
+        }
+
+        // Every place the piece could land, under the hint and the piece
+        if matches!(
+            effective_state,
+            GameState::Falling | GameState::Locking { .. }
+        ) {
+            self.draw_overlay(draw);
+        }
+
+        // Highlight the suggested placement under the active piece
+        if effective_state == GameState::Falling {
+            self.draw_hint(draw);
+        }
+
+        // Highlight filled rows waiting for a manual clear
+        if self.manual_clear && effective_state != GameState::Clearing {
+            self.draw_full_rows(draw);
+        }
+
+        // Highlight the rows a drop would clear
+        if self.clear_preview
+            && matches!(
+                effective_state,
+                GameState::Falling | GameState::Locking { .. }
+            )
+        {
+            self.draw_clear_preview(draw);
+        }
+
+        // Draw the active piece, between its last two steps when interpolating
+        if let Some(piece) = &self.active_piece {
+            let offset = self.piece_draw_offset();
+            let cells = piece.absolute_cells().map(|pos| self.board.wrap(pos));
+            self.draw_shadows(draw, cells.into_iter());
+            for pos in cells {
+                if pos.x >= 0 && pos.x < self.board.width && pos.y >= 0 && pos.y < self.board.height
+                {
+                    self.draw_cell_at(
+                        draw,
+                        pos.to_screen(self) + offset,
+                        self.glow_color(piece.color, Some(piece.typ), pos),
+                    );
+                }
+            }
+            if self.show_pivot {
+                self.draw_pivot(draw, piece, offset);
+            }
+        }
+
+        // Telegraph the incoming piece during the entry delay
+        if effective_state == GameState::Ready {
+            self.draw_spawn_telegraph(draw);
+        }
+
+        // Draw the clearing animation if effective state is Clearing state
+        if effective_state == GameState::Clearing && !self.pre_clear_flashing {
+            self.draw_clear_animation(draw);
+            self.draw_col_clear_animation(draw);
+        }
+
+        for particle in &self.particles {
+            draw.rect()
+                .xy(particle.position)
+                .w_h(self.cell_size * 0.2, self.cell_size * 0.2)
+                .color(particle.color());
+        }
+
+        // Draw the game over animation if effective state is GameOver state
+        if effective_state == GameState::GameOver {
+            self.draw_game_over(draw, game_over_line_pos);
+        }
+
+        // Draw boundary around the board
+        if effective_state == GameState::Frozen {
+            self.draw_boundary(draw, altered_color);
+        } else if effective_state == GameState::GameWon {
+            // gold for the winner
+            self.draw_boundary(draw, rgba(1.0, 0.84, 0.0, 1.0));
+        } else {
+            self.draw_boundary(draw, self.boundary_color);
+        }
+
+        self.draw_garbage_meter(draw);
+        if self.fill_meter {
+            self.draw_fill_meter(draw);
+        }
+
+        if let Some(layout) = &self.preview {
+            self.draw_preview(draw, layout);
+        }
+        if self.hold_capacity > 0 {
+            self.draw_hold(draw);
+        }
+    }
+
+    // The board's filled cells as one mesh. Cells are inset by the rect
+    // path's stroke width so the grid lines still show between them.
+    fn cell_mesh(&self, game_over: bool, game_over_line_pos: f32, altered_color: Rgba) -> CellMesh {
+        let mut mesh = CellMesh::with_capacity(self.board.grid().len());
+        if let Some((offset, color)) = self.shadow {
+            for pos in self.filled_positions() {
+                let center = pos.to_screen(self) + offset;
+                mesh.push_cell(center, self.cell_size * SHADOW_SCALE, color);
+            }
+        }
+        for y in 0..self.board.height {
+            for x in 0..self.board.width {
+                let pos = BoardPosition { x, y };
+                if !self.board.is_cell_filled(pos) {
+                    continue;
+                }
+                let screen_pos = pos.to_screen(self);
+                let color = if self.is_flashing_cell(pos) {
+                    self.pre_clear_color()
+                } else if game_over && screen_pos.y > game_over_line_pos {
+                    altered_color
+                } else {
+                    self.glow_color(self.color, None, pos)
+                };
+                mesh.push_cell(screen_pos, self.cell_size - 1.5, color);
+            }
+        }
+        mesh
+    }
+
+    // Screen center of the lines being cleared: midway between the outer rows,
+    // and the outer columns for column clears
+    pub fn clear_center(&self) -> Option<Vec2> {
+        let rows = self.rows_to_clear.as_deref().unwrap_or_default();
+        let cols = self.cols_to_clear.as_deref().unwrap_or_default();
+        if rows.is_empty() && cols.is_empty() {
+            return None;
+        }
+
+        let mid = |lines: &[isize]| {
+            let low = *lines.iter().min().unwrap_or(&0) as f32;
+            let high = *lines.iter().max().unwrap_or(&0) as f32;
+            (low + high) * 0.5
+        };
+        let mut center = self.location;
+        if !rows.is_empty() {
+            center.y += (mid(rows) - (self.board.height as f32 - 1.0) * 0.5) * self.cell_size;
+        }
+        if !cols.is_empty() {
+            center.x += (mid(cols) - (self.board.width as f32 - 1.0) * 0.5) * self.cell_size;
+        }
+        Some(center)
+    }
+
+    // Where and how big the background flash for the current clear is, when enabled
+    pub fn background_flash(&self) -> Option<(Vec2, f32)> {
+        if !self.clear_flash_background {
+            return None;
+        }
+        let lines = self.rows_to_clear.as_ref().map_or(0, Vec::len)
+            + self.cols_to_clear.as_ref().map_or(0, Vec::len);
+        let radius = lines as f32 * CLEAR_FLASH_CELLS * self.cell_size;
+        self.clear_center().map(|center| (center, radius))
+    }
+
+    // A cell of a line about to clear, during the pre-clear flash
+    fn is_flashing_cell(&self, pos: BoardPosition) -> bool {
+        self.game_state == GameState::Clearing
+            && self.pre_clear_flashing
+            && (self
+                .rows_to_clear
+                .as_ref()
+                .is_some_and(|rows| rows.contains(&pos.y))
+                || self
+                    .cols_to_clear
+                    .as_ref()
+                    .is_some_and(|cols| cols.contains(&pos.x)))
+    }
+
+    // White at the start of the flash, easing toward a bright tint of the cell color
+    fn pre_clear_color(&self) -> Rgba {
+        let t = 0.5 * self.timers.pre_clear.progress();
+        blend_rgba(rgba(1.0, 1.0, 1.0, 1.0), self.color, t)
+    }
+
+    fn filled_positions(&self) -> impl Iterator<Item = BoardPosition> + '_ {
+        (0..self.board.height)
+            .flat_map(move |y| (0..self.board.width).map(move |x| BoardPosition { x, y }))
+            .filter(|&pos| self.board.is_cell_filled(pos))
+    }
+
+    // Shadows go down in their own pass so no shadow lands on a neighboring cell
+    fn draw_shadows(&self, draw: &Draw, cells: impl Iterator<Item = BoardPosition>) {
+        let Some((offset, color)) = self.shadow else {
+            return;
+        };
+        let size = self.cell_size * SHADOW_SCALE;
+        for pos in cells {
+            draw.rect()
+                .xy(self.board.wrap(pos).to_screen(self) + offset)
+                .w_h(size, size)
+                .color(color);
+        }
+    }
+
+    // A small dot where the piece turns, ringed so it shows on any cell color
+    fn draw_pivot(&self, draw: &Draw, piece: &PieceInstance, offset: Vec2) {
+        let (x, y) = piece.typ.pivot(piece.rot_idx);
+        let xy = piece.position.to_screen(self) + vec2(x, y) * self.cell_size + offset;
+        draw.ellipse()
+            .xy(xy)
+            .radius(self.cell_size * 0.15)
+            .color(WHITE)
+            .stroke_weight(1.5)
+            .stroke(BLACK);
+    }
+
+    // Draw a filled cell
+    fn draw_cell(&self, draw: &Draw, pos: BoardPosition, color: Rgba) {
+        self.draw_cell_at(draw, self.board.wrap(pos).to_screen(self), color);
+    }
+
+    // A filled cell centered anywhere on screen, not just on the grid
+    fn draw_cell_at(&self, draw: &Draw, xy: Vec2, color: Rgba) {
+        // Faint cells take the translucent sprite so they stay see-through
+        let texture = if color.alpha >= 1.0 {
+            &self.cell_texture
+        } else {
+            &self.faint_texture
+        };
+        if let Some(texture) = texture {
+            draw_sprite(draw, texture, xy, self.cell_size, color);
+            return;
+        }
+
+        // Draw block
+        draw.rect()
+            .xy(xy)
+            .w_h(self.cell_size, self.cell_size) // cell size
+            .color(color) // color
+            .stroke_weight(1.5)
+            .stroke(BLACK);
+    }
+
+    // A cell's color, lifted past the bloom threshold if it's set to glow
+    fn glow_color(&self, color: Rgba, piece: Option<PieceType>, pos: BoardPosition) -> Rgba {
+        if self.glow.glows(piece, pos.y) {
+            emissive(color, self.glow.bloom_threshold + self.glow.margin)
+        } else {
+            color
+        }
+    }
+
+    // For debug, draw the unfilled cell's outline
+    fn draw_unfilled_cell(&self, draw: &Draw, pos: BoardPosition) {
+        // Draw block
+        draw.rect()
+            .xy(pos.to_screen(self))
+            .w_h(self.cell_size, self.cell_size) // cell size
+            .color(BLACK) // color
+            .stroke_weight(1.5)
+            .stroke(rgba(0.2, 0.2, 0.2, 1.0));
+    }
+
+    // Upcoming pieces with the centers of their preview boxes, next piece first
+    pub fn preview_slots(&self) -> Vec<(PieceType, Vec2)> {
+        let Some(layout) = &self.preview else {
+            return Vec::new();
+        };
+        let first = layout.box_center(self.location, self.screen_width, self.screen_height);
+        self.next_queue
+            .iter()
+            .take(layout.count)
+            .enumerate()
+            .map(|(i, &piece_type)| (piece_type, layout.list_center(first, i)))
+            .collect()
+    }
+
+    // Draw the upcoming pieces in their preview boxes
+    fn draw_preview(&self, draw: &Draw, layout: &PreviewLayout) {
+        let first = layout.box_center(self.location, self.screen_width, self.screen_height);
+        for i in 0..layout.count {
+            let center = layout.list_center(first, i);
+            let piece_type = self.next_queue.get(i).copied();
+            self.draw_piece_box(draw, layout, center, piece_type, false);
+        }
+    }
+
+    // Pending garbage as a bar up the left edge, a cell per row. Rows still
+    // waiting out the delay are dim; rows that rise on the next lock are solid.
+    fn draw_garbage_meter(&self, draw: &Draw) {
+        let x = self.location.x - self.screen_width / 2.0 - self.cell_size * 0.3;
+        let mut y = self.location.y - self.screen_height / 2.0;
+        for &(rows, wait) in &self.pending_garbage {
+            let height =
+                (rows as f32 * self.cell_size).min(self.location.y + self.screen_height / 2.0 - y);
+            if height <= 0.0 {
+                break;
+            }
+            let alpha = if wait > 0.0 { 0.4 } else { 1.0 };
+            draw.rect()
+                .x_y(x, y + height / 2.0)
+                .w_h(self.cell_size * 0.3, height - 1.0)
+                .color(rgba(1.0, 0.25, 0.2, alpha));
+            y += height;
+        }
+    }
+
+    // Share of the board filled, as a bar up the right edge shading from green
+    // to red as it fills
+    fn draw_fill_meter(&self, draw: &Draw) {
+        let ratio = self.board.fill_ratio();
+        if ratio <= 0.0 {
+            return;
+        }
+        let x = self.location.x + self.screen_width / 2.0 + self.cell_size * 0.3;
+        let bottom = self.location.y - self.screen_height / 2.0;
+        let height = ratio * self.screen_height;
+        draw.rect()
+            .x_y(x, bottom + height / 2.0)
+            .w_h(self.cell_size * 0.3, height)
+            .color(hsva((1.0 - ratio) / 3.0, 1.0, 0.9, 1.0));
+    }
+
+    // Hold is used up until the next piece spawns; the hold box shows it grayed
+    pub fn hold_locked(&self) -> bool {
+        self.dim_used_hold && self.hold_used
+    }
+
+    // One box per hold slot, oldest on top
+    fn draw_hold(&self, draw: &Draw) {
+        let layout = &self.hold_layout;
+        let first = layout.box_center(self.location, self.screen_width, self.screen_height);
+        let dimmed = self.hold_locked();
+        for slot in 0..self.hold_capacity {
+            let center = layout.slot_center(first, slot);
+            self.draw_piece_box(draw, layout, center, self.held.get(slot).copied(), dimmed);
+        }
+    }
+
+    fn draw_piece_box(
+        &self,
+        draw: &Draw,
+        layout: &PreviewLayout,
+        center: Vec2,
+        piece_type: Option<PieceType>,
+        dimmed: bool,
+    ) {
+        let box_size = layout.box_size();
+        draw.rect()
+            .xy(center)
+            .w_h(box_size, box_size)
+            .color(layout.background)
+            .stroke_weight(1.5)
+            .stroke(self.boundary_color);
+
+        let Some(piece_type) = piece_type else {
+            return;
+        };
+        let mut color = layout.cell_color(piece_type, self.get_piece_color());
+        if dimmed {
+            color = desaturate(color);
+            color.alpha *= 0.4;
+        }
+        for cell in layout.cell_positions(piece_type, center) {
+            if let Some(texture) = &self.faint_texture {
+                draw_sprite(draw, texture, cell, layout.mini_cell, color);
+                continue;
+            }
+            draw.rect()
+                .xy(cell)
+                .w_h(layout.mini_cell, layout.mini_cell)
+                .color(color)
+                .stroke_weight(1.0)
+                .stroke(BLACK);
+        }
+    }
+
+    // The next piece at its spawn position, and how far through the entry delay we are
+    fn spawn_telegraph(&self) -> Option<(PieceInstance, f32)> {
+        if !self.spawn_telegraph || self.entry_delay <= 0.0 {
+            return None;
+        }
+
+        let piece_type = self.next_piece()?;
+        let piece = self.spawn_piece(piece_type);
+
+        Some((piece, self.timers.entry.progress()))
+    }
+
+    fn draw_spawn_telegraph(&self, draw: &Draw) {
+        let Some((piece, progress)) = self.spawn_telegraph() else {
+            return;
+        };
+
+        // Faint preview that brightens as the piece is about to enter
+        let mut color = piece.color;
+        color.alpha *= 0.5 * progress;
+
+        for pos in piece.absolute_cells() {
+            self.draw_cell(draw, pos, color);
+        }
+    }
+
+    fn draw_overlay(&self, draw: &Draw) {
+        let Some(piece) = &self.active_piece else {
+            return;
+        };
+
+        for (placement, hole) in &self.overlay {
+            let color = if *hole {
+                rgba(1.0, 0.2, 0.2, 0.12)
+            } else {
+                rgba(0.2, 1.0, 0.3, 0.12)
+            };
+            for (x, y) in placement.cells(piece.typ) {
+                self.draw_cell(draw, BoardPosition { x, y }, color);
+            }
+        }
+    }
+
+    fn draw_hint(&self, draw: &Draw) {
+        let (Some(hint), Some(piece)) = (self.hint, &self.active_piece) else {
+            return;
+        };
+
+        let mut color = piece.color;
+        color.alpha *= 0.2;
+        for (x, y) in hint.cells(piece.typ) {
+            self.draw_cell(draw, BoardPosition { x, y }, color);
+        }
+    }
+
+    fn draw_clear_preview(&self, draw: &Draw) {
+        let (rows, cols) = self.lines_cleared_on_drop();
+        for row in rows {
+            let y = BoardPosition { x: 0, y: row }.to_screen(self).y;
+            draw.rect()
+                .x_y(self.location.x, y)
+                .w_h(self.screen_width, self.cell_size)
+                .color(rgba(1.0, 1.0, 1.0, 0.12));
+        }
+        for col in cols {
+            let x = BoardPosition { x: col, y: 0 }.to_screen(self).x;
+            draw.rect()
+                .x_y(x, self.location.y)
+                .w_h(self.cell_size, self.screen_height)
+                .color(rgba(1.0, 1.0, 1.0, 0.12));
+        }
+    }
+
+    fn draw_full_rows(&self, draw: &Draw) {
+        for row in self.board.full_rows() {
+            let y = BoardPosition { x: 0, y: row }.to_screen(self).y;
+            draw.rect()
+                .x_y(self.location.x, y)
+                .w_h(self.screen_width, self.cell_size)
+                .color(rgba(1.0, 1.0, 0.6, 0.3));
+        }
+    }
+
+    fn draw_clear_animation(&self, draw: &Draw) {
+        let Some(rows) = &self.rows_to_clear else {
+            return;
+        };
+
+        let progress = self.timers.clear_animation.progress();
+        let alpha = 0.5 * progress.powf(1.4);
+
+        // Find row bounds
+        let top_row = *rows.iter().max().unwrap_or(&0);
+        let bottom_row = *rows.iter().min().unwrap_or(&0);
+
+        // Calculate clear area
+        let top_bound = BoardPosition { x: 0, y: top_row }.to_screen(self).y;
+        let bottom_bound = BoardPosition {
+            x: 0,
+            y: bottom_row,
+        }
+        .to_screen(self)
+        .y;
+
+        let board_left_edge = self.location.x - (self.board.width as f32 * self.cell_size / 2.0);
+        let board_width = self.board.width as f32 * self.cell_size;
+
+        // Calculate separation based on progress. Minimum is half a cell height.
+        let center_y = bottom_bound + (top_bound - bottom_bound) / 2.0;
+        let half_max_distance = (top_bound - bottom_bound) / 2.0;
+        let half_separation = if top_row == bottom_row {
+            self.cell_size / 2.0 * progress
+        } else {
+            half_max_distance * progress
+        };
+
+        // Line positions
+        let top_y = center_y + half_separation;
+        let bottom_y = center_y - half_separation;
+
+        // Clear the area between the lines as they separate
+        if progress > 0.01 {
+            // Start clearing after a little bit of separation
+            let clear_height = (top_y - bottom_y).abs();
+            draw.rect()
+                .x_y(self.location.x, center_y)
+                .w_h(board_width, clear_height)
+                .color(rgba(1.0, 0.91, 0.65, alpha));
+        }
+
+        // Draw top and bottom lines
+        for y_pos in [top_y, bottom_y] {
+            // Main line
+            draw.line()
+                .points(
+                    vec2(board_left_edge, y_pos),
+                    vec2(board_left_edge + board_width, y_pos),
+                )
+                .color(rgba(1.0, 0.91, 0.65, alpha))
+                .stroke_weight(1.0);
+        }
+    }
+
+    // Cleared columns fade out as a vertical flash
+    fn draw_col_clear_animation(&self, draw: &Draw) {
+        let Some(cols) = &self.cols_to_clear else {
+            return;
+        };
+
+        let alpha = 0.5 * self.timers.clear_animation.progress().powf(1.4);
+        for &x in cols {
+            let col_x = BoardPosition { x, y: 0 }.to_screen(self).x;
+            draw.rect()
+                .x_y(col_x, self.location.y)
+                .w_h(self.cell_size, self.screen_height)
+                .color(rgba(1.0, 0.91, 0.65, alpha));
+        }
+    }
+
+    fn draw_game_over(&self, draw: &Draw, line_pos: f32) {
+        let board_left_edge = self.location.x - self.screen_width / 2.0;
+        let board_width = self.screen_width;
+
+        // Main line
+        draw.line()
+            .points(
+                vec2(board_left_edge, line_pos),
+                vec2(board_left_edge + board_width, line_pos),
+            )
+            //.color(rgba(1.0, 0.91, 0.65, 0.55))
+            .color(rgba(1.0, 0.8, 0.8, 0.65))
+            .stroke_weight(3.0);
+    }
+
+    // Diagnostic overlay under the board: timer bars and a status line.
+    // Read-only, never affects gameplay.
+    pub fn draw_telemetry(&self, draw: &Draw) {
+        let bottom = self.location.y - self.screen_height / 2.0;
+        let left = self.location.x - self.screen_width / 2.0;
+
+        let bars = [
+            (self.gravity_progress(), rgba(0.2, 0.6, 1.0, 0.8)),
+            (self.lock_progress(), rgba(1.0, 0.4, 0.2, 0.8)),
+        ];
+        for (i, (progress, color)) in bars.iter().enumerate() {
+            let width = self.screen_width * progress.clamp(0.0, 1.0);
+            draw.rect()
+                .x_y(left + width / 2.0, bottom - 8.0 - 8.0 * i as f32)
+                .w_h(width, 4.0)
+                .color(*color);
+        }
+
+        draw.text(&self.telemetry())
+            .x_y(self.location.x, bottom - 35.0)
+            .w(self.screen_width)
+            .color(RED)
+            .font_size(14);
+    }
+
+    // Endpoints of the origin crosshair: (+x, +y) arms from the board's location
+    // and a short tail the other way, sized in cells
+    pub fn origin_crosshair(&self) -> [(Vec2, Vec2); 2] {
+        let arm = self.cell_size * CROSSHAIR_CELLS;
+        let tail = arm * 0.25;
+        let (x, y) = self.location.into();
+        [
+            (vec2(x - tail, y), vec2(x + arm, y)),
+            (vec2(x, y - tail), vec2(x, y + arm)),
+        ]
+    }
+
+    // Per-board counterpart to the global axes in the debug display
+    pub fn draw_origin(&self, draw: &Draw) {
+        if !self.debug_axes {
+            return;
+        }
+        let [x_axis, y_axis] = self.origin_crosshair();
+        for ((start, end), color) in [(x_axis, RED), (y_axis, BLUE)] {
+            draw.line()
+                .points(start, end)
+                .color(color)
+                .stroke_weight(1.0);
+        }
+
+        draw.text(&format!(
+            "{} ({:.0}, {:.0})",
+            self.id, self.location.x, self.location.y
+        ))
+        .xy(self.location + vec2(self.cell_size, self.cell_size) * 0.5)
+        .left_justify()
+        .w(self.screen_width)
+        .color(RED)
+        .font_size(12);
+    }
+
+    // The ghost run's active piece, faint, where it was on this frame
+    fn draw_ghost(&self, draw: &Draw, ghost: &BoardSnapshot) {
+        let mut color = self.color;
+        color.alpha *= 0.25;
+
+        for &(x, y) in &ghost.active_cells {
+            if x >= 0 && x < self.board.width && y >= 0 && y < self.board.height {
+                let pos = BoardPosition { x, y };
+                if let Some(texture) = &self.faint_texture {
+                    draw.texture(texture)
+                        .xy(pos.to_screen(self))
+                        .w_h(self.cell_size, self.cell_size);
+                }
+                draw.rect()
+                    .xy(pos.to_screen(self))
+                    .w_h(self.cell_size, self.cell_size)
+                    .color(color);
+            }
+        }
+    }
+
+    fn draw_panel(&self, draw: &Draw, color: Rgba) {
+        draw.rect()
+            .x_y(self.location.x, self.location.y)
+            .w_h(self.screen_width, self.screen_height)
+            .color(color);
+    }
+
+    // Draw the outer boundary of the grid
+    fn draw_boundary(&self, draw: &Draw, color: Rgba) {
+        draw.rect()
+            .x_y(self.location.x, self.location.y)
+            .w_h(self.screen_width, self.screen_height)
+            .stroke_weight(1.0)
+            .stroke_color(color)
+            .color(rgba(0.0, 0.0, 0.0, 0.0));
+    }
+
+    /************************ Utility methods *******************************/
+
+    // Record this game and every one after a reset
+    pub fn start_recording(&mut self) {
+        self.record = true;
+        self.recording = Some(Replay::new(self.seed));
+    }
+
+    pub fn take_recording(&mut self) -> Option<Replay> {
+        self.recording.take()
+    }
+
+    pub fn set_ghost(&mut self, replay: Replay) {
+        self.ghost = Some(replay);
+    }
+
+    // The ghost's snapshot after as many updates as this run has had
+    pub fn ghost_frame(&self) -> Option<BoardSnapshot> {
+        let index = self.frame.checked_sub(1)?;
+        self.ghost.as_ref()?.frame(index)
+    }
+
+    // Whether ghost_frame has a frame, without rebuilding it
+    fn ghost_has_frame(&self) -> bool {
+        match (self.frame.checked_sub(1), &self.ghost) {
+            (Some(index), Some(ghost)) => index < ghost.len(),
+            _ => false,
+        }
+    }
+
+    pub fn snapshot(&self) -> BoardSnapshot {
+        let active_cells = self
+            .active_piece
+            .as_ref()
+            .map(|piece| {
+                piece
+                    .absolute_cells()
+                    .iter()
+                    .map(|&cell| self.board.wrap(cell))
+                    .map(|cell| (cell.x, cell.y))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        BoardSnapshot {
+            width: self.board.width,
+            height: self.board.height,
+            grid: self.board.grid().to_vec(),
+            active_cells,
+            score: self.score(),
+        }
+    }
+
+    // Cells shown differently since an earlier snapshot, with whether they show
+    // now: the board's changed locked cells plus the active piece's, old and new.
+    // Row by row from the bottom, so streamers can send diffs between keyframes.
+    pub fn diff(&self, prev: &BoardSnapshot) -> Vec<(BoardPosition, bool)> {
+        let now = self.snapshot();
+        let resized = (now.width, now.height) != (prev.width, prev.height);
+        let active = now
+            .active_cells
+            .iter()
+            .chain(&prev.active_cells)
+            .map(|&(x, y)| BoardPosition { x, y })
+            .filter(|pos| pos.x >= 0 && pos.x < now.width && pos.y >= 0 && pos.y < now.height);
+
+        let mut cells: Vec<BoardPosition> = self
+            .board
+            .diff(prev)
+            .into_iter()
+            .map(|(pos, _)| pos)
+            .chain(active)
+            .collect();
+        cells.sort_by_key(|pos| (pos.y, pos.x));
+        cells.dedup();
+
+        cells
+            .into_iter()
+            .map(|pos| (pos, now.is_visible(pos.x, pos.y)))
+            .filter(|&(pos, visible)| resized || visible != prev.is_visible(pos.x, pos.y))
+            .collect()
+    }
+
+    // The board alone at one pixel per screen unit: locked cells, plus the
+    // active piece if asked for, on black. Row 0 is the bottom of the image.
+    pub fn board_image(&self, include_active: bool) -> RgbaImage {
+        let cell = (self.cell_size.round() as u32).max(1);
+        let (width, height) = (self.board.width as u32, self.board.height as u32);
+        let mut image = RgbaImage::from_pixel(
+            width * cell,
+            height * cell,
+            to_pixel(rgba(0.0, 0.0, 0.0, 1.0)),
+        );
+
+        let mut fill = |x: isize, y: isize, color: Rgba| {
+            if x < 0 || y < 0 || x >= self.board.width || y >= self.board.height {
+                return;
+            }
+            let left = x as u32 * cell;
+            let top = (height - 1 - y as u32) * cell;
+            // leave a one pixel edge, like the drawn cell outline
+            let inset: u32 = (cell > 2).into();
+            for py in top + inset..top + cell - inset {
+                for px in left + inset..left + cell - inset {
+                    image.put_pixel(px, py, to_pixel(color));
+                }
+            }
+        };
+
+        for y in 0..self.board.height {
+            for x in 0..self.board.width {
+                if self.board.is_cell_filled(BoardPosition { x, y }) {
+                    fill(x, y, self.color);
+                }
+            }
+        }
+        if let Some(piece) = self.active_piece.as_ref().filter(|_| include_active) {
+            for cell in piece.absolute_cells().map(|cell| self.board.wrap(cell)) {
+                fill(cell.x, cell.y, piece.color);
+            }
+        }
+
+        image
+    }
+
+    pub fn export_png(&self, path: &Path, include_active: bool) -> io::Result<()> {
+        self.board_image(include_active)
+            .save(path)
+            .map_err(io::Error::other)
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn board_mut(&mut self) -> &mut Board {
+        &mut self.board
+    }
+}
+
+// A textured cell: the sprite under a wash of the cell color
+fn draw_sprite(draw: &Draw, texture: &wgpu::Texture, xy: Vec2, size: f32, color: Rgba) {
+    draw.texture(texture).xy(xy).w_h(size, size);
+    draw.rect().xy(xy).w_h(size, size).color(rgba(
+        color.red,
+        color.green,
+        color.blue,
+        CELL_TEXTURE_TINT * color.alpha,
+    ));
+}
+
+// A color as an 8-bit pixel, for board_image
+fn to_pixel(color: Rgba) -> image::Rgba<u8> {
+    let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    image::Rgba([
+        channel(color.red),
+        channel(color.green),
+        channel(color.blue),
+        channel(color.alpha),
+    ])
+}
+
+// Grey of the same brightness, used to dim the board on game over
+fn desaturate(color: Rgba) -> Rgba {
+    let avg = (color.red + color.green + color.blue) / 3.0;
+    rgba(avg, avg, avg, color.alpha)
+}
+
+// Perceived brightness, as bloom thresholds measure it
+fn luminance(color: Rgba) -> f32 {
+    0.2126 * color.red + 0.7152 * color.green + 0.0722 * color.blue
+}
+
+// Scale a color up to a target brightness; already brighter colors are kept
+fn emissive(color: Rgba, brightness: f32) -> Rgba {
+    let current = luminance(color);
+    if current <= 0.0 || current >= brightness {
+        return color;
+    }
+    let scale = brightness / current;
+    rgba(
+        color.red * scale,
+        color.green * scale,
+        color.blue * scale,
+        color.alpha,
+    )
+}
+
+fn blend_rgba(from: Rgba, to: Rgba, t: f32) -> Rgba {
+    rgba(
+        from.red + (to.red - from.red) * t,
+        from.green + (to.green - from.green) * t,
+        from.blue + (to.blue - from.blue) * t,
+        from.alpha + (to.alpha - from.alpha) * t,
+    )
+}
+
+/************************ Stdout functions *******************************/
+
+fn format_telemetry(
+    state: &GameState,
+    piece: Option<&PieceInstance>,
+    gravity_progress: f32,
+    lock_progress: f32,
+) -> String {
+    let piece = piece
+        .map(|piece| format!("{:?} rot {}", piece.typ, piece.rot_idx))
+        .unwrap_or_else(|| "-".to_owned());
+
+    format!(
+        "{} | {} | gravity {:.0}% | lock {:.0}%",
+        state.name(),
+        piece,
+        gravity_progress * 100.0,
+        lock_progress * 100.0
+    )
+}
+
+fn spawn_new_piece_msg(piece: &PieceInstance) {
+    println!("\n-- Spawned new piece --");
+    println!(
+        "PieceType: {:?}\nPosition:{:?}\n",
+        piece.typ, piece.position
+    )
+}
+
+fn print_col_score(col_score: &Vec<isize>) {
+    println!("\nCol score:");
+    println!("{:?}", col_score);
+}
+
+struct GameTimers {
+    gravity: Timer,
+    lock: Timer,
+    entry: Timer,
+    clear_animation: Timer,
+    pre_clear: Timer,
+    slide_animation: Timer,
+    game_over_animation: Timer,
+    idle: Timer,
+    finale: Timer,
+}
+
+impl GameTimers {
+    pub fn new(
+        gravity_interval: f32,
+        lock_delay: f32,
+        entry_delay: f32,
+        clear_duration: f32,
+        slide_duration: f32,
+        game_over_duration: f32,
+        idle_duration: f32,
+    ) -> Self {
+        Self {
+            gravity: Timer::new(gravity_interval),
+            lock: Timer::new(lock_delay),
+            entry: Timer::new(entry_delay),
+            clear_animation: Timer::new(clear_duration),
+            pre_clear: Timer::new(0.0),
+            slide_animation: Timer::new(slide_duration), // currently unused
+            game_over_animation: Timer::new(game_over_duration),
+            idle: Timer::new(idle_duration),
+            finale: Timer::new(0.0),
+        }
+    }
+
+    pub fn with_finale(mut self, finale_duration: f32) -> Self {
+        self.finale = Timer::new(finale_duration);
+        self
+    }
+
+    pub fn with_pre_clear(mut self, flash_duration: f32) -> Self {
+        self.pre_clear = Timer::new(flash_duration);
+        self
+    }
+
+    pub fn pause_all(&mut self) {
+        self.gravity.pause();
+        self.lock.pause();
+        self.entry.pause();
+        self.clear_animation.pause();
+        self.pre_clear.pause();
+        self.slide_animation.pause();
+        self.game_over_animation.pause();
+        self.idle.pause();
+        self.finale.pause();
+    }
+
+    pub fn resume_all(&mut self) {
+        self.gravity.resume();
+        self.lock.resume();
+        self.entry.resume();
+        self.clear_animation.resume();
+        self.pre_clear.resume();
+        self.slide_animation.resume();
+        self.game_over_animation.resume();
+        self.idle.resume();
+        self.finale.resume();
+    }
+
+    pub fn reset_all(&mut self) {
+        self.gravity.reset();
+        self.lock.reset();
+        self.entry.reset();
+        self.clear_animation.reset();
+        self.pre_clear.reset();
+        self.slide_animation.reset();
+        self.game_over_animation.reset();
+        self.idle.reset();
+        self.finale.reset();
+    }
+}
+
+impl GameState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameState::Ready => "Ready",
+            GameState::Falling => "Falling",
+            GameState::Locking { .. } => "Locking",
+            GameState::Clearing => "Clearing",
+            GameState::GameOver => "GameOver",
+            GameState::Finale => "Finale",
+            GameState::Frozen => "Frozen",
+            GameState::GameWon => "GameWon",
+            GameState::Paused => "Paused",
+        }
+    }
+}
+
+impl PartialEq for GameState {
+    fn eq(&self, other: &Self) -> bool {
+        use GameState::*;
+
+        matches!(
+            (self, other),
+            (Ready, Ready)
+                | (Falling, Falling)
+                | (Clearing, Clearing)
+                | (GameOver, GameOver)
+                | (Paused, Paused)
+                | (Locking { .. }, Locking { .. })
+                | (Finale, Finale)
+                | (Frozen, Frozen)
+                | (GameWon, GameWon)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CellSize, PreviewConfig};
+    use std::{cell::Cell, rc::Rc};
+
+    fn test_board(gravity_per_frame: f32) -> BoardInstance {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            gravity_per_frame,
+            ..Default::default()
+        };
+        BoardInstance::new("test", vec2(0.0, 0.0), &config)
+    }
+
+    fn lowest_cell_y(board: &BoardInstance) -> isize {
+        let piece = board.active_piece.as_ref().unwrap();
+        piece
+            .cells()
+            .iter()
+            .map(|&(_, dy)| piece.position.y + dy)
+            .min()
+            .unwrap()
+    }
+
+    fn filled_cells(board: &BoardInstance) -> Vec<bool> {
+        let mut cells = Vec::new();
+        for y in 0..board.board.height {
+            for x in 0..board.board.width {
+                cells.push(board.board.is_cell_filled(BoardPosition { x, y }));
+            }
+        }
+        cells
+    }
+
+    // Bottom row filled except for columns 4 and 5
+    fn board_with_gap() -> BoardInstance {
+        let mut board = test_board(0.0);
+        let i_piece = PieceInstance::new(PieceType::I, board.color, BoardPosition { x: 0, y: 0 });
+        board
+            .board
+            .commit_piece_at(&i_piece, BoardPosition { x: 0, y: 0 });
+        board
+            .board
+            .commit_piece_at(&i_piece, BoardPosition { x: 6, y: 0 });
+        board
+    }
+
+    fn run_until_ready(board: &mut BoardInstance) {
+        for _ in 0..10 {
+            board.update(CLEAR_DURATION, &[]);
+            if board.game_state == GameState::Ready {
+                return;
+            }
+        }
+        panic!("board never returned to Ready");
+    }
+
+    #[test]
+    fn test_pre_clear_flash_precedes_clear_animation() {
+        let mut board = board_with_gap();
+        board.timers.pre_clear = Timer::new(0.2);
+        let o_piece = PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 4, y: 1 });
+        board.active_piece = Some(o_piece);
+        board.game_state = GameState::Falling;
+        board.update(0.0, &[PlayerInput::HardDrop]);
+        board.update(0.0, &[]);
+        assert_eq!(board.game_state, GameState::Clearing);
+        assert!(board.is_flashing_cell(BoardPosition { x: 0, y: 0 }));
+        assert!(!board.is_flashing_cell(BoardPosition { x: 0, y: 1 }));
+
+        // Pausing holds the flash
+        board.update(0.0, &[PlayerInput::Pause]);
+        board.update(1.0, &[]);
+        board.update(0.0, &[PlayerInput::Pause]);
+        assert!(board.pre_clear_flashing);
+
+        // The animation only starts once the flash is over
+        board.update(0.1, &[]);
+        assert!(board.pre_clear_flashing);
+        assert_eq!(board.timers.clear_animation.progress(), 0.0);
+        board.update(0.1, &[]);
+        assert!(!board.pre_clear_flashing);
+        board.update(CLEAR_DURATION / 2.0, &[]);
+        assert!(board.timers.clear_animation.progress() > 0.0);
+        run_until_ready(&mut board);
+        assert!(board.board.full_rows().is_empty());
+    }
+
+    #[test]
+    fn test_piece_limit_ends_game() {
+        let mut board = test_board(0.0);
+        board.piece_limit = 2;
+        for _ in 0..2 {
+            assert_ne!(board.game_state, GameState::GameWon);
+            board.update(0.0, &[]);
+            board.update(0.0, &[PlayerInput::HardDrop]);
+            board.update(BoardConfig::default().lock_delay, &[]);
+        }
+        assert_eq!(board.pieces_placed, 2);
+        assert_eq!(board.game_state, GameState::GameWon);
+        assert!(board.active_piece.is_none());
+    }
+
+    #[test]
+    fn test_time_limit_ends_game_and_pauses() {
+        let mut board = test_board(0.0);
+        board.time_limit = 1.0;
+        board.update(0.6, &[]);
+
+        // Paused time doesn't count
+        board.update(0.0, &[PlayerInput::Pause]);
+        board.update(5.0, &[]);
+        board.update(0.0, &[PlayerInput::Pause]);
+        assert_ne!(board.game_state, GameState::GameWon);
+
+        board.update(0.3, &[]);
+        assert_ne!(board.game_state, GameState::GameWon);
+
+        // Time runs out mid-lock: the piece still locks before the game ends
+        board.update(0.0, &[PlayerInput::HardDrop]);
+        assert!(matches!(board.game_state, GameState::Locking { .. }));
+        board.update(0.2, &[]);
+        assert!(board.active_piece.is_some());
+        assert_ne!(board.game_state, GameState::GameWon);
+        board.update(BoardConfig::default().lock_delay, &[]);
+        assert_eq!(board.pieces_placed, 1);
+        assert_eq!(board.game_state, GameState::GameWon);
+    }
+
+    #[test]
+    fn test_flip_pushes_active_piece_clear_of_stack() {
+        let mut board = test_board(0.0);
+        board.flip_input = true;
+        board.set_state_for_test(
+            GameState::Falling,
+            "
+            #.........
+            ##########
+            .#########
+            ",
+        );
+        let o_piece = PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 0, y: 1 });
+        board.active_piece = Some(o_piece);
+        board.update(0.0, &[PlayerInput::Flip]);
+
+        // The lone cell is now at the bottom, under the piece
+        assert!(board.board.is_cell_filled(BoardPosition { x: 0, y: 0 }));
+        assert!(!board.board.is_cell_filled(BoardPosition { x: 0, y: 2 }));
+        assert!(board.board.is_cell_filled(BoardPosition { x: 1, y: 2 }));
+        let piece = board.active_piece.as_ref().unwrap();
+        assert_eq!(piece.position.y, 3);
+    }
+
+    #[test]
+    fn test_clear_flash_center_from_rows() {
+        let mut board = test_board(0.0);
+        board.cell_size = 10.0;
+        board.location = vec2(100.0, 50.0);
+        board.clear_flash_background = true;
+        assert_eq!(board.background_flash(), None);
+
+        // Rows 0 and 3 of a 20 row board: midway is 1.5 rows, 8 rows below center
+        board.rows_to_clear = Some(vec![0, 3]);
+        assert_eq!(board.clear_center(), Some(vec2(100.0, -30.0)));
+        assert_eq!(
+            board.background_flash(),
+            Some((vec2(100.0, -30.0), 2.0 * CLEAR_FLASH_CELLS * 10.0))
+        );
+
+        // Columns 9 only: right of center by 4.5 cells
+        board.rows_to_clear = None;
+        board.cols_to_clear = Some(vec![9]);
+        assert_eq!(board.clear_center(), Some(vec2(145.0, 50.0)));
+    }
+
+    #[test]
+    fn test_row_filled_by_move_matches_gravity() {
+        // Gravity drops an O piece into the gap
+        let mut by_gravity = board_with_gap();
+        let o_piece =
+            PieceInstance::new(PieceType::O, by_gravity.color, BoardPosition { x: 4, y: 1 });
+        by_gravity.active_piece = Some(o_piece);
+        by_gravity.game_state = GameState::Falling;
+        let gravity_interval = BoardConfig::default().gravity_interval;
+        by_gravity.update(gravity_interval, &[]);
+        assert_eq!(
+            by_gravity.game_state,
+            GameState::Locking {
+                now: true,
+                hard_drop: false
+            }
+        );
+
+        // Movement places an O piece into the gap
+        let mut by_move = board_with_gap();
+        let o_piece = PieceInstance::new(PieceType::O, by_move.color, BoardPosition { x: 2, y: 1 });
+        by_move.active_piece = Some(o_piece);
+        by_move.game_state = GameState::Falling;
+        by_move.move_active_piece(BoardPosition { x: 4, y: 0 });
+        assert_eq!(
+            by_move.game_state,
+            GameState::Locking {
+                now: true,
+                hard_drop: false
+            }
+        );
+
+        // A Falling frame after the move must not undo the immediate lock
+        by_move.update(0.0, &[]);
+        assert_eq!(by_move.game_state, GameState::Clearing);
+
+        run_until_ready(&mut by_gravity);
+        run_until_ready(&mut by_move);
+
+        assert_eq!(filled_cells(&by_gravity), filled_cells(&by_move));
+        assert_eq!(
+            by_gravity.board.col_score_all(),
+            by_move.board.col_score_all()
+        );
+        assert_eq!(by_gravity.score(), by_move.score());
+    }
+
+    #[test]
+    fn test_event_callback_counts_tetris() {
+        let mut board = test_board(0.0);
+        board.set_state_for_test(
+            GameState::Falling,
+            "
+            #########.
+            #########.
+            #########.
+            #########.
+            ",
+        );
+        let mut i_piece =
+            PieceInstance::new(PieceType::I, board.color, BoardPosition { x: 7, y: 10 });
+        i_piece.rot_idx = 1;
+        board.active_piece = Some(i_piece);
+
+        let count = Rc::new(Cell::new(0));
+        let tetrises = Rc::new(Cell::new(0));
+        let (c, t) = (count.clone(), tetrises.clone());
+        board.on_event(Box::new(move |cue| {
+            c.set(c.get() + 1);
+            if *cue == SoundCue::Tetris {
+                t.set(t.get() + 1);
+            }
+        }));
+
+        board.update(0.0, &[PlayerInput::HardDrop]);
+        board.update(0.0, &[]);
+        assert_eq!(board.game_state, GameState::Clearing);
+
+        // Lock and Tetris, both also in the drained queue
+        assert_eq!(count.get(), 2);
+        assert_eq!(tetrises.get(), 1);
+        assert_eq!(
+            board.drain_sound_cues(),
+            vec![SoundCue::Lock, SoundCue::Tetris]
+        );
+    }
+
+    #[test]
+    fn test_sound_cues_for_single_line_clear() {
+        let mut board = board_with_gap();
+        let o_piece = PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 5, y: 1 });
+        board.active_piece = Some(o_piece);
+        board.game_state = GameState::Falling;
+
+        // Slide into place over the gap, fall into it, then commit
+        board.update(0.0, &[PlayerInput::L]);
+        board.update(BoardConfig::default().gravity_interval, &[]);
+        board.update(0.0, &[]);
+        assert_eq!(
+            board.drain_sound_cues(),
+            vec![SoundCue::Move, SoundCue::Lock, SoundCue::Clear(1)]
+        );
+
+        // The clear animation doesn't fire the cues again
+        run_until_ready(&mut board);
+        assert!(board.drain_sound_cues().is_empty());
+    }
+
+    #[test]
+    fn test_muted_board_emits_nothing() {
+        let mut board = board_with_gap();
+        board.set_emit(false);
+        let o_piece = PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 5, y: 1 });
+        board.active_piece = Some(o_piece);
+        board.game_state = GameState::Falling;
+
+        board.update(0.0, &[PlayerInput::L]);
+        board.update(BoardConfig::default().gravity_interval, &[]);
+        board.update(0.0, &[]);
+        assert!(board.drain_sound_cues().is_empty());
+
+        // The clear still happens and scores
+        run_until_ready(&mut board);
+        assert!(board.score() > 0);
+        let bottom_row = &filled_cells(&board)[..10];
+        assert_eq!(bottom_row.iter().filter(|&&filled| filled).count(), 2);
+
+        // Unmuting only affects cues fired afterwards
+        board.set_emit(true);
+        assert!(board.drain_sound_cues().is_empty());
+    }
+
+    #[test]
+    fn test_soloed_out_board_emits_nothing() {
+        let mut board = test_board(0.0);
+        let count = Rc::new(Cell::new(0));
+        let c = count.clone();
+        board.on_event(Box::new(move |_| c.set(c.get() + 1)));
+        board.update(0.0, &[]);
+
+        // No handlers and nothing drained while another board is soloed
+        board.set_soloed_out(true);
+        board.update(0.0, &[PlayerInput::L]);
+        assert_eq!(count.get(), 0);
+        assert!(board.drain_sound_cues().is_empty());
+
+        // Clearing the solo brings back the board's own mute setting
+        board.set_emit(false);
+        board.set_soloed_out(false);
+        assert!(!board.emits());
+        board.set_emit(true);
+        board.update(0.0, &[PlayerInput::L]);
+        assert_eq!(count.get(), 1);
+        assert_eq!(board.drain_sound_cues(), vec![SoundCue::Move]);
+    }
+
+    #[test]
+    fn test_frozen_board_resets_after_idle() {
+        let config = BoardConfig {
+            idle_reset: 10.0,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.update(0.5, &[]);
+        board.update(0.5, &[]);
+        assert!((board.playtime() - 1.0).abs() < 1e-6);
+
+        // Paused time doesn't count as playtime
+        board.update(0.0, &[PlayerInput::Pause]);
+        board.update(5.0, &[]);
+        board.update(0.0, &[PlayerInput::Pause]);
+        assert!((board.playtime() - 1.0).abs() < 1e-6);
+
+        // Game over, then sit frozen
+        board.game_state = GameState::GameOver;
+        board.update(GAME_OVER_DURATION, &[]);
+        assert_eq!(board.game_state, GameState::Frozen);
+        board.update(9.0, &[]);
+        assert_eq!(board.game_state, GameState::Frozen);
+
+        board.update(1.0, &[]);
+        assert_eq!(board.game_state, GameState::Ready);
+        assert_eq!(board.playtime(), 0.0);
+        assert!(board.active_piece.is_none());
+        assert!(filled_cells(&board).iter().all(|&filled| !filled));
+    }
+
+    fn drop_scoring_board(soft_drop_points: usize, hard_drop_points: usize) -> BoardInstance {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            soft_drop_points,
+            hard_drop_points,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.update(0.0, &[]);
+        board
+    }
+
+    // Rows the active piece can still fall
+    fn drop_distance(board: &mut BoardInstance) -> usize {
+        let start_y = board.active_piece.as_ref().unwrap().position.y;
+        let (drop_pos, _) = board.get_drop_position().unwrap();
+        (start_y - drop_pos.y) as usize
+    }
+
+    // Hard drop the active piece and let it lock
+    fn hard_drop_and_lock(board: &mut BoardInstance) {
+        board.update(0.0, &[PlayerInput::HardDrop]);
+        board.update(BoardConfig::default().lock_delay, &[]);
+        assert_eq!(board.game_state, GameState::Ready);
+    }
+
+    #[test]
+    fn test_hard_drop_distance_scoring() {
+        let mut board = drop_scoring_board(1, 2);
+        let distance = drop_distance(&mut board);
+        assert!(distance > 0);
+
+        hard_drop_and_lock(&mut board);
+
+        // A point per cell of the piece for the lock, 2 per row dropped
+        assert_eq!(board.score(), 4 + distance * 2);
+    }
+
+    #[test]
+    fn test_soft_drop_distance_scoring() {
+        let mut board = drop_scoring_board(3, 7);
+        let distance = drop_distance(&mut board);
+
+        // Soft drop five rows, then hard drop the rest
+        board.update(
+            0.0,
+            &[
+                PlayerInput::SoftDrop,
+                PlayerInput::SoftDrop,
+                PlayerInput::SoftDrop,
+                PlayerInput::SoftDrop,
+                PlayerInput::SoftDrop,
+            ],
+        );
+        assert_eq!(board.soft_drop_cells, 5);
+        assert_eq!(drop_distance(&mut board), distance - 5);
+
+        hard_drop_and_lock(&mut board);
+        assert_eq!(board.score(), 4 + 5 * 3 + (distance - 5) * 7);
+
+        // Counters start over for the next piece
+        assert_eq!(board.soft_drop_cells, 0);
+        assert_eq!(board.hard_drop_cells, 0);
+    }
+
+    #[test]
+    fn test_forced_sequence_spawns_in_order() {
+        let mut board = test_board(0.0);
+        board.update(0.0, &[]);
+        board.force_sequence(&[PieceType::I, PieceType::O]);
+        board.push_next(PieceType::T);
+        assert_eq!(board.next_piece(), Some(PieceType::I));
+
+        let mut spawned = Vec::new();
+        for _ in 0..3 {
+            hard_drop_and_lock(&mut board);
+            board.update(0.0, &[]);
+            spawned.push(board.active_piece.as_ref().unwrap().typ);
+        }
+        assert_eq!(spawned, vec![PieceType::I, PieceType::O, PieceType::T]);
+
+        // Script used up: the queue is random again
+        assert_eq!(board.scripted_len, 0);
+        assert_eq!(board.next_queue.len(), NEXT_QUEUE_LEN);
+    }
+
+    #[test]
+    fn test_preview_list_follows_queue() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            preview: PreviewConfig {
+                enabled: true,
+                count: 5,
+                list_gap: 0.5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        let seq = [
+            PieceType::I,
+            PieceType::O,
+            PieceType::T,
+            PieceType::S,
+            PieceType::Z,
+            PieceType::J,
+        ];
+        board.force_sequence(&seq);
+
+        let slots = board.preview_slots();
+        let types: Vec<_> = slots.iter().map(|&(t, _)| t).collect();
+        assert_eq!(types, seq[..5]);
+
+        // Boxes share a column, each a box plus the gap below the last
+        let layout = board.preview.unwrap();
+        let step = layout.box_size() + layout.list_gap;
+        for pair in slots.windows(2) {
+            assert_eq!(pair[0].1.x, pair[1].1.x);
+            assert!((pair[0].1.y - pair[1].1.y - step).abs() < 1e-4);
+        }
+
+        // Spawning the next piece shifts the list up by one
+        board.update(0.0, &[]);
+        assert_eq!(board.active_piece.as_ref().unwrap().typ, PieceType::I);
+        let types: Vec<_> = board.preview_slots().iter().map(|&(t, _)| t).collect();
+        assert_eq!(types[..4], seq[1..5]);
+        assert_eq!(board.preview_slots().len(), 5);
+    }
+
+    #[test]
+    fn test_lock_delay_curve_by_level() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            lock_delay: 0.5,
+            level_lines: 1,
+            lock_delay_curve: LockDelayCurve {
+                scale: 0.5,
+                floor: 0.1,
+            },
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        assert_eq!(board.lock_delay(), 0.5);
+
+        let mut delays = Vec::new();
+        for _ in 0..4 {
+            board.count_lines(1);
+            delays.push(board.lock_delay());
+        }
+        assert_eq!(delays, vec![0.25, 0.125, 0.1, 0.1]);
+
+        board.count_lines(100);
+        assert_eq!(board.level(), 104);
+        assert_eq!(board.lock_delay(), 0.1);
+
+        board.reset();
+        assert_eq!(board.lock_delay(), 0.5);
+    }
+
+    #[test]
+    fn test_clear_spawns_particles_on_cleared_row() {
+        let mut board = board_with_gap();
+        board.clear_particles = 12;
+        let o_piece = PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 4, y: 1 });
+        board.active_piece = Some(o_piece);
+        board.game_state = GameState::Falling;
+        board.update(0.0, &[PlayerInput::HardDrop]);
+        board.update(0.0, &[]);
+        assert_eq!(board.game_state, GameState::Clearing);
+
+        // One row cleared: 12 sparks, all starting on row 0
+        let row_y = BoardPosition { x: 0, y: 0 }.to_screen(&board).y;
+        assert_eq!(board.particles.len(), 12);
+        assert!(board.particles.iter().all(|p| p.position.y == row_y));
+
+        // Paused sparks stay put
+        let before = board.particles[1].position;
+        board.update(0.0, &[PlayerInput::Pause]);
+        board.update(0.1, &[]);
+        assert_eq!(board.particles[1].position, before);
+
+        // Capped by max_particles
+        board.max_particles = 15;
+        board.spawn_clear_particles(1);
+        assert_eq!(board.particles.len(), 15);
+    }
+
+    #[test]
+    fn test_hard_drop_lockout_after_soft_drop() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            hard_drop_lockout: 0.1,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.update(0.0, &[]);
+        board.update(0.0, &[PlayerInput::SoftDrop]);
+
+        // Too soon after the soft drop: ignored
+        board.update(0.05, &[PlayerInput::HardDrop]);
+        assert_eq!(board.pieces_placed, 0);
+        assert!(lowest_cell_y(&board) > 0);
+
+        // Past the window: applies
+        board.update(0.06, &[PlayerInput::HardDrop]);
+        assert_eq!(lowest_cell_y(&board), 0);
+        board.update(BoardConfig::default().lock_delay, &[]);
+        assert_eq!(board.pieces_placed, 1);
+    }
+
+    #[test]
+    fn test_moves_after_hard_drop_in_same_frame_are_dropped() {
+        let mut board = test_board(0.0);
+        board.update(0.0, &[]);
+        let x = board.active_piece.as_ref().unwrap().position.x;
+
+        board.update(0.0, &[PlayerInput::HardDrop, PlayerInput::R]);
+        let piece = board.active_piece.as_ref().unwrap();
+        assert_eq!(piece.position.x, x);
+        assert_eq!(lowest_cell_y(&board), 0);
+    }
+
+    #[test]
+    fn test_render_alpha_eases_the_last_move() {
+        let mut board = test_board(0.0);
+        board.update(0.0, &[]);
+        board.update(0.0, &[PlayerInput::SoftDrop]);
+
+        // A quarter of the way from the last step: mostly still a row up
+        board.set_render_alpha(0.25);
+        let offset = board.piece_draw_offset();
+        assert_eq!(offset.x, 0.0);
+        assert!((offset.y - board.cell_size * 0.75).abs() < 1e-3);
+
+        board.set_render_alpha(1.0);
+        assert_eq!(board.piece_draw_offset(), Vec2::ZERO);
+
+        // A rotation snaps
+        board.update(0.0, &[PlayerInput::Rotate]);
+        board.set_render_alpha(0.0);
+        assert_eq!(board.piece_draw_offset(), Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_dirty_only_on_change() {
+        let mut board = test_board(0.0);
+        board.set_render_on_change(true);
+        board.update(0.0, &[]);
+        board.update(1.0, &[]);
+        board.set_state_for_test(GameState::Frozen, "");
+        board.update(1.0, &[]);
+        board.update(1.0, &[]);
+        assert!(!board.is_dirty());
+
+        // A piece moving sideways redraws
+        let mut board = test_board(0.0);
+        board.set_render_on_change(true);
+        board.update(0.0, &[]);
+        board.update(1.0, &[]);
+        board.update(0.0, &[PlayerInput::L]);
+        assert!(board.is_dirty());
+
+        // The game-over sweep animates a still board
+        board.set_state_for_test(GameState::GameOver, "");
+        board.update(0.0, &[]);
+        assert!(board.is_dirty());
+
+        // The telegraph animates only during the entry delay, the ghost only while
+        // it has frames, and the garbage meter only when a batch arrives or is due
+        let config = BoardConfig {
+            entry_delay: 0.5,
+            spawn_telegraph: true,
+            garbage_delay: 1.0,
+            gravity_enabled: false,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        let mut past = Replay::new(0);
+        past.record(&board.board, board.snapshot());
+        board.set_ghost(past);
+        board.set_render_on_change(true);
+        board.update(0.1, &[]);
+        assert!(board.is_dirty());
+        board.update(0.5, &[]);
+        board.update(0.0, &[]);
+        assert_eq!(board.game_state, GameState::Falling);
+        assert!(!board.is_dirty());
+
+        board.queue_garbage(2);
+        board.update(0.0, &[]);
+        assert!(board.is_dirty());
+        board.update(0.5, &[]);
+        assert!(!board.is_dirty());
+        board.update(0.5, &[]);
+        assert!(board.is_dirty());
+        board.update(0.5, &[]);
+        assert!(!board.is_dirty());
+
+        // A frozen board stays clean with a telegraph, a ghost and garbage pending
+        board.queue_garbage(1);
+        board.update(0.0, &[]);
+        board.set_state_for_test(GameState::Frozen, "");
+        board.update(1.0, &[]);
+        board.update(1.0, &[]);
+        assert!(!board.is_dirty());
+
+        // Not tracked: every update counts as a change
+        let mut board = test_board(0.0);
+        board.set_state_for_test(GameState::Frozen, "");
+        board.update(1.0, &[]);
+        assert!(board.is_dirty());
+    }
+
+    #[test]
+    fn test_held_rotate_does_not_repeat() {
+        use PlayerInput::*;
+
+        // Presses of held keys, as auto-repeat sends them, counting what applies
+        let presses = [Rotate, Rotate, Rotate, L, L];
+        let applied = |rotation_repeats: bool| {
+            let mut held = Vec::new();
+            let mut applied = Vec::new();
+            for input in presses {
+                if accept_key_repeat(input, &held, rotation_repeats) {
+                    applied.push(input);
+                }
+                if !held.contains(&input) {
+                    held.push(input);
+                }
+            }
+            applied
+        };
+        assert_eq!(applied(false), vec![Rotate, L, L]);
+        assert_eq!(applied(true), presses.to_vec());
+    }
+
+    #[test]
+    fn test_draw_with_panel() {
+        let config = BoardConfig {
+            panel: true,
+            panel_color: [0.2, 0.4, 0.6, 0.5],
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        let draw = Draw::new();
+
+        board.update(0.0, &[]);
+        board.draw(&draw);
+        board.game_state = GameState::Frozen;
+        board.draw(&draw);
+
+        let dimmed = desaturate(board.panel_color.unwrap());
+        assert!((dimmed.red - 0.4).abs() < 1e-6);
+        assert_eq!(dimmed.red, dimmed.blue);
+        assert_eq!(dimmed.alpha, 0.5);
+    }
+
+    #[test]
+    fn test_draw_with_shadows() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            shadow: true,
+            shadow_offset: [2.0, -2.0],
+            shadow_color: [0.0, 0.0, 0.0, 0.4],
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.set_state_for_test(GameState::Ready, "####..####");
+        let draw = Draw::new();
+
+        board.update(0.0, &[]);
+        board.draw(&draw);
+        board.set_batch_cells(true);
+        board.draw(&draw);
+
+        let (offset, color) = board.shadow.unwrap();
+        assert_eq!(offset, vec2(2.0, -2.0));
+        assert_eq!(color.alpha, 0.4);
+
+        // Batched: one shadow quad under each of the 8 filled cells
+        let mesh = board.cell_mesh(false, f32::MIN, board.color);
+        assert_eq!(mesh.vertex_count(), 2 * 8 * 4);
+    }
+
+    #[test]
+    fn test_ghost_reads_matching_frame() {
+        // A short past run, frames told apart by score
+        let mut past = Replay::new(0);
+        let past_board = test_board(0.0);
+        for score in 0..3 {
+            let mut snapshot = past_board.snapshot();
+            snapshot.score = score;
+            past.record(&past_board.board, snapshot);
+        }
+
+        let mut board = test_board(0.0);
+        board.set_ghost(past);
+        board.start_recording();
+        assert!(board.ghost_frame().is_none());
+
+        for frame in 0..3 {
+            board.update(0.0, &[]);
+            assert_eq!(board.ghost_frame().unwrap().score, frame);
+        }
+
+        // The current run outlasts the replay
+        board.update(0.0, &[]);
+        assert!(board.ghost_frame().is_none());
+        board.draw(&Draw::new());
+
+        assert_eq!(board.take_recording().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_replay_rebuilds_every_frame() {
+        let mut board = test_board(0.0);
+        board.start_recording();
+        let interval = board.gravity_interval();
+        let mut snapshots = Vec::new();
+        for i in 0..150 {
+            let inputs = if i % 10 == 9 {
+                vec![PlayerInput::HardDrop]
+            } else {
+                vec![]
+            };
+            board.update(interval, &inputs);
+            snapshots.push(board.snapshot());
+        }
+
+        let replay = board.take_recording().unwrap();
+        assert_eq!(replay.len(), snapshots.len());
+        for (index, snapshot) in snapshots.iter().enumerate() {
+            assert_eq!(replay.frame(index).as_ref(), Some(snapshot));
+        }
+        assert!(replay.frame(snapshots.len()).is_none());
+
+        // Far smaller than a snapshot per frame
+        let full = serde_json::to_string(&snapshots).unwrap().len();
+        assert!(serde_json::to_string(&replay).unwrap().len() * 4 < full);
+    }
+
+    #[test]
+    fn test_reset_records_the_next_game_on_the_ghost_seed() {
+        let config = BoardConfig {
+            seed: Some(7),
+            reseed_on_reset: ReseedOnReset::Increment,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.set_ghost(Replay::new(7));
+        board.start_recording();
+        board.update(0.0, &[]);
+        assert_eq!(board.take_recording().unwrap().len(), 1);
+
+        // After a reset the next game is recorded too, still on the ghost's seed
+        board.reset();
+        assert_eq!(board.seed(), 7);
+        board.update(0.0, &[]);
+        board.update(0.0, &[]);
+        let replay = board.take_recording().unwrap();
+        assert_eq!((replay.seed, replay.len()), (7, 2));
+    }
+
+    #[test]
+    fn test_replay_path_per_board() {
+        let path = Replay::board_path(Path::new("runs/replay.json"), "board1");
+        assert_eq!(path, Path::new("runs/replay_board1.json"));
+        let path = Replay::board_path(Path::new("replay"), "board2");
+        assert_eq!(path, Path::new("replay_board2"));
+    }
+
+    #[test]
+    fn test_left_soft_drop_combo() {
+        use PlayerInput::*;
+
+        let mut board = test_board(0.0);
+        board.force_sequence(&[PieceType::T]);
+        board.update(0.0, &[]);
+        let start = board.active_piece.as_ref().unwrap().position;
+
+        // Left pressed while Down is held: both apply this frame
+        let inputs = apply_input_combos(vec![L], &[SoftDrop], ComboOrder::MoveFirst);
+        assert_eq!(inputs, vec![L, SoftDrop]);
+        board.update(0.0, &inputs);
+        let pos = board.active_piece.as_ref().unwrap().position;
+        assert_eq!((pos.x, pos.y), (start.x - 1, start.y - 1));
+
+        // Drop first puts the drop ahead of the move
+        let inputs = apply_input_combos(vec![SoftDrop], &[L, SoftDrop], ComboOrder::DropFirst);
+        assert_eq!(inputs, vec![SoftDrop, L]);
+
+        // Nothing held: a lone move stays a lone move
+        assert_eq!(
+            apply_input_combos(vec![R], &[], ComboOrder::MoveFirst),
+            vec![R]
+        );
+
+        // Without a combo, nothing is reordered
+        for order in [ComboOrder::MoveFirst, ComboOrder::DropFirst] {
+            assert_eq!(
+                apply_input_combos(vec![L, HardDrop], &[], order),
+                vec![L, HardDrop]
+            );
+            assert_eq!(
+                apply_input_combos(vec![SoftDrop, Hold], &[], order),
+                vec![SoftDrop, Hold]
+            );
+        }
+
+        // The pair is ordered in place, around the other inputs
+        assert_eq!(
+            apply_input_combos(
+                vec![Rotate, L, HardDrop],
+                &[SoftDrop],
+                ComboOrder::DropFirst
+            ),
+            vec![Rotate, SoftDrop, L, HardDrop]
+        );
+
+        // Holding Left and Right adds both, and the opposite-input rule picks one
+        let inputs = apply_input_combos(vec![SoftDrop], &[L, R], ComboOrder::MoveFirst);
+        assert_eq!(inputs, vec![L, R, SoftDrop]);
+        assert_eq!(
+            resolve_opposite_inputs(inputs, OppositeInputs::LastWins),
+            vec![R, SoftDrop]
+        );
+    }
+
+    #[test]
+    fn test_opposite_input_rules() {
+        let net_movement = |rule| {
+            let mut board = test_board(0.0);
+            board.update(0.0, &[]);
+            let start_x = board.active_piece.as_ref().unwrap().position.x;
+
+            let inputs = resolve_opposite_inputs(
+                vec![PlayerInput::L, PlayerInput::Rotate, PlayerInput::R],
+                rule,
+            );
+            assert!(inputs.contains(&PlayerInput::Rotate));
+            board.update(0.0, &inputs);
+            board.active_piece.as_ref().unwrap().position.x - start_x
+        };
+
+        assert_eq!(net_movement(OppositeInputs::FirstWins), -1);
+        assert_eq!(net_movement(OppositeInputs::LastWins), 1);
+        assert_eq!(net_movement(OppositeInputs::Cancel), 0);
+
+        // A lone direction is never dropped
+        let inputs = resolve_opposite_inputs(vec![PlayerInput::L], OppositeInputs::Cancel);
+        assert_eq!(inputs, vec![PlayerInput::L]);
+    }
+
+    #[test]
+    fn test_upside_down_orientation() {
+        let config = BoardConfig {
+            orientation: 180,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+
+        // The logical bottom row shows at the top of the screen
+        let bottom = board.to_view(BoardPosition { x: 0, y: 0 });
+        let top = board.to_view(BoardPosition {
+            x: 0,
+            y: board.board.height - 1,
+        });
+        assert!(bottom.y > top.y);
+        assert!(bottom.y > board.location.y);
+
+        // Left on screen is still left on screen
+        board.update(0.0, &[]);
+        let view_x =
+            |board: &BoardInstance| board.to_view(board.active_piece().unwrap().position).x;
+        let start = view_x(&board);
+        board.update(0.0, &[PlayerInput::L]);
+        assert!(view_x(&board) < start);
+        assert!((start - view_x(&board) - board.cell_size).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_lock_waits_for_beat() {
+        // 120 bpm: a beat every 0.5s
+        let mut board = test_board(0.0);
+        board.set_beat_lock(120);
+        board.update(0.0, &[]);
+        board.update(0.0, &[PlayerInput::HardDrop]);
+        assert!(matches!(board.game_state, GameState::Locking { .. }));
+
+        // Lock delay is over at 0.25s, but the beat isn't until 0.5s
+        board.update(BoardConfig::default().lock_delay, &[]);
+        assert!(matches!(board.game_state, GameState::Locking { .. }));
+        board.update(0.2, &[]);
+        assert!(matches!(board.game_state, GameState::Locking { .. }));
+        assert!(board.active_piece.is_some());
+
+        // The frame that crosses the beat commits
+        board.update(0.1, &[]);
+        assert_eq!(board.game_state, GameState::Ready);
+        assert!(board.active_piece.is_none());
+    }
+
+    #[test]
+    fn test_score_race_ends_other_board() {
+        let mut leader = BoardInstance::new("leader", vec2(0.0, 0.0), &BoardConfig::default());
+        let mut trailer = BoardInstance::new("trailer", vec2(0.0, 0.0), &BoardConfig::default());
+        leader.update(0.0, &[]);
+        trailer.update(0.0, &[]);
+
+        leader.board.add_score(900);
+        trailer.board.add_score(300);
+        assert_eq!(settle_score_race([&mut leader, &mut trailer], 1000), None);
+        assert_eq!(trailer.game_state, GameState::Falling);
+
+        leader.board.add_score(100);
+        assert_eq!(
+            settle_score_race([&mut leader, &mut trailer], 1000).as_deref(),
+            Some("leader")
+        );
+        assert_eq!(leader.game_state, GameState::GameWon);
+        assert_eq!(trailer.game_state, GameState::GameOver);
+
+        // Settled: later scores don't change the result
+        trailer.board.add_score(5000);
+        assert_eq!(settle_score_race([&mut leader, &mut trailer], 1000), None);
+        assert_eq!(leader.game_state, GameState::GameWon);
+    }
+
+    // Every row filled except columns 4 and 5, so nothing clears and spawns collide
+    fn fill_stack(board: &mut BoardInstance) {
+        let i_piece = PieceInstance::new(PieceType::I, board.color, BoardPosition { x: 0, y: 0 });
+        for y in 0..board.board.height {
+            for x in [0, 6] {
+                board
+                    .board
+                    .commit_piece_at(&i_piece, BoardPosition { x, y });
+            }
+        }
+    }
+
+    #[test]
+    fn test_mercy_forgives_first_topout() {
+        let config = BoardConfig {
+            mercy_count: 1,
+            mercy_rows: 4,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        let height = board.board.height;
+
+        // First top-out: the top rows clear and play continues
+        fill_stack(&mut board);
+        board.update(0.0, &[]);
+        assert_eq!(board.game_state, GameState::Falling);
+        assert_eq!(board.stack_height(), height - 4);
+        assert_eq!(board.mercies_left(), 0);
+
+        // Second top-out ends the game
+        fill_stack(&mut board);
+        board.active_piece = None;
+        board.game_state = GameState::Ready;
+        board.update(0.0, &[]);
+        assert_eq!(board.game_state, GameState::GameOver);
+
+        board.reset();
+        assert_eq!(board.mercies_left(), 1);
+    }
+
+    #[test]
+    fn test_shared_sequence_deals_every_board_the_same_pieces() {
+        let boards = |seeds: [u64; 2]| {
+            seeds.map(|seed| {
+                let config = BoardConfig {
+                    seed: Some(seed),
+                    ..Default::default()
+                };
+                BoardInstance::new("test", vec2(0.0, 0.0), &config)
+            })
+        };
+
+        // Different seeds, one shared sequence; each board draws at its own pace
+        let [mut a, mut b] = boards([1, 2]);
+        let shared = Rc::new(RefCell::new(SharedSequence::new(
+            7,
+            Randomizer::Uniform,
+            [1.0; 7],
+        )));
+        a.share_sequence(shared.clone());
+        b.share_sequence(shared);
+        let first: Vec<_> = (0..20).map(|_| a.take_next_piece()).collect();
+        let second: Vec<_> = (0..20).map(|_| b.take_next_piece()).collect();
+        assert_eq!(first, second);
+
+        // Unshared, the same seeds diverge
+        let [mut a, mut b] = boards([1, 2]);
+        let first: Vec<_> = (0..20).map(|_| a.take_next_piece()).collect();
+        let second: Vec<_> = (0..20).map(|_| b.take_next_piece()).collect();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_auto_hold_assist_survives_topout() {
+        let topout = |assist: bool, seq: &[PieceType]| {
+            let config = BoardConfig {
+                auto_hold_assist: assist,
+                hold_capacity: 1,
+                ..Default::default()
+            };
+            let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+
+            // A full-height tower left of center blocks the I's spawn but not the O's
+            let tower = board.board.midpoint_x() as usize - 1;
+            let width = board.board.width as usize;
+            let row = format!("{}#{}\n", ".".repeat(tower), ".".repeat(width - tower - 1));
+            board.set_state_for_test(GameState::Ready, &row.repeat(board.board.height as usize));
+            board.force_sequence(seq);
+            board.update(0.0, &[]);
+            board
+        };
+
+        assert_eq!(
+            topout(false, &[PieceType::I, PieceType::O]).game_state,
+            GameState::GameOver
+        );
+
+        let mut board = topout(true, &[PieceType::I, PieceType::O]);
+        assert_eq!(board.game_state, GameState::Falling);
+        assert_eq!(board.active_piece.as_ref().unwrap().typ, PieceType::O);
+        assert_eq!(board.held.front(), Some(&PieceType::I));
+        assert!(board.drain_sound_cues().contains(&SoundCue::AutoHold));
+
+        // Nothing to gain from holding: the game still ends
+        assert_eq!(
+            topout(true, &[PieceType::I, PieceType::I]).game_state,
+            GameState::GameOver
+        );
+    }
+
+    #[test]
+    fn test_origin_crosshair_follows_location() {
+        let config = BoardConfig {
+            cell_size: CellSize::Pixels(10.0),
+            ..Default::default()
+        };
+        let board = BoardInstance::new("test", vec2(100.0, -50.0), &config);
+
+        let [x_axis, y_axis] = board.origin_crosshair();
+        assert_eq!(x_axis, (vec2(95.0, -50.0), vec2(120.0, -50.0)));
+        assert_eq!(y_axis, (vec2(100.0, -55.0), vec2(100.0, -30.0)));
+    }
+
+    #[test]
+    fn test_manual_clear_waits_for_confirm() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            manual_clear: true,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        let i_piece = PieceInstance::new(PieceType::I, board.color, BoardPosition { x: 0, y: 0 });
+        board
+            .board
+            .commit_piece_at(&i_piece, BoardPosition { x: 0, y: 0 });
+        board
+            .board
+            .commit_piece_at(&i_piece, BoardPosition { x: 4, y: 0 });
+        let o_piece = PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 0, y: 0 });
+        board
+            .board
+            .commit_piece_at(&o_piece, BoardPosition { x: 8, y: 0 });
+        assert_eq!(board.board.full_rows(), vec![0]);
+
+        // Locking and spawning carry on over the filled row
+        board.update(0.0, &[]);
+        board.update(0.0, &[PlayerInput::HardDrop]);
+        run_until_ready(&mut board);
+        board.update(0.0, &[]);
+        assert_eq!(board.game_state, GameState::Falling);
+        assert_eq!(board.board.full_rows(), vec![0]);
+        let falling = board.active_piece.as_ref().unwrap().typ;
+
+        // Confirm clears it and the piece in play comes back after the clear
+        board.update(0.0, &[PlayerInput::ConfirmClear]);
+        assert_eq!(board.game_state, GameState::Clearing);
+        run_until_ready(&mut board);
+        assert!(board.board.full_rows().is_empty());
+        board.update(0.0, &[]);
+        assert_eq!(board.active_piece.as_ref().unwrap().typ, falling);
+    }
+
+    #[test]
+    fn test_clearing_to_ready_clears_rows() {
+        let mut board = test_board(0.0);
+        board.set_state_for_test(
+            GameState::Clearing,
+            "
+            #.........
+            ##########
+            #.#.......
+            ##########
+            ",
+        );
+        assert_eq!(board.rows_to_clear, Some(vec![0, 2]));
+
+        board.update(CLEAR_DURATION, &[]);
+        assert_eq!(board.game_state, GameState::Ready);
+        assert!(board.board.full_rows().is_empty());
+        assert_eq!(board.board.row_score(0), Some(2));
+        assert_eq!(board.board.row_score(1), Some(1));
+        assert_eq!(board.stack_height(), 2);
+    }
+
+    #[test]
+    fn test_chain_meter_builds_and_decays() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            chain_per_line: 1.5,
+            chain_max: 10.0,
+            chain_decay: 1.0,
+            chain_thresholds: vec![2.0],
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.set_state_for_test(
+            GameState::Clearing,
+            "
+            ##########
+            ##########
+            ",
+        );
+        board.update(CLEAR_DURATION, &[]);
+        assert_eq!(board.chain_meter(), 3.0);
+        assert!(board
+            .drain_sound_cues()
+            .contains(&SoundCue::ChainMeterChanged(1)));
+
+        // No more clears: drains a point a second, down to zero
+        board.update(1.0, &[]);
+        assert_eq!(board.chain_meter(), 2.0);
+        board.update(1.0, &[]);
+        assert!(board
+            .drain_sound_cues()
+            .contains(&SoundCue::ChainMeterChanged(0)));
+        board.update(1.0, &[]);
+        assert_eq!(board.chain_meter(), 0.0);
+    }
+
+    #[test]
+    fn test_level_up_starts_hue_fade() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            level_lines: 2,
+            level_hue_step: 90.0,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.set_state_for_test(
+            GameState::Clearing,
+            "
+            ##########
+            ##########
+            ",
+        );
+        board.update(CLEAR_DURATION, &[]);
+
+        assert_eq!(board.level(), 1);
+        assert!(board.drain_sound_cues().contains(&SoundCue::LevelUp(1)));
+        assert!(board.level_fade.is_active());
+        let target: Rgba = hsva(130.0 / 360.0, 1.0, 0.75, 1.0).into();
+        assert_eq!(
+            board.level_fade.target(),
+            rgb(target.red, target.green, target.blue)
+        );
+
+        // Reset heads back to the base hue
+        board.reset();
+        assert_eq!(board.level(), 0);
+        let base = board.level_color(0);
+        assert_eq!(
+            board.level_fade.target(),
+            rgb(base.red, base.green, base.blue)
+        );
+    }
+
+    #[test]
+    fn test_grounded_soft_drop_lock_reset_toggle() {
+        let lock_delay = BoardConfig::default().lock_delay;
+        let grounded = |resets: bool| {
+            let mut board = test_board(0.0);
+            board.set_soft_drop_resets_lock(resets);
+            board.update(0.0, &[]);
+            board.update(0.0, &[PlayerInput::HardDrop]);
+            assert!(matches!(
+                board.game_state,
+                GameState::Locking { now: false, .. }
+            ));
+
+            // Two soft drops, each most of a lock delay apart
+            board.update(lock_delay * 0.6, &[PlayerInput::SoftDrop]);
+            board.update(lock_delay * 0.6, &[PlayerInput::SoftDrop]);
+            board
+        };
+
+        assert_eq!(grounded(false).game_state, GameState::Ready);
+        assert!(matches!(
+            grounded(true).game_state,
+            GameState::Locking { .. }
+        ));
+    }
+
+    #[test]
+    fn test_gravity_multiplier_relative_rates() {
+        let mut normal = test_board(0.0);
+        let mut handicapped = test_board(0.0);
+        handicapped.set_gravity_multiplier(2.0);
+        // Same piece on both, so they spawn at the same height
+        normal.force_sequence(&[PieceType::O]);
+        handicapped.force_sequence(&[PieceType::O]);
+        normal.update(0.0, &[]);
+        handicapped.update(0.0, &[]);
+        let start = lowest_cell_y(&normal);
+        assert_eq!(lowest_cell_y(&handicapped), start);
+
+        // Four intervals in quarter steps
+        let step = normal.gravity_interval() / 4.0;
+        for _ in 0..16 {
+            normal.update(step, &[]);
+            handicapped.update(step, &[]);
+        }
+        assert_eq!(start - lowest_cell_y(&normal), 4);
+        assert_eq!(start - lowest_cell_y(&handicapped), 8);
+    }
+
+    #[test]
+    fn test_set_gravity_interval_mid_fall() {
+        let mut board = test_board(0.0);
+        board.update(0.0, &[]);
+        let start_y = lowest_cell_y(&board);
+        let interval = board.gravity_interval();
+
+        // Halfway to the next row, then slow down to twice the interval
+        board.update(interval * 0.5, &[]);
+        board.set_gravity_interval(interval * 2.0);
+        assert!((board.gravity_progress() - 0.5).abs() < 1e-6);
+        assert_eq!(board.gravity_interval(), interval * 2.0);
+
+        // The other half now takes a full old interval
+        board.update(interval * 0.9, &[]);
+        assert_eq!(lowest_cell_y(&board), start_y);
+        board.update(interval * 0.1, &[]);
+        assert_eq!(lowest_cell_y(&board), start_y - 1);
+
+        board.set_gravity_interval(0.0);
+        assert_eq!(board.gravity_interval(), MIN_GRAVITY_INTERVAL);
+    }
+
+    #[test]
+    fn test_rotate_during_entry_delay_spawns_rotated() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            entry_delay: 0.2,
+            irs: true,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.force_sequence(&[PieceType::T, PieceType::T]);
+
+        board.update(0.1, &[PlayerInput::Rotate]);
+        assert!(board.active_piece.is_none());
+        board.update(0.1, &[]);
+        assert_eq!(board.active_piece.as_ref().unwrap().rot_idx, 1);
+
+        // Only the piece it was buffered for
+        hard_drop_and_lock(&mut board);
+        board.update(0.2, &[]);
+        assert_eq!(board.active_piece.as_ref().unwrap().rot_idx, 0);
+    }
+
+    #[test]
+    fn test_stack_warning_hysteresis() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            stack_warning: 8,
+            stack_warning_band: 2,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.game_state = GameState::Paused;
+        let i_piece = PieceInstance::new(PieceType::I, board.color, BoardPosition { x: 0, y: 0 });
+        let stack_to = |board: &mut BoardInstance, height: isize| {
+            board.board = Board::new(10, 20);
+            for y in 0..height {
+                board
+                    .board
+                    .commit_piece_at(&i_piece, BoardPosition { x: 0, y });
+            }
+            board.update(0.0, &[]);
+            board.drain_sound_cues()
+        };
+
+        // Crossing up fires once
+        assert_eq!(stack_to(&mut board, 8), vec![SoundCue::StackHigh]);
+        assert!(stack_to(&mut board, 9).is_empty());
+
+        // Inside the band: still high, nothing fires
+        assert!(stack_to(&mut board, 6).is_empty());
+        assert!(stack_to(&mut board, 8).is_empty());
+
+        // Below the band clears, then it can fire again
+        assert_eq!(stack_to(&mut board, 5), vec![SoundCue::StackCleared]);
+        assert_eq!(stack_to(&mut board, 8), vec![SoundCue::StackHigh]);
+    }
+
+    #[test]
+    fn test_near_miss_slow_motion_eases_back() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            stack_warning: 8,
+            stack_warning_band: 2,
+            near_miss_window: 2.0,
+            slow_mo_duration: 1.0,
+            slow_mo_factor: 0.25,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.game_state = GameState::Frozen;
+        let i_piece = PieceInstance::new(PieceType::I, board.color, BoardPosition { x: 0, y: 0 });
+        let stack_to = |board: &mut BoardInstance, height: isize, dt: f32| {
+            board.board = Board::new(10, 20);
+            for y in 0..height {
+                board
+                    .board
+                    .commit_piece_at(&i_piece, BoardPosition { x: 0, y });
+            }
+            board.update(dt, &[]);
+        };
+
+        // Cleared long after going high: no slow motion
+        stack_to(&mut board, 8, 0.0);
+        stack_to(&mut board, 8, 3.0);
+        stack_to(&mut board, 2, 0.0);
+        assert_eq!(board.time_scale(), 1.0);
+
+        // Cleared just in time: slows down, then eases back to full speed
+        stack_to(&mut board, 8, 0.0);
+        stack_to(&mut board, 2, 0.5);
+        assert!(board.slow_mo.is_some());
+        assert_eq!(board.time_scale(), 0.25);
+        board.update(0.5, &[]);
+        let halfway = board.time_scale();
+        assert!(halfway > 0.25 && halfway < 1.0);
+        board.update(0.6, &[]);
+        assert!(board.slow_mo.is_none());
+        assert_eq!(board.time_scale(), 1.0);
+    }
+
+    #[test]
+    fn test_set_paused_restores_state() {
+        let mut board = test_board(0.0);
+        board.update(0.0, &[]);
+        assert_eq!(board.game_state, GameState::Falling);
+
+        // Focus lost
+        assert!(board.set_paused(true));
+        assert_eq!(board.game_state, GameState::Paused);
+        assert!(!board.set_paused(true));
+
+        // Focus back
+        assert!(board.set_paused(false));
+        assert_eq!(board.game_state, GameState::Falling);
+        assert!(!board.set_paused(false));
+    }
+
+    #[test]
+    fn test_lines_cleared_on_drop() {
+        let mut board = board_with_gap();
+        let o_piece = PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 4, y: 10 });
+        board.active_piece = Some(o_piece);
+        board.refresh_landing();
+
+        assert_eq!(board.landing_position().map(|pos| pos.y), Some(0));
+        assert_eq!(board.lines_cleared_on_drop(), (vec![0], vec![]));
+
+        // Moved off the gap, it would land on the stack and clear nothing
+        board.active_piece.as_mut().unwrap().position.x = 0;
+        board.refresh_landing();
+        assert_eq!(board.landing_position().map(|pos| pos.y), Some(1));
+        assert_eq!(board.lines_cleared_on_drop(), (vec![], vec![]));
+
+        // Clearing columns too, an upright I topping off the last column completes it
+        let mut board = test_board(0.0);
+        board.board.set_clear_axis(ClearAxis::Both);
+        board.set_state_for_test(GameState::Falling, &".........#\n".repeat(16));
+        let mut i_piece =
+            PieceInstance::new(PieceType::I, board.color, BoardPosition { x: 7, y: 16 });
+        i_piece.rot_idx = 1;
+        board.active_piece = Some(i_piece);
+        board.refresh_landing();
+        assert_eq!(board.lines_cleared_on_drop(), (vec![], vec![9]));
+    }
+
+    #[test]
+    fn test_clearing_board_holds_others_gravity() {
+        let mut clearing = test_board(0.0);
+        clearing.game_state = GameState::Clearing;
+        let mut falling = test_board(0.0);
+        falling.update(0.0, &[]);
+        let start_y = lowest_cell_y(&falling);
+
+        sync_clear_pause([&mut clearing, &mut falling]);
+        falling.update(BoardConfig::default().gravity_interval, &[]);
+        assert_eq!(lowest_cell_y(&falling), start_y);
+
+        // Done clearing: gravity picks up again
+        clearing.game_state = GameState::Ready;
+        sync_clear_pause([&mut clearing, &mut falling]);
+        falling.update(BoardConfig::default().gravity_interval, &[]);
+        assert_eq!(lowest_cell_y(&falling), start_y - 1);
+    }
+
+    #[test]
+    fn test_board_image_size() {
+        let config = BoardConfig {
+            width: 6,
+            height: 12,
+            cell_size: CellSize::Pixels(10.0),
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        let o_piece = PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 0, y: 0 });
+        board
+            .board
+            .commit_piece_at(&o_piece, BoardPosition { x: 0, y: 0 });
+
+        let image = board.board_image(false);
+        assert_eq!(image.dimensions(), (60, 120));
+
+        // Bottom-left cell is filled, top-left is background
+        assert_eq!(*image.get_pixel(5, 115), to_pixel(board.color));
+        assert_eq!(*image.get_pixel(5, 5), to_pixel(rgba(0.0, 0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn test_late_move_defers_lock() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            lock_grace: 0.1,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.update(0.0, &[]);
+        board.update(0.0, &[PlayerInput::HardDrop]);
+
+        // Slide just before the 0.25s lock delay runs out
+        board.update(0.2, &[]);
+        board.update(0.05, &[PlayerInput::L]);
+        assert!(matches!(board.game_state, GameState::Locking { .. }));
+
+        // Commits once the grace is over, not before
+        board.update(0.05, &[]);
+        assert!(matches!(board.game_state, GameState::Locking { .. }));
+        board.update(0.05, &[]);
+        assert_eq!(board.game_state, GameState::Ready);
+    }
+
+    #[test]
+    fn test_frozen_input_restarts() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            frozen_input: FrozenInput::Restart,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.game_state = GameState::Frozen;
+
+        // No input: stays frozen
+        board.update(1.0, &[]);
+        assert_eq!(board.game_state, GameState::Frozen);
+
+        board.update(0.0, &[PlayerInput::Rotate]);
+        assert_eq!(board.game_state, GameState::Ready);
+
+        // Ignore (the default) leaves it frozen
+        let mut board = test_board(0.0);
+        board.game_state = GameState::Frozen;
+        board.update(0.0, &[PlayerInput::Rotate]);
+        assert_eq!(board.game_state, GameState::Frozen);
+    }
+
+    #[test]
+    fn test_game_over_finale_clears_stack() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            game_over_finale: 2.0,
+            idle_reset: 1.0,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        let i_piece = PieceInstance::new(PieceType::I, board.color, BoardPosition { x: 0, y: 0 });
+        for y in 0..20 {
+            board
+                .board
+                .commit_piece_at(&i_piece, BoardPosition { x: y % 6, y });
+        }
+
+        board.game_state = GameState::GameOver;
+        board.update(GAME_OVER_DURATION, &[]);
+        assert_eq!(board.game_state, GameState::Finale);
+
+        // Halfway through, the bottom half is gone and the top half remains
+        board.update(1.0, &[]);
+        let cells = filled_cells(&board);
+        assert!(cells[..10 * 10].iter().all(|&filled| !filled));
+        assert!(cells[10 * 10..].iter().any(|&filled| filled));
+
+        // Within the configured duration the board is empty and frozen
+        board.update(1.0, &[]);
+        assert_eq!(board.game_state, GameState::Frozen);
+        assert!(filled_cells(&board).iter().all(|&filled| !filled));
+
+        // Then the idle reset starts the attract loop over
+        board.update(1.0, &[]);
+        assert_eq!(board.game_state, GameState::Ready);
+    }
+
+    #[test]
+    fn test_cell_mesh_matches_cells() {
+        let config = BoardConfig {
+            width: 8,
+            height: 4,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(100.0, -50.0), &config);
+        let o_piece = PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 0, y: 0 });
+        for y in (0..4).step_by(2) {
+            for x in (0..8).step_by(2) {
+                board
+                    .board
+                    .commit_piece_at(&o_piece, BoardPosition { x, y });
+            }
+        }
 
-        // Calculate clear area
-        let top_bound = BoardPosition { x: 0, y: top_row }.to_screen(self).y;
-        let bottom_bound = BoardPosition {
-            x: 0,
-            y: bottom_row,
+        let mesh = board.cell_mesh(false, f32::MIN, board.color);
+        assert_eq!(mesh.vertex_count(), 8 * 4 * 4);
+
+        // Same centers, in the same row-major order, as the per-rect path
+        let expected: Vec<Vec2> = (0..4)
+            .flat_map(|y| (0..8).map(move |x| BoardPosition { x, y }))
+            .map(|pos| pos.to_screen(&board))
+            .collect();
+        let centers: Vec<Vec2> = mesh.cell_centers().collect();
+        assert_eq!(centers.len(), expected.len());
+        for (center, expected) in centers.iter().zip(&expected) {
+            assert!(center.distance(*expected) < 1e-3);
         }
-        .to_screen(self)
-        .y;
+    }
 
-        let board_left_edge = self.location.x - (self.board.width as f32 * self.cell_size / 2.0);
-        let board_width = self.board.width as f32 * self.cell_size;
+    #[test]
+    fn test_hint_lays_i_piece_flat() {
+        let mut board = test_board(0.0);
+        board.force_sequence(&[PieceType::I]);
+        board.update(0.0, &[]);
 
-        // Calculate separation based on progress. Minimum is half a cell height.
-        let center_y = bottom_bound + (top_bound - bottom_bound) / 2.0;
-        let half_max_distance = (top_bound - bottom_bound) / 2.0;
-        let half_separation = if top_row == bottom_row {
-            self.cell_size / 2.0 * progress
-        } else {
-            half_max_distance * progress
+        let hint = board.best_placement_hint().unwrap();
+        let cells = hint.cells(PieceType::I);
+
+        // Flat on the floor, so nothing is left uncovered beneath it
+        assert!(cells.iter().all(|&(_, y)| y == 0));
+        assert!(cells.iter().all(|&(x, _)| x >= 0 && x < board.board.width));
+    }
+
+    #[test]
+    fn test_hold_queue_cycles() {
+        use PieceType::*;
+
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            hold_capacity: 2,
+            ..Default::default()
         };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.force_sequence(&[T, I, O, S, Z]);
+        board.update(0.0, &[]);
+        let active = |board: &BoardInstance| board.active_piece.as_ref().unwrap().typ;
+        let held = |board: &BoardInstance| board.held_pieces().copied().collect::<Vec<_>>();
 
-        // Line positions
-        let top_y = center_y + half_separation;
-        let bottom_y = center_y - half_separation;
+        // First hold brings in the next piece; a second hold before locking does nothing
+        board.update(0.0, &[PlayerInput::Hold, PlayerInput::Hold]);
+        assert_eq!(active(&board), I);
+        assert_eq!(held(&board), vec![T]);
 
-        // Clear the area between the lines as they separate
-        if progress > 0.01 {
-            // Start clearing after a little bit of separation
-            let clear_height = (top_y - bottom_y).abs();
-            draw.rect()
-                .x_y(self.location.x, center_y)
-                .w_h(board_width, clear_height)
-                .color(rgba(1.0, 0.91, 0.65, alpha));
+        // After a lock, hold again: the queue still has room
+        hard_drop_and_lock(&mut board);
+        board.update(0.0, &[]);
+        assert_eq!(active(&board), O);
+        board.update(0.0, &[PlayerInput::Hold]);
+        assert_eq!(active(&board), S);
+        assert_eq!(held(&board), vec![T, O]);
+
+        // Queue full: holding swaps in the oldest held piece
+        hard_drop_and_lock(&mut board);
+        board.update(0.0, &[]);
+        assert_eq!(active(&board), Z);
+        board.update(0.0, &[PlayerInput::Hold]);
+        assert_eq!(active(&board), T);
+        assert_eq!(held(&board), vec![O, Z]);
+    }
+
+    #[test]
+    fn test_clearing_input_buffers_into_next_piece() {
+        let spawn_x = |buffer: bool| {
+            let mut board = test_board(0.0);
+            if buffer {
+                board.clearing_input = ClearingInput::Buffer;
+            }
+            board.force_sequence(&[PieceType::O]);
+            board.set_state_for_test(GameState::Clearing, "##########");
+            board.update(0.0, &[PlayerInput::L, PlayerInput::L]);
+            run_until_ready(&mut board);
+            board.update(0.0, &[]);
+            board.active_piece.as_ref().unwrap().position.x
+        };
+
+        let spawn = test_board(0.0).spawn_position(PieceType::O).x;
+        assert_eq!(spawn_x(false), spawn);
+        assert_eq!(spawn_x(true), spawn - 2);
+    }
+
+    #[test]
+    fn test_set_palette_now_and_faded() {
+        let mut board = test_board(0.0);
+        board.update(0.0, &[]);
+        let start = board.palette();
+
+        let red = Palette {
+            cell: rgba(1.0, 0.0, 0.0, 1.0),
+            boundary: rgba(0.8, 0.0, 0.0, 1.0),
+            panel: None,
+        };
+        board.set_palette(red, 0.0);
+        assert_eq!(board.palette(), red);
+        assert_eq!(board.active_piece.as_ref().unwrap().color, red.cell);
+
+        // Halfway through a fade back the colors are halfway there
+        board.set_palette(start, 1.0);
+        board.update(0.5, &[]);
+        assert_eq!(board.palette(), red.blend(&start, 0.5));
+        board.update(0.5, &[]);
+        assert_eq!(board.palette(), start);
+        assert!(board.palette_fade.is_none());
+    }
+
+    #[test]
+    fn test_nes_spawn_columns_and_orientations() {
+        use PieceType::*;
+
+        let mut board = test_board(0.0);
+        board.spawn_rule = SpawnRule::Nes;
+
+        // Occupied columns and rotation index per piece on a 10-wide board
+        let expected = [
+            (I, (3, 6), 0),
+            (J, (4, 6), 2),
+            (L, (4, 6), 2),
+            (S, (4, 6), 0),
+            (Z, (4, 6), 0),
+            (T, (4, 6), 2),
+            (O, (4, 5), 0),
+        ];
+        for (typ, cols, rot_idx) in expected {
+            let piece = board.spawn_piece(typ);
+            assert_eq!(piece.rot_idx, rot_idx, "{:?}", typ);
+            let xs = piece.cells().iter().map(|&(dx, _)| piece.position.x + dx);
+            assert_eq!(
+                (xs.clone().min().unwrap(), xs.max().unwrap()),
+                cols,
+                "{:?}",
+                typ
+            );
+
+            // Top cell on the top row
+            let top = piece
+                .cells()
+                .iter()
+                .map(|&(_, dy)| piece.position.y + dy)
+                .max();
+            assert_eq!(top, Some(board.board.height - 1), "{:?}", typ);
         }
+    }
 
-        // Draw top and bottom lines
-        for y_pos in [top_y, bottom_y] {
-            // Main line
-            draw.line()
-                .points(
-                    vec2(board_left_edge, y_pos),
-                    vec2(board_left_edge + board_width, y_pos),
-                )
-                .color(rgba(1.0, 0.91, 0.65, alpha))
-                .stroke_weight(1.0);
+    #[test]
+    fn test_clears_cancel_pending_garbage() {
+        let mut board = test_board(0.0);
+        board.garbage_delay = 0.0;
+        board.queue_garbage(3);
+        assert_eq!(board.pending_garbage(), 3);
+
+        // An O piece finishing two rows cancels two of the three
+        board.set_state_for_test(
+            GameState::Falling,
+            "
+            ####..####
+            ####..####
+            ",
+        );
+        let o_piece = PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 4, y: 5 });
+        board.active_piece = Some(o_piece);
+        board.update(0.0, &[PlayerInput::HardDrop]);
+        board.update(0.0, &[]);
+        assert_eq!(board.game_state, GameState::Clearing);
+        assert_eq!(board.pending_garbage(), 1);
+        run_until_ready(&mut board);
+        assert_eq!(board.stack_height(), 0);
+
+        // The next lock without a clear raises the rest
+        board.update(0.0, &[]);
+        board.update(0.0, &[PlayerInput::HardDrop]);
+        board.update(BoardConfig::default().lock_delay, &[]);
+        assert_eq!(board.pending_garbage(), 0);
+        assert_eq!(board.board.row_score(0), Some(board.board.width - 1));
+    }
+
+    #[test]
+    fn test_hold_box_locked_until_next_spawn() {
+        let mut board = test_board(0.0);
+        board.hold_capacity = 1;
+        board.update(0.0, &[]);
+        assert!(!board.hold_locked());
+
+        board.update(0.0, &[PlayerInput::Hold]);
+        assert!(board.hold_locked());
+
+        // Still locked through the lock and the wait for the next piece
+        board.update(0.0, &[PlayerInput::HardDrop]);
+        board.update(BoardConfig::default().lock_delay, &[]);
+        assert_eq!(board.game_state, GameState::Ready);
+        assert!(board.hold_locked());
+
+        board.update(0.0, &[]);
+        assert!(board.active_piece.is_some());
+        assert!(!board.hold_locked());
+    }
+
+    #[test]
+    fn test_spawn_telegraph_follows_entry_delay() {
+        let config = BoardConfig {
+            entry_delay: 0.5,
+            spawn_telegraph: true,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+
+        // Partway through the entry delay: no piece yet, telegraph shows the next piece
+        board.update(0.25, &[]);
+        assert!(board.active_piece.is_none());
+        let (telegraph, progress) = board.spawn_telegraph().unwrap();
+        assert_eq!(Some(telegraph.typ), board.next_piece());
+        assert!((progress - 0.5).abs() < 1e-6);
+
+        // Delay elapses: the telegraphed piece spawns where it was shown
+        board.update(0.25, &[]);
+        let spawned = board.active_piece.as_ref().unwrap();
+        assert_eq!(spawned.typ, telegraph.typ);
+        assert_eq!(spawned.position.x, telegraph.position.x);
+        assert_eq!(spawned.position.y, telegraph.position.y);
+        assert_eq!(board.game_state, GameState::Falling);
+    }
+
+    #[test]
+    fn test_inputs_in_one_frame_apply_in_order() {
+        let mut board = test_board(0.0);
+        board.update(0.0, &[]);
+        let start_x = board.active_piece.as_ref().unwrap().position.x;
+
+        // Both moves land in the same frame
+        board.update(0.0, &[PlayerInput::L, PlayerInput::L]);
+        assert_eq!(board.active_piece.as_ref().unwrap().position.x, start_x - 2);
+
+        // Pause first: the move after it is ignored
+        board.update(0.0, &[PlayerInput::Pause, PlayerInput::R]);
+        assert_eq!(board.game_state, GameState::Paused);
+        assert_eq!(board.active_piece.as_ref().unwrap().position.x, start_x - 2);
+
+        // Unpause first: the move after it applies
+        board.update(0.0, &[PlayerInput::Pause, PlayerInput::R]);
+        assert_eq!(board.game_state, GameState::Falling);
+        assert_eq!(board.active_piece.as_ref().unwrap().position.x, start_x - 1);
+    }
+
+    #[test]
+    fn test_telemetry_format() {
+        let mut board = test_board(0.0);
+        let mut piece =
+            PieceInstance::new(PieceType::T, board.color, BoardPosition { x: 3, y: 10 });
+        piece.rotate(&RotationDirection::Cw);
+        board.active_piece = Some(piece);
+        board.game_state = GameState::Falling;
+        board.timers.gravity.tick(0.25);
+
+        assert_eq!(
+            board.telemetry(),
+            "Falling | T rot 1 | gravity 50% | lock 0%"
+        );
+
+        board.active_piece = None;
+        board.game_state = GameState::Ready;
+        assert_eq!(board.telemetry(), "Ready | - | gravity 50% | lock 0%");
+    }
+
+    #[test]
+    fn test_boundary_blends_toward_next_piece() {
+        let config = BoardConfig {
+            color_from_next: true,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.next_queue.push_back(PieceType::S);
+
+        let start = board.boundary_color;
+        let target = board.boundary_target();
+        assert_eq!(target, PieceType::S.color());
+
+        // A quarter second at 2.0/s covers half the distance
+        board.update_boundary_color(0.25);
+        let halfway = (start.green + target.green) / 2.0;
+        assert!((board.boundary_color.green - halfway).abs() < 1e-5);
+
+        // Long enough frames settle on the target
+        board.update_boundary_color(1.0);
+        assert!((board.boundary_color.red - target.red).abs() < 1e-5);
+        assert!((board.boundary_color.green - target.green).abs() < 1e-5);
+        assert!((board.boundary_color.blue - target.blue).abs() < 1e-5);
+
+        // Disabled: the target is the board's own boundary color
+        board.color_from_next = false;
+        assert_eq!(board.boundary_target(), start);
+    }
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let config = BoardConfig {
+            seed: Some(2025),
+            ..Default::default()
+        };
+        let mut a = BoardInstance::new("a", vec2(0.0, 0.0), &config);
+        let mut b = BoardInstance::new("b", vec2(0.0, 0.0), &config);
+
+        for _ in 0..20 {
+            assert_eq!(a.get_random_piece_type(), b.get_random_piece_type());
         }
     }
 
-    fn draw_game_over(&self, draw: &Draw, line_pos: f32) {
-        let board_left_edge = self.location.x - self.screen_width / 2.0;
-        let board_width = self.screen_width;
+    #[test]
+    fn test_garbage_leaves_sequence_alone() {
+        let config = BoardConfig {
+            seed: Some(2025),
+            garbage_delay: 0.0,
+            ..Default::default()
+        };
+        let mut plain = BoardInstance::new("a", vec2(0.0, 0.0), &config);
+        let mut hit = BoardInstance::new("b", vec2(0.0, 0.0), &config);
 
-        // Main line
-        draw.line()
-            .points(
-                vec2(board_left_edge, line_pos),
-                vec2(board_left_edge + board_width, line_pos),
-            )
-            //.color(rgba(1.0, 0.91, 0.65, 0.55))
-            .color(rgba(1.0, 0.8, 0.8, 0.65))
-            .stroke_weight(3.0);
+        hit.queue_garbage(2);
+        assert!(hit.raise_due_garbage());
+        assert_eq!(hit.board.row_score(0), Some(hit.board.width - 1));
+        for _ in 0..10 {
+            assert_eq!(plain.take_next_piece(), hit.take_next_piece());
+        }
     }
 
-    // Draw the outer boundary of the grid
-    fn draw_boundary(&self, draw: &Draw, color: Rgba) {
-        draw.rect()
-            .x_y(self.location.x, self.location.y)
-            .w_h(self.screen_width, self.screen_height)
-            .stroke_weight(1.0)
-            .stroke_color(color)
-            .color(rgba(0.0, 0.0, 0.0, 0.0));
+    #[test]
+    fn test_reseed_on_reset() {
+        let sequence = |board: &mut BoardInstance| -> Vec<PieceType> {
+            (0..10).map(|_| board.take_next_piece()).collect()
+        };
+        let seeded = |reseed_on_reset, seed| {
+            let config = BoardConfig {
+                seed: Some(seed),
+                reseed_on_reset,
+                ..Default::default()
+            };
+            BoardInstance::new("test", vec2(0.0, 0.0), &config)
+        };
+
+        let mut same = seeded(ReseedOnReset::Same, 2025);
+        let first = sequence(&mut same);
+        same.reset();
+        assert_eq!(sequence(&mut same), first);
+
+        // Increment plays what a board seeded one higher would
+        let mut increment = seeded(ReseedOnReset::Increment, 2025);
+        sequence(&mut increment);
+        increment.reset();
+        assert_eq!(increment.seed(), 2026);
+        let next = sequence(&mut increment);
+        assert_ne!(next, first);
+        assert_eq!(next, sequence(&mut seeded(ReseedOnReset::Same, 2026)));
     }
 
-    /************************ Utility methods *******************************/
+    #[test]
+    fn test_diff_after_placing_piece() {
+        let mut board = test_board(0.0);
+        let empty = board.snapshot();
+        assert!(board.diff(&empty).is_empty());
 
-    pub fn board(&self) -> &Board {
-        &self.board
+        let o_piece = PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 0, y: 0 });
+        board
+            .board
+            .commit_piece_at(&o_piece, BoardPosition { x: 3, y: 0 });
+        let placed = o_piece
+            .cells()
+            .iter()
+            .map(|&(dx, dy)| (BoardPosition { x: 3 + dx, y: dy }, true));
+        let mut expected: Vec<_> = placed.collect();
+        expected.sort_by_key(|(pos, _)| (pos.y, pos.x));
+        assert_eq!(board.diff(&empty), expected);
+
+        // The spawned piece shows up as its own cells only
+        let locked = board.snapshot();
+        board.update(0.0, &[]);
+        let diff = board.diff(&locked);
+        assert_eq!(diff.len(), 4);
+        assert!(diff.iter().all(|&(_, visible)| visible));
     }
 
-    pub fn board_mut(&mut self) -> &mut Board {
-        &mut self.board
+    #[test]
+    fn test_rotation_into_filled_row_locks() {
+        let rotating_into_gap = |row_fill_lock| {
+            let mut board = test_board(0.0);
+            board.row_fill_lock = row_fill_lock;
+            board.set_state_for_test(
+                GameState::Falling,
+                "
+                ###....###
+                #####.####
+                ",
+            );
+            // Upright I in column 5; lying flat it fills the gap in row 1
+            let mut i_piece =
+                PieceInstance::new(PieceType::I, board.color, BoardPosition { x: 3, y: 0 });
+            i_piece.rot_idx = 1;
+            board.active_piece = Some(i_piece);
+            board.update(0.0, &[PlayerInput::Rotate]);
+            board
+        };
+
+        let immediate = rotating_into_gap(RowFillLock::Immediate);
+        assert_eq!(immediate.active_piece.as_ref().unwrap().rot_idx, 2);
+        assert_eq!(
+            immediate.game_state,
+            GameState::Locking {
+                now: true,
+                hard_drop: false
+            }
+        );
+
+        let delayed = rotating_into_gap(RowFillLock::Delay);
+        assert_eq!(delayed.active_piece.as_ref().unwrap().rot_idx, 2);
+        assert_eq!(delayed.game_state, GameState::Falling);
     }
-}
 
-/************************ Stdout functions *******************************/
+    #[test]
+    fn test_row_fill_lock_delay_keeps_adjustment_window() {
+        let falling_into_gap = |row_fill_lock| {
+            let mut board = board_with_gap();
+            board.row_fill_lock = row_fill_lock;
+            let o_piece =
+                PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 4, y: 1 });
+            board.active_piece = Some(o_piece);
+            board.game_state = GameState::Falling;
+            board.update(BoardConfig::default().gravity_interval, &[]);
+            board
+        };
 
-fn spawn_new_piece_msg(piece: &PieceInstance) {
-    println!("\n-- Spawned new piece --");
-    println!(
-        "PieceType: {:?}\nPosition:{:?}\n",
-        piece.typ, piece.position
-    )
-}
+        let immediate = falling_into_gap(RowFillLock::Immediate);
+        assert_eq!(
+            immediate.game_state,
+            GameState::Locking {
+                now: true,
+                hard_drop: false
+            }
+        );
 
-fn print_col_score(col_score: &Vec<isize>) {
-    println!("\nCol score:");
-    println!("{:?}", col_score);
-}
+        // Delay: the piece sits in the gap uncommitted until the lock delay runs out
+        let mut delayed = falling_into_gap(RowFillLock::Delay);
+        assert_eq!(delayed.game_state, GameState::Falling);
+        assert_eq!(lowest_cell_y(&delayed), 0);
+        assert!(delayed.board.full_rows().is_empty());
 
-struct GameTimers {
-    gravity: Timer,
-    lock: Timer,
-    clear_animation: Timer,
-    slide_animation: Timer,
-    game_over_animation: Timer,
-}
+        delayed.update(BoardConfig::default().gravity_interval, &[]);
+        assert!(matches!(
+            delayed.game_state,
+            GameState::Locking { now: false, .. }
+        ));
+        delayed.update(BoardConfig::default().lock_delay, &[]);
+        assert_eq!(delayed.game_state, GameState::Clearing);
+        run_until_ready(&mut delayed);
+        assert_eq!(delayed.board.row_score(0), Some(2));
+    }
 
-impl GameTimers {
-    pub fn new(
-        gravity_interval: f32,
-        lock_delay: f32,
-        clear_duration: f32,
-        slide_duration: f32,
-        game_over_duration: f32,
-    ) -> Self {
-        Self {
-            gravity: Timer::new(gravity_interval),
-            lock: Timer::new(lock_delay),
-            clear_animation: Timer::new(clear_duration),
-            slide_animation: Timer::new(slide_duration), // currently unused
-            game_over_animation: Timer::new(game_over_duration),
+    #[test]
+    fn test_grounded_lock_stack_versus_floor() {
+        // An O falling in columns 0-1, onto the floor or a stack one row high
+        let landed = |grounded_lock, stack: &str| {
+            let mut board = test_board(1.0);
+            board.grounded_lock = grounded_lock;
+            board.board.set_grid_ascii(stack);
+            let o_piece =
+                PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 0, y: 8 });
+            board.active_piece = Some(o_piece);
+            board.game_state = GameState::Falling;
+            for _ in 0..12 {
+                board.update(0.0, &[]);
+            }
+            board
+        };
+        let is_locking = |board: &BoardInstance| {
+            matches!(board.game_state, GameState::Locking { now: false, .. })
+        };
+
+        for grounded_lock in [GroundedLock::Contact, GroundedLock::Floor] {
+            let board = landed(grounded_lock, "");
+            assert!(is_locking(&board));
+            assert_eq!(lowest_cell_y(&board), 0);
         }
+
+        let board = landed(GroundedLock::Contact, "##........");
+        assert!(is_locking(&board));
+        assert_eq!(lowest_cell_y(&board), 1);
+
+        // Floor: the stack isn't ground, but gravity failing to move the piece still locks it
+        let board = landed(GroundedLock::Floor, "##........");
+        assert!(is_locking(&board));
+        assert_eq!(lowest_cell_y(&board), 1);
     }
 
-    pub fn pause_all(&mut self) {
-        self.gravity.pause();
-        self.lock.pause();
-        self.clear_animation.pause();
-        self.slide_animation.pause();
-        self.game_over_animation.pause();
+    #[test]
+    fn test_grounded_lock_floor_locks_on_stack_without_input() {
+        let mut board = test_board(0.0);
+        board.grounded_lock = GroundedLock::Floor;
+        board.board.set_grid_ascii("##........");
+        let o_piece = PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 0, y: 8 });
+        board.active_piece = Some(o_piece);
+        board.game_state = GameState::Falling;
+
+        // Eight rows down, a failed step, then the lock delay: all in gravity steps
+        let interval = board.gravity_interval();
+        let steps = 10 + (BoardConfig::default().lock_delay / interval).ceil() as usize;
+        for _ in 0..steps {
+            board.update(interval, &[]);
+        }
+        for (x, y) in [(0, 1), (1, 1), (0, 2), (1, 2)] {
+            assert!(board.board.is_cell_filled(BoardPosition { x, y }));
+        }
     }
 
-    pub fn resume_all(&mut self) {
-        self.gravity.resume();
-        self.lock.resume();
-        self.clear_animation.resume();
-        self.slide_animation.resume();
-        self.game_over_animation.resume();
+    #[test]
+    fn test_dig_depth_grows_with_deeper_clears() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            dig_rows: 4,
+            seed: Some(1),
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        assert_eq!(board.stack_height(), 4);
+        assert_eq!(board.board.row_score(3), Some(9));
+        assert_eq!(board.max_dig_depth(), 0);
+
+        // Dig out the top garbage row; a fresh one rises from below
+        board.set_state_for_test(
+            GameState::Clearing,
+            "
+            #########.
+            ##########
+            ",
+        );
+        run_until_ready(&mut board);
+        assert_eq!(board.max_dig_depth(), 1);
+        assert_eq!(board.score(), config.dig_points);
+        assert_eq!(board.stack_height(), 2);
+        assert_eq!(board.board.row_score(0), Some(9));
+
+        // Two rows deeper
+        board.set_state_for_test(
+            GameState::Clearing,
+            "
+            #########.
+            ##########
+            ##########
+            ",
+        );
+        run_until_ready(&mut board);
+        assert_eq!(board.max_dig_depth(), 3);
+        assert_eq!(board.score(), 3 * config.dig_points);
     }
 
-    pub fn reset_all(&mut self) {
-        self.gravity.reset();
-        self.lock.reset();
-        self.clear_animation.reset();
-        self.slide_animation.reset();
-        self.game_over_animation.reset();
+    #[test]
+    fn test_glowing_piece_passes_bloom_threshold() {
+        let mut config = BoardConfig::default();
+        config.glow.pieces = vec![PieceType::T];
+        let board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        let pos = BoardPosition { x: 0, y: 0 };
+        let threshold = config.glow.bloom_threshold;
+
+        let glowing = board.glow_color(PieceType::T.color(), Some(PieceType::T), pos);
+        assert!(luminance(glowing) > threshold);
+        let plain = board.glow_color(PieceType::S.color(), Some(PieceType::S), pos);
+        assert_eq!(plain, PieceType::S.color());
+        assert!(luminance(plain) < threshold);
     }
-}
 
-impl PartialEq for GameState {
-    fn eq(&self, other: &Self) -> bool {
-        use GameState::*;
+    #[test]
+    fn test_placement_overlay_for_o_piece() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            placement_overlay: true,
+            drill_piece: Some(PieceType::O),
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.update(0.0, &[]);
 
-        matches!(
-            (self, other),
-            (Ready, Ready)
-                | (Falling, Falling)
-                | (Clearing, Clearing)
-                | (GameOver, GameOver)
-                | (Paused, Paused)
-                | (Locking { .. }, Locking { .. })
-                | (Frozen, Frozen)
-        )
+        // One place per column pair the O can sit in, none leaving a hole
+        assert_eq!(board.overlay.len(), 9);
+        assert!(board.overlay.iter().all(|(_, hole)| !hole));
+
+        // Over a one-cell step, landing across it leaves a hole
+        board.board.set_grid_ascii("#.........");
+        board.refresh_overlay();
+        assert_eq!(board.overlay.iter().filter(|(_, hole)| *hole).count(), 1);
+
+        board.set_placement_overlay(false);
+        assert!(board.overlay.is_empty());
+    }
+
+    #[test]
+    fn test_wrap_x_moves_and_drops_across_edge() {
+        let config = BoardConfig {
+            width: 10,
+            height: 20,
+            wrap_x: true,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        board.board.set_grid_ascii(
+            "
+            .........#
+            .........#
+            .........#
+            ",
+        );
+        let o_piece = PieceInstance::new(PieceType::O, board.color, BoardPosition { x: 0, y: 10 });
+        board.active_piece = Some(o_piece);
+        board.game_state = GameState::Falling;
+
+        // Left past column 0: the O now spans the last and first columns
+        board.update(0.0, &[PlayerInput::L]);
+        let piece = board.active_piece.as_ref().unwrap();
+        assert_eq!(piece.position.x, 9);
+        let mut cols: Vec<isize> = piece
+            .absolute_cells()
+            .iter()
+            .map(|&cell| board.board.wrap(cell).x)
+            .collect();
+        cols.sort_unstable();
+        cols.dedup();
+        assert_eq!(cols, vec![0, 9]);
+
+        // Dropping lands it on the stack in the last column, and locks across the seam
+        hard_drop_and_lock(&mut board);
+        assert!(board.board.is_cell_filled(BoardPosition { x: 9, y: 3 }));
+        assert!(board.board.is_cell_filled(BoardPosition { x: 0, y: 3 }));
+        assert!(!board.board.is_cell_filled(BoardPosition { x: 0, y: 0 }));
+    }
+
+    #[test]
+    fn test_drill_piece_always_spawns() {
+        let config = BoardConfig {
+            drill_piece: Some(PieceType::S),
+            randomizer: Randomizer::SevenBag,
+            ..Default::default()
+        };
+        let mut board = BoardInstance::new("test", vec2(0.0, 0.0), &config);
+        for _ in 0..50 {
+            assert!(board.spawn_new_piece());
+            assert_eq!(board.active_piece.as_ref().unwrap().typ, PieceType::S);
+        }
+        assert_eq!(board.next_piece(), Some(PieceType::S));
+    }
+
+    #[test]
+    fn test_save_slots_restore_independently() {
+        let mut board = test_board(0.0);
+        let place = |board: &mut BoardInstance, typ, x| {
+            board.set_state_for_test(GameState::Falling, "");
+            let i_piece =
+                PieceInstance::new(PieceType::I, board.color, BoardPosition { x: 0, y: 0 });
+            board
+                .board
+                .commit_piece_at(&i_piece, BoardPosition { x, y: 0 });
+            board.active_piece = Some(PieceInstance::new(
+                typ,
+                board.color,
+                BoardPosition { x: 4, y: 10 },
+            ));
+        };
+
+        place(&mut board, PieceType::T, 0);
+        board.update(0.0, &[PlayerInput::Pause, PlayerInput::SaveSlot(1)]);
+        let first = board.board.state().clone();
+        board.update(0.0, &[PlayerInput::Pause]);
+
+        place(&mut board, PieceType::S, 6);
+        board.update(0.0, &[PlayerInput::Pause, PlayerInput::SaveSlot(2)]);
+        let second = board.board.state().clone();
+        assert_ne!(first, second);
+
+        board.update(0.0, &[PlayerInput::LoadSlot(1)]);
+        assert_eq!(board.board.state(), &first);
+        assert_eq!(board.active_piece.as_ref().unwrap().typ, PieceType::T);
+
+        board.update(0.0, &[PlayerInput::LoadSlot(2)]);
+        assert_eq!(board.board.state(), &second);
+        assert_eq!(board.active_piece.as_ref().unwrap().typ, PieceType::S);
+
+        // An empty slot changes nothing; unpausing plays the loaded piece
+        board.update(0.0, &[PlayerInput::LoadSlot(3), PlayerInput::Pause]);
+        assert_eq!(board.board.state(), &second);
+        assert_eq!(board.game_state, GameState::Falling);
+    }
+
+    #[test]
+    fn test_gravity_off_only_hard_drop_moves_down() {
+        let mut board = test_board(0.0);
+        board.set_gravity_enabled(false);
+        board.update(0.0, &[]);
+        let start_y = lowest_cell_y(&board);
+
+        let interval = BoardConfig::default().gravity_interval;
+        for _ in 0..10 {
+            board.update(interval, &[]);
+        }
+        assert_eq!(lowest_cell_y(&board), start_y);
+        assert_eq!(board.game_state, GameState::Falling);
+
+        board.update(0.0, &[PlayerInput::HardDrop]);
+        assert_eq!(lowest_cell_y(&board), 0);
+        assert!(matches!(board.game_state, GameState::Locking { .. }));
+    }
+
+    #[test]
+    fn test_20g_lands_piece_in_one_frame() {
+        let mut board = test_board(20.0);
+
+        // Spawn, then a single frame of gravity
+        board.update(0.0, &[]);
+        assert_eq!(board.game_state, GameState::Falling);
+        board.update(0.0, &[]);
+
+        assert_eq!(lowest_cell_y(&board), 0);
+        assert_eq!(
+            board.game_state,
+            GameState::Locking {
+                now: false,
+                hard_drop: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_fractional_gravity_accumulates() {
+        let mut board = test_board(0.25);
+
+        board.update(0.0, &[]);
+        let start_y = board.active_piece.as_ref().unwrap().position.y;
+
+        // Three frames at 0.25 cells/frame isn't a full row yet
+        for _ in 0..3 {
+            board.update(0.0, &[]);
+        }
+        assert_eq!(board.active_piece.as_ref().unwrap().position.y, start_y);
+
+        // Fourth frame completes the row
+        board.update(0.0, &[]);
+        assert_eq!(board.active_piece.as_ref().unwrap().position.y, start_y - 1);
     }
 }
@@ -0,0 +1,64 @@
+// src/views/cell_mesh.rs
+//
+// Batches a board's cells into a single colored mesh,
+// drawn with one call instead of one rect per cell
+
+use nannou::prelude::*;
+
+// Two triangles per cell, over its four corners
+const QUAD_INDICES: [usize; 6] = [0, 1, 2, 0, 2, 3];
+
+#[derive(Default)]
+pub struct CellMesh {
+    points: Vec<(Vec3, Rgba)>,
+    indices: Vec<usize>,
+}
+
+impl CellMesh {
+    pub fn with_capacity(cells: usize) -> Self {
+        Self {
+            points: Vec::with_capacity(cells * 4),
+            indices: Vec::with_capacity(cells * QUAD_INDICES.len()),
+        }
+    }
+
+    // Add a square cell centered at `center`
+    pub fn push_cell(&mut self, center: Vec2, size: f32, color: Rgba) {
+        let half = size * 0.5;
+        let base = self.points.len();
+        let corners = [
+            vec2(center.x - half, center.y - half),
+            vec2(center.x + half, center.y - half),
+            vec2(center.x + half, center.y + half),
+            vec2(center.x - half, center.y + half),
+        ];
+
+        self.points
+            .extend(corners.iter().map(|corner| (corner.extend(0.0), color)));
+        self.indices.extend(QUAD_INDICES.iter().map(|i| base + i));
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    // Center of each cell, in the order they were pushed
+    pub fn cell_centers(&self) -> impl Iterator<Item = Vec2> + '_ {
+        self.points.chunks(4).map(|quad| {
+            let (min, max) = (quad[0].0, quad[2].0);
+            vec2((min.x + max.x) * 0.5, (min.y + max.y) * 0.5)
+        })
+    }
+
+    pub fn draw(&self, draw: &Draw) {
+        if self.is_empty() {
+            return;
+        }
+        draw.mesh()
+            .indexed_colored(self.points.iter().copied(), self.indices.iter().copied());
+    }
+}
@@ -0,0 +1,285 @@
+// src/views/keymap.rs
+//
+// Which key plays which action, and the in-app rebinding mode that edits it.
+// Rebinds are saved as `action = Key` lines and loaded over the defaults.
+
+use crate::views::PlayerInput;
+use nannou::prelude::*;
+use std::{collections::HashMap, fs, io, path::Path};
+
+// Actions that can be rebound, in the order bind mode steps through them.
+// Save/load slots stay on the number keys.
+pub const REBINDABLE: [PlayerInput; 9] = [
+    PlayerInput::L,
+    PlayerInput::R,
+    PlayerInput::Rotate,
+    PlayerInput::SoftDrop,
+    PlayerInput::HardDrop,
+    PlayerInput::Hold,
+    PlayerInput::ConfirmClear,
+    PlayerInput::Flip,
+    PlayerInput::Pause,
+];
+
+// Keys an action can be bound to; anything else is ignored in bind mode.
+// G and P are left out: they stay the app's spawn-boards and debug hotkeys.
+const BINDABLE_KEYS: [Key; 33] = [
+    Key::Left,
+    Key::Right,
+    Key::Up,
+    Key::Down,
+    Key::Space,
+    Key::Return,
+    Key::LShift,
+    Key::RShift,
+    Key::Back,
+    Key::A,
+    Key::B,
+    Key::C,
+    Key::D,
+    Key::E,
+    Key::F,
+    Key::H,
+    Key::I,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::M,
+    Key::N,
+    Key::O,
+    Key::Q,
+    Key::R,
+    Key::S,
+    Key::T,
+    Key::U,
+    Key::V,
+    Key::W,
+    Key::X,
+    Key::Y,
+    Key::Z,
+];
+
+// Name of an action in the saved keybinds
+pub fn action_name(input: PlayerInput) -> &'static str {
+    match input {
+        PlayerInput::L => "left",
+        PlayerInput::R => "right",
+        PlayerInput::Rotate => "rotate",
+        PlayerInput::SoftDrop => "soft_drop",
+        PlayerInput::HardDrop => "hard_drop",
+        PlayerInput::Hold => "hold",
+        PlayerInput::ConfirmClear => "confirm_clear",
+        PlayerInput::Flip => "flip",
+        PlayerInput::Pause => "pause",
+        PlayerInput::SaveSlot(_) => "save_slot",
+        PlayerInput::LoadSlot(_) => "load_slot",
+    }
+}
+
+fn key_name(key: Key) -> String {
+    format!("{:?}", key)
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+    BINDABLE_KEYS.into_iter().find(|&key| key_name(key) == name)
+}
+
+fn parse_action(name: &str) -> Option<PlayerInput> {
+    REBINDABLE
+        .into_iter()
+        .find(|&input| action_name(input) == name)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: HashMap<Key, PlayerInput>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = [
+            (Key::Left, PlayerInput::L),
+            (Key::Right, PlayerInput::R),
+            (Key::Up, PlayerInput::Rotate),
+            (Key::Down, PlayerInput::SoftDrop),
+            (Key::C, PlayerInput::Hold),
+            (Key::X, PlayerInput::ConfirmClear),
+            (Key::F, PlayerInput::Flip),
+            (Key::Space, PlayerInput::HardDrop),
+            (Key::Return, PlayerInput::Pause),
+        ];
+        Self {
+            bindings: bindings.into_iter().collect(),
+        }
+    }
+}
+
+impl Keymap {
+    pub fn input(&self, key: Key) -> Option<PlayerInput> {
+        self.bindings.get(&key).copied()
+    }
+
+    pub fn key_for(&self, input: PlayerInput) -> Option<Key> {
+        self.bindings
+            .iter()
+            .find(|&(_, &bound)| bound == input)
+            .map(|(&key, _)| key)
+    }
+
+    // Make `key` the one key for `input`. Returns the action the key was taken
+    // from, if it was bound to a different one.
+    pub fn bind(&mut self, key: Key, input: PlayerInput) -> Option<PlayerInput> {
+        self.bindings.retain(|_, &mut bound| bound != input);
+        self.bindings
+            .insert(key, input)
+            .filter(|&previous| previous != input)
+    }
+
+    // Defaults with the `action = Key` lines of a keybinds file applied.
+    // Unknown actions or keys are skipped with a warning.
+    pub fn parse(text: &str) -> Self {
+        let mut keymap = Self::default();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parsed = line.split_once('=').and_then(|(action, key)| {
+                Some((
+                    parse_action(action.trim())?,
+                    parse_key(key.trim().trim_matches('"'))?,
+                ))
+            });
+            match parsed {
+                Some((input, key)) => {
+                    keymap.bind(key, input);
+                }
+                None => println!("Keybinds: ignoring <{}>", line),
+            }
+        }
+        keymap
+    }
+
+    pub fn to_text(&self) -> String {
+        REBINDABLE
+            .into_iter()
+            .filter_map(|input| {
+                let key = self.key_for(input)?;
+                Some(format!("{} = \"{}\"\n", action_name(input), key_name(key)))
+            })
+            .collect()
+    }
+
+    // Defaults when there's no keybinds file yet
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+}
+
+// Bind mode: steps through REBINDABLE, binding the selected action to the
+// next key pressed. Tab skips an action.
+#[derive(Debug, Default)]
+pub struct Rebinder {
+    selected: Option<usize>, // index into REBINDABLE, None when not binding
+}
+
+impl Rebinder {
+    pub fn toggle(&mut self) {
+        self.selected = match self.selected {
+            Some(_) => None,
+            None => Some(0),
+        };
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.selected.is_some()
+    }
+
+    pub fn selected(&self) -> Option<PlayerInput> {
+        self.selected.map(|idx| REBINDABLE[idx])
+    }
+
+    // Handle a key while binding. Returns true if the keymap changed.
+    pub fn key_pressed(&mut self, key: Key, keymap: &mut Keymap) -> bool {
+        let Some(idx) = self.selected else {
+            return false;
+        };
+        let next = Some((idx + 1) % REBINDABLE.len());
+        if key == Key::Tab {
+            self.selected = next;
+            return false;
+        }
+        if !BINDABLE_KEYS.contains(&key) {
+            return false;
+        }
+
+        let input = REBINDABLE[idx];
+        if let Some(previous) = keymap.bind(key, input) {
+            println!(
+                "Warning: {:?} moved from {} to {}; {} is now unbound",
+                key,
+                action_name(previous),
+                action_name(input),
+                action_name(previous)
+            );
+        }
+        self.selected = next;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebind_selected_action() {
+        let mut keymap = Keymap::default();
+        let mut rebinder = Rebinder::default();
+        assert!(!rebinder.key_pressed(Key::A, &mut keymap));
+
+        // Tab from Left to Right, then bind Right to D
+        rebinder.toggle();
+        assert_eq!(rebinder.selected(), Some(PlayerInput::L));
+        rebinder.key_pressed(Key::Tab, &mut keymap);
+        assert_eq!(rebinder.selected(), Some(PlayerInput::R));
+        assert!(rebinder.key_pressed(Key::D, &mut keymap));
+        assert_eq!(keymap.input(Key::D), Some(PlayerInput::R));
+        assert_eq!(keymap.input(Key::Right), None);
+
+        // Rotate takes Space from hard drop, which is left unbound
+        assert_eq!(rebinder.selected(), Some(PlayerInput::Rotate));
+        assert!(rebinder.key_pressed(Key::Space, &mut keymap));
+        assert_eq!(keymap.input(Key::Space), Some(PlayerInput::Rotate));
+        assert_eq!(keymap.key_for(PlayerInput::HardDrop), None);
+        assert_eq!(keymap.input(Key::Up), None);
+
+        // Saved and read back unchanged
+        assert_eq!(Keymap::parse(&keymap.to_text()), keymap);
+    }
+
+    #[test]
+    fn test_app_hotkeys_not_bindable() {
+        let mut keymap = Keymap::default();
+        let mut rebinder = Rebinder::default();
+        rebinder.toggle();
+        for key in [Key::G, Key::P] {
+            assert!(!rebinder.key_pressed(key, &mut keymap));
+            assert_eq!(keymap.input(key), None);
+        }
+        assert_eq!(rebinder.selected(), Some(PlayerInput::L));
+
+        // A keybinds file can't take them either
+        assert_eq!(
+            Keymap::parse("hold = \"G\"\npause = \"P\""),
+            Keymap::default()
+        );
+    }
+}
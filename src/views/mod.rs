@@ -2,8 +2,21 @@
 
 pub mod background;
 pub mod board_instance;
+pub mod cell_mesh;
+pub mod keymap;
+pub mod orientation;
+pub mod palette;
 pub mod piece_instance;
+pub mod preview;
 
 pub use background::BackgroundManager;
-pub use board_instance::{BoardInstance, PlayerInput};
+pub use board_instance::{
+    accept_key_repeat, apply_input_combos, resolve_opposite_inputs, settle_score_race,
+    sync_clear_pause, BoardInstance, EventHandler, GameState, PlayerInput, SoundCue,
+};
+pub use cell_mesh::CellMesh;
+pub use keymap::{action_name, Keymap, Rebinder};
+pub use orientation::Orientation;
+pub use palette::Palette;
 pub use piece_instance::{BoardPosition, PieceInstance, RotationDirection};
+pub use preview::PreviewLayout;
@@ -0,0 +1,135 @@
+// src/views/orientation.rs
+//
+// Presentation-only board rotation. The game logic always runs upright;
+// drawing is rotated about the board's center and directional inputs,
+// which arrive in screen directions, are turned back into board directions.
+
+use crate::views::PlayerInput;
+use nannou::prelude::*;
+
+// Clockwise turns of the board on screen
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Orientation {
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+impl Orientation {
+    // Nearest quarter turn at or below the given clockwise angle
+    pub fn from_degrees(degrees: u32) -> Self {
+        match (degrees / 90) % 4 {
+            1 => Orientation::Deg90,
+            2 => Orientation::Deg180,
+            3 => Orientation::Deg270,
+            _ => Orientation::Deg0,
+        }
+    }
+
+    pub fn radians(&self) -> f32 {
+        match self {
+            Orientation::Deg0 => 0.0,
+            Orientation::Deg90 => PI * 0.5,
+            Orientation::Deg180 => PI,
+            Orientation::Deg270 => PI * 1.5,
+        }
+    }
+
+    // Where an upright screen point ends up once the board is turned about center
+    pub fn rotate_point(&self, point: Vec2, center: Vec2) -> Vec2 {
+        let d = point - center;
+        let turned = match self {
+            Orientation::Deg0 => d,
+            Orientation::Deg90 => vec2(d.y, -d.x),
+            Orientation::Deg180 => -d,
+            Orientation::Deg270 => vec2(-d.y, d.x),
+        };
+        center + turned
+    }
+
+    // A Draw that applies rotate_point to everything drawn with it
+    pub fn transform(&self, draw: &Draw, center: Vec2) -> Draw {
+        if *self == Orientation::Deg0 {
+            return draw.clone();
+        }
+        draw.xy(center).rotate(-self.radians()).xy(-center)
+    }
+
+    // Turn a screen-direction input into the board direction that appears that way.
+    // Turned a quarter, one key points up the board, which has no move; it takes
+    // the board direction no other key reaches, so every direction stays pressable.
+    pub fn remap_input(&self, input: PlayerInput) -> PlayerInput {
+        const KEYS: [PlayerInput; 3] = [PlayerInput::L, PlayerInput::R, PlayerInput::SoftDrop];
+        if !KEYS.contains(&input) {
+            return input;
+        }
+        self.board_direction(input).unwrap_or_else(|| {
+            let reached = KEYS.map(|key| self.board_direction(key));
+            KEYS.into_iter()
+                .find(|&dir| !reached.contains(&Some(dir)))
+                .unwrap_or(input)
+        })
+    }
+
+    // The board direction a screen-direction input points, None for up the board
+    fn board_direction(&self, input: PlayerInput) -> Option<PlayerInput> {
+        let (x, y) = match input {
+            PlayerInput::L => (-1, 0),
+            PlayerInput::R => (1, 0),
+            PlayerInput::SoftDrop => (0, -1),
+            other => return Some(other),
+        };
+
+        // undo the clockwise turn
+        let board_dir = match self {
+            Orientation::Deg0 => (x, y),
+            Orientation::Deg90 => (-y, x),
+            Orientation::Deg180 => (-x, -y),
+            Orientation::Deg270 => (y, -x),
+        };
+
+        match board_dir {
+            (-1, 0) => Some(PlayerInput::L),
+            (1, 0) => Some(PlayerInput::R),
+            (0, -1) => Some(PlayerInput::SoftDrop),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_board_direction_reachable() {
+        let keys = [PlayerInput::L, PlayerInput::R, PlayerInput::SoftDrop];
+        for orientation in [
+            Orientation::Deg0,
+            Orientation::Deg90,
+            Orientation::Deg180,
+            Orientation::Deg270,
+        ] {
+            let mapped = keys.map(|key| orientation.remap_input(key));
+            for dir in keys {
+                assert!(
+                    mapped.contains(&dir),
+                    "{:?} can't reach {:?}",
+                    orientation,
+                    dir
+                );
+            }
+        }
+
+        // Turned a quarter clockwise, board down shows as screen left
+        assert_eq!(
+            Orientation::Deg90.remap_input(PlayerInput::L),
+            PlayerInput::SoftDrop
+        );
+        assert_eq!(
+            Orientation::Deg90.remap_input(PlayerInput::R),
+            PlayerInput::L
+        );
+    }
+}
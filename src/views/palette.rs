@@ -0,0 +1,44 @@
+// src/views/palette.rs
+//
+// A board's colors as one value, so they can be read and changed together
+
+use nannou::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Palette {
+    pub cell: Rgba,          // stack and piece cells
+    pub boundary: Rgba,      // outer boundary, before any next-piece tint
+    pub panel: Option<Rgba>, // fill behind the playfield, None when off
+}
+
+impl Palette {
+    // The stock board look around a hue (0.0 - 1.0)
+    pub fn from_hue(hue: f32, panel: Option<Rgba>) -> Self {
+        Self {
+            cell: hsva(hue, 1.0, 0.7, 1.0).into(),
+            boundary: hsva(hue, 1.0, 0.75, 1.0).into(),
+            panel,
+        }
+    }
+
+    // Partway from self to another palette. A panel only turned on or off snaps.
+    pub fn blend(&self, to: &Palette, t: f32) -> Palette {
+        Palette {
+            cell: lerp_rgba(self.cell, to.cell, t),
+            boundary: lerp_rgba(self.boundary, to.boundary, t),
+            panel: match (self.panel, to.panel) {
+                (Some(from), Some(to)) => Some(lerp_rgba(from, to, t)),
+                (_, to) => to,
+            },
+        }
+    }
+}
+
+fn lerp_rgba(from: Rgba, to: Rgba, t: f32) -> Rgba {
+    rgba(
+        from.red + (to.red - from.red) * t,
+        from.green + (to.green - from.green) * t,
+        from.blue + (to.blue - from.blue) * t,
+        from.alpha + (to.alpha - from.alpha) * t,
+    )
+}
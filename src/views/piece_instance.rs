@@ -12,7 +12,7 @@ pub enum RotationDirection {
 }
 
 // Board position of a piece
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct BoardPosition {
     pub x: isize,
     pub y: isize,
@@ -55,6 +55,19 @@ impl PieceInstance {
         self.typ.get_rotation(self.rot_idx)
     }
 
+    // Board positions the piece covers where it is
+    pub fn absolute_cells(&self) -> [BoardPosition; 4] {
+        self.cells_at(self.position)
+    }
+
+    // Board positions the piece would cover with its origin at `position`
+    pub fn cells_at(&self, position: BoardPosition) -> [BoardPosition; 4] {
+        self.cells().map(|(dx, dy)| BoardPosition {
+            x: position.x + dx,
+            y: position.y + dy,
+        })
+    }
+
     // Look up the rotation from the piece type table
     pub fn rotate(&mut self, direction: &RotationDirection) -> &Cells {
         let count = self.typ.rotation_count();
@@ -68,3 +81,41 @@ impl PieceInstance {
         self.typ.get_rotation(inx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absolute_cells() {
+        let mut piece = PieceInstance::new(
+            PieceType::T,
+            rgba(1.0, 1.0, 1.0, 1.0),
+            BoardPosition { x: 3, y: 7 },
+        );
+        piece.rot_idx = 1;
+
+        let manual: Vec<BoardPosition> = piece
+            .cells()
+            .iter()
+            .map(|&(dx, dy)| BoardPosition {
+                x: piece.position.x + dx,
+                y: piece.position.y + dy,
+            })
+            .collect();
+        assert_eq!(piece.absolute_cells().to_vec(), manual);
+        assert_eq!(
+            piece.absolute_cells(),
+            [
+                BoardPosition { x: 4, y: 7 },
+                BoardPosition { x: 4, y: 8 },
+                BoardPosition { x: 4, y: 9 },
+                BoardPosition { x: 5, y: 8 },
+            ]
+        );
+        assert_eq!(
+            piece.cells_at(BoardPosition { x: 0, y: 0 })[3],
+            BoardPosition { x: 2, y: 1 }
+        );
+    }
+}
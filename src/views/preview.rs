@@ -0,0 +1,197 @@
+// src/views/preview.rs
+//
+// Layout of the next-piece preview box beside a board
+
+use crate::{
+    config::{PreviewConfig, PreviewSide},
+    models::PieceType,
+};
+use nannou::prelude::*;
+
+// preview box width in mini-cells, wide enough for any piece
+const BOX_CELLS: f32 = 4.0;
+
+#[derive(Debug, Copy, Clone)]
+pub struct PreviewLayout {
+    pub side: PreviewSide,
+    pub spacing: f32,   // gap between board edge and box, in screen pts
+    pub mini_cell: f32, // preview cell size in screen pts
+    pub background: Rgba,
+    pub true_colors: bool, // piece colors instead of the board's
+    pub count: usize,      // upcoming pieces listed
+    pub list_gap: f32,     // gap between listed boxes, in screen pts
+}
+
+impl PreviewLayout {
+    // Resolve the config against a board's cell size so every board scales alike
+    pub fn new(config: &PreviewConfig, cell_size: f32) -> Self {
+        let [r, g, b, a] = config.background;
+        Self {
+            side: config.side,
+            spacing: config.spacing * cell_size,
+            mini_cell: config.cell_scale * cell_size,
+            background: rgba(r, g, b, a),
+            true_colors: config.true_colors,
+            count: config.count.max(1),
+            list_gap: config.list_gap * cell_size,
+        }
+    }
+
+    // Box side length: the piece area plus half a mini-cell of padding all round
+    pub fn box_size(&self) -> f32 {
+        (BOX_CELLS + 1.0) * self.mini_cell
+    }
+
+    // Center of the box for a board centered at board_center.
+    // Side boxes line up with the board's top edge.
+    pub fn box_center(&self, board_center: Vec2, board_width: f32, board_height: f32) -> Vec2 {
+        let half_box = self.box_size() * 0.5;
+        let offset = self.spacing + half_box;
+        let top_aligned = board_center.y + board_height * 0.5 - half_box;
+
+        match self.side {
+            PreviewSide::Left => vec2(board_center.x - board_width * 0.5 - offset, top_aligned),
+            PreviewSide::Right => vec2(board_center.x + board_width * 0.5 + offset, top_aligned),
+            PreviewSide::Top => vec2(board_center.x, board_center.y + board_height * 0.5 + offset),
+        }
+    }
+
+    // Color of a piece's mini-cells, given the board's piece color
+    pub fn cell_color(&self, piece_type: PieceType, board_color: Rgba) -> Rgba {
+        if self.true_colors {
+            piece_type.color()
+        } else {
+            board_color
+        }
+    }
+
+    // Same layout on the other side of the board, used for the hold box
+    pub fn mirrored(&self) -> Self {
+        let side = match self.side {
+            PreviewSide::Left => PreviewSide::Right,
+            PreviewSide::Right | PreviewSide::Top => PreviewSide::Left,
+        };
+        Self { side, ..*self }
+    }
+
+    // Center of the nth box in a column of boxes stacked downward from the first
+    pub fn slot_center(&self, first_center: Vec2, slot: usize) -> Vec2 {
+        let step = self.box_size() + self.mini_cell * 0.5;
+        first_center - vec2(0.0, step * slot as f32)
+    }
+
+    // Center of the nth upcoming piece's box, the next piece (index 0) at
+    // first_center. Side lists run downward; a list above the board runs
+    // left to right so it stays clear of the playfield.
+    pub fn list_center(&self, first_center: Vec2, index: usize) -> Vec2 {
+        let step = (self.box_size() + self.list_gap) * index as f32;
+        match self.side {
+            PreviewSide::Left | PreviewSide::Right => first_center - vec2(0.0, step),
+            PreviewSide::Top => first_center + vec2(step, 0.0),
+        }
+    }
+
+    // Screen positions of a piece's mini-cells, centered in the box
+    pub fn cell_positions(&self, piece_type: PieceType, box_center: Vec2) -> Vec<Vec2> {
+        let cells = piece_type.get_rotation(0);
+        let (min_x, max_x) = piece_type.minmax_x(0);
+        let min_y = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+        let max_y = piece_type.max_y(0);
+
+        let mid_x = (min_x + max_x) as f32 * 0.5;
+        let mid_y = (min_y + max_y) as f32 * 0.5;
+
+        cells
+            .iter()
+            .map(|&(x, y)| {
+                box_center
+                    + vec2(
+                        (x as f32 - mid_x) * self.mini_cell,
+                        (y as f32 - mid_y) * self.mini_cell,
+                    )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_cell_positions() {
+        let config = PreviewConfig {
+            enabled: true,
+            side: PreviewSide::Right,
+            spacing: 1.0,
+            cell_scale: 0.5,
+            ..Default::default()
+        };
+        let layout = PreviewLayout::new(&config, 30.0);
+        assert_eq!(layout.mini_cell, 15.0);
+        assert_eq!(layout.box_size(), 75.0);
+
+        // 300 x 600 board at the origin: box sits 30 pts right of the edge, top aligned
+        let center = layout.box_center(vec2(0.0, 0.0), 300.0, 600.0);
+        assert_eq!(center, vec2(217.5, 262.5));
+
+        let cells = layout.cell_positions(PieceType::O, center);
+        assert_eq!(
+            cells,
+            vec![
+                vec2(210.0, 255.0),
+                vec2(225.0, 255.0),
+                vec2(210.0, 270.0),
+                vec2(225.0, 270.0),
+            ]
+        );
+
+        // Same layout above the board
+        let top = PreviewLayout {
+            side: PreviewSide::Top,
+            ..layout
+        };
+        assert_eq!(
+            top.box_center(vec2(0.0, 0.0), 300.0, 600.0),
+            vec2(0.0, 367.5)
+        );
+    }
+
+    #[test]
+    fn test_preview_list_centers() {
+        let config = PreviewConfig {
+            cell_scale: 0.5,
+            count: 5,
+            list_gap: 0.5,
+            ..Default::default()
+        };
+        let layout = PreviewLayout::new(&config, 20.0);
+        let first = vec2(100.0, 50.0);
+        assert_eq!(layout.list_center(first, 0), first);
+        // a 50 pt box plus a 10 pt gap per step
+        assert_eq!(layout.list_center(first, 3), vec2(100.0, -130.0));
+
+        let top = PreviewLayout {
+            side: PreviewSide::Top,
+            ..layout
+        };
+        assert_eq!(top.list_center(first, 1), vec2(160.0, 50.0));
+    }
+
+    #[test]
+    fn test_preview_cell_color_mode() {
+        let board_color = rgba(0.5, 0.5, 0.5, 1.0);
+        let uniform = PreviewLayout::new(&PreviewConfig::default(), 30.0);
+        assert_eq!(uniform.cell_color(PieceType::T, board_color), board_color);
+
+        let config = PreviewConfig {
+            true_colors: true,
+            ..Default::default()
+        };
+        let colored = PreviewLayout::new(&config, 30.0);
+        assert_eq!(
+            colored.cell_color(PieceType::T, board_color),
+            PieceType::T.color()
+        );
+    }
+}